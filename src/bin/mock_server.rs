@@ -0,0 +1,11 @@
+//! Development server: `cargo run --features mock_server --bin mock_server -- [address]`.
+
+use client::{network::mock_server::MockServer, DEFAULT_SERVER_ADDRESS};
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let address = std::env::args().nth(1).unwrap_or_else(|| DEFAULT_SERVER_ADDRESS.to_string());
+    let server = MockServer::bind(&address).await?;
+    println!("Mock chess server listening on {}", server.local_addr()?);
+    server.run().await
+}
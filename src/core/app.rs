@@ -53,12 +53,14 @@ impl ChessClientApp {
             .init_resource::<CameraController>()
             .init_resource::<NetworkState>()
             .init_resource::<AudioSettings>()
+            .init_resource::<InputSettings>()
             .init_resource::<PerformanceStats>();
 
         // イベント
         app.add_event::<PieceSelectedEvent>()
             .add_event::<MovePieceEvent>()
             .add_event::<SendNetworkMessageEvent>()
+            .add_event::<SendClientRequestEvent>()
             .add_event::<NetworkResponseEvent>()
             .add_event::<UIStateChangeEvent>()
             .add_event::<CameraControlEvent>()
@@ -4,6 +4,15 @@ use bevy::prelude::*;
 pub const BOARD_SIZE: f32 = 8.0;
 pub const SQUARE_SIZE: f32 = 1.0;
 pub const BOARD_THICKNESS: f32 = 0.2;
+pub const BOARD_FRAME_WIDTH: f32 = 0.6;
+pub const GRAVEYARD_OFFSET: f32 = SQUARE_SIZE * 4.0 + BOARD_FRAME_WIDTH + 0.6;
+pub const GRAVEYARD_SPACING: f32 = 0.8;
+pub const GRAVEYARD_PIECE_SCALE: f32 = 0.7;
+pub const GRAVEYARD_MOVE_DURATION: f32 = 0.4;
+pub const BOARD_FRAME_TOP: f32 = BOARD_THICKNESS + 0.04;
+pub const COORDINATE_LABEL_SIZE: f32 = 0.28;
+pub const COORDINATE_LABEL_STROKE: f32 = 0.045;
+pub const COORDINATE_LABEL_DEPTH: f32 = 0.01;
 
 // Piece
 pub const PAWN_HEIGHT: f32 = 1.0;
@@ -20,7 +29,13 @@ pub const PIECE_BASE_SCALE: f32 = 0.8;
 pub const PIECE_MOVE_DURATION: f32 = 0.5;
 pub const PIECE_CAPTURE_DURATION: f32 = 0.3;
 pub const PIECE_HOVER_HEIGHT: f32 = 0.2;
+pub const PIECE_PROMOTION_DURATION: f32 = 0.6;
+pub const PIECE_LIFT_HEIGHT: f32 = 0.35;
+pub const KNIGHT_ARC_HEIGHT: f32 = 1.2;
+pub const CAPTURE_KNOCK_DISTANCE: f32 = 0.8;
 pub const BOARD_FLIP_DURATION: f32 = 1.0;
+/// Floor for `GameSettings::animation_speed`; instant play is `instant_moves`.
+pub const MIN_ANIMATION_SPEED: f32 = 0.01;
 pub const CAMERA_MOVE_DURATION: f32 = 1.5;
 pub const UI_FADE_DURATION: f32 = 0.25;
 
@@ -38,12 +53,23 @@ pub const CAMERA_SMOOTH_FACTOR: f32 = 5.0;
 pub const AMBIENT_LIGHT_STRENGTH: f32 = 0.3;
 pub const DIRECTIONAL_LIGHT_STRENGTH: f32 = 0.8;
 pub const DIRECTIONAL_LIGHT_ANGLE: f32 = -45.0;
+/// Lighting strengths above are relative; these convert them to Bevy units.
+pub const AMBIENT_BRIGHTNESS_SCALE: f32 = 500.0;
+pub const DIRECTIONAL_ILLUMINANCE_SCALE: f32 = 10000.0;
+pub const ENVIRONMENT_MAP_INTENSITY_SCALE: f32 = 1500.0;
 
 // Material
 pub const WHITE_PIECE_COLOR: Color = Color::srgb(0.95, 0.95, 0.92);
 pub const BLACK_PIECE_COLOR: Color = Color::srgb(0.15, 0.15, 0.15);
 pub const LIGHT_SQUARE_COLOR: Color = Color::srgb(0.93, 0.85, 0.73);
 pub const DARK_SQUARE_COLOR: Color = Color::srgb(0.65, 0.42, 0.24);
+pub const BOARD_FRAME_COLOR: Color = Color::srgb(0.36, 0.22, 0.12);
+pub const COORDINATE_LABEL_COLOR: Color = Color::srgb(0.93, 0.85, 0.73);
+
+pub const DEFAULT_THEME: &str = "classic";
+pub const PROCEDURAL_TEXTURE_SIZE: u32 = 512;
+/// Default for `GameSettings::texture_seed`.
+pub const PROCEDURAL_TEXTURE_SEED: u32 = 0x5EED;
 
 // Highlight Color
 pub const SELECTED_COLOR: Color = Color::srgb(0.2, 0.8, 0.2);
@@ -52,12 +78,31 @@ pub const LAST_MOVE_COLOR: Color = Color::srgba(0.8, 0.8, 0.2, 0.5);
 pub const CHECK_COLOR: Color = Color::srgb(0.9, 0.1, 0.1);
 pub const CAPTURE_COLOR: Color = Color::srgba(0.9, 0.3, 0.1, 0.8);
 pub const THREAT_COLOR: Color = Color::srgba(0.9, 0.6, 0.1, 0.6);
+pub const WHITE_CONTROL_COLOR: Color = Color::srgba(0.3, 0.6, 1.0, 0.25);
+pub const BLACK_CONTROL_COLOR: Color = Color::srgba(0.9, 0.2, 0.3, 0.25);
+pub const CONTESTED_COLOR: Color = Color::srgba(0.7, 0.4, 0.8, 0.25);
+pub const PIN_COLOR: Color = Color::srgb(0.8, 0.2, 0.9);
+
+// Threat overlay
+pub const THREAT_OVERLAY_HEIGHT: f32 = BOARD_THICKNESS + 0.012;
+pub const THREAT_PIP_RADIUS: f32 = 0.06;
+pub const THREAT_PIP_SPACING: f32 = 0.15;
+pub const MAX_THREAT_PIPS: u8 = 5;
+pub const PIN_LINE_WIDTH: f32 = 0.05;
 
 // Effect
 pub const PARTICLE_COUNT: u32 = 50;
+pub const MAX_PARTICLES: usize = 512;
 pub const EXPLOSION_DURATION: f32 = 1.0;
 pub const SPARKLE_DURATION: f32 = 2.0;
 pub const GLOW_INTENSITY: f32 = 1.5;
+pub const PULSE_FREQUENCY: f32 = 1.5;
+/// Checked king and its square pulse at the same rate.
+pub const CHECK_PULSE_FREQUENCY: f32 = 0.5;
+pub const OUTLINE_COLOR: Color = Color::srgb(1.0, 0.85, 0.3);
+pub const OUTLINE_THICKNESS: f32 = 0.06;
+pub const BLOB_SHADOW_OPACITY: f32 = 0.55;
+pub const BLOB_SHADOW_SIZE: f32 = PIECE_RADIUS * 2.6;
 
 // UI
 pub const UI_FONT_SIZE_SMALL: f32 = 14.0;
@@ -72,6 +117,16 @@ pub const UI_MARGIN_LARGE: f32 = 16.0;
 pub const UI_BUTTON_HEIGHT: f32 = 40.0;
 pub const UI_PANEL_BORDER_RADIUS: f32 = 8.0;
 
+pub const LOBBY_REFRESH_INTERVAL_SECONDS: f32 = 5.0;
+pub const MAX_GAME_ID_LENGTH: usize = 64;
+pub const MAX_PASSWORD_LENGTH: usize = 32;
+
+pub const CHAT_PANEL_WIDTH: f32 = 380.0;
+pub const CHAT_PANEL_HEIGHT: f32 = 320.0;
+pub const CHAT_VISIBLE_MESSAGES: usize = 12;
+pub const MAX_CHAT_HISTORY: usize = 200;
+pub const MAX_CHAT_MESSAGE_LENGTH: usize = 200;
+
 // UI Color
 pub const UI_BACKGROUND_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.9);
 pub const UI_PANEL_COLOR: Color = Color::srgba(0.2, 0.2, 0.2, 0.95);
@@ -89,8 +144,20 @@ pub const UI_ERROR_COLOR: Color = Color::srgb(0.9, 0.2, 0.2);
 pub const DEFAULT_SERVER_ADDRESS: &str = "127.0.0.1:8080";
 pub const CONNECTION_TIMEOUT_SECONDS: f32 = 10.0;
 pub const RECONNECT_ATTEMPTS: u32 = 3;
+pub const RECONNECT_BASE_DELAY_SECONDS: f32 = 1.0;
+pub const RECONNECT_MAX_DELAY_SECONDS: f32 = 16.0;
 pub const PING_INTERVAL_SECONDS: f32 = 30.0;
 pub const NETWORK_BUFFER_SIZE: usize = 8192;
+pub const MAX_FRAME_SIZE: usize = 1024 * 1024;
+/// Capacity of each channel between the network task and Bevy.
+pub const NETWORK_CHANNEL_CAPACITY: usize = 64;
+pub const MAX_INBOUND_MESSAGES_PER_FRAME: usize = 32;
+/// How long to wait for a requested game snapshot before asking again.
+pub const RESYNC_TIMEOUT_SECONDS: f32 = 5.0;
+/// Minimum time between two draw offers, or two undo requests, from us.
+pub const OFFER_COOLDOWN_SECONDS: f32 = 30.0;
+/// Clock differences from the server above this are logged as drift.
+pub const CLOCK_DRIFT_TOLERANCE_SECONDS: f32 = 0.5;
 
 // Audio
 pub const DEFAULT_MASTER_VOLUME: f32 = 0.8;
@@ -130,9 +197,11 @@ pub const LOG_FILE_PATH: &str = "logs/application.log";
 
 // Asset path
 pub mod asset_paths {
+    pub const ASSETS_DIR: &str = "assets/";
     pub const FONTS_DIR: &str = "fonts/";
     pub const TEXTURES_DIR: &str = "textures/";
     pub const MODELS_DIR: &str = "models/";
+    pub const THEMES_DIR: &str = "themes/";
     pub const SOUNDS_DIR: &str = "sounds/";
     pub const MUSIC_DIR: &str = "music/";
     pub const SHADERS_DIR: &str = "shaders/";
@@ -145,6 +214,17 @@ pub mod asset_paths {
     pub const WOOD_TEXTURE: &str = "textures/wood.png";
     pub const MARBLE_TEXTURE: &str = "textures/marble.png";
     pub const METAL_TEXTURE: &str = "textures/metal.png";
+    pub const GENERATED_TEXTURES_DIR: &str = "textures/generated/";
+    pub const ENVIRONMENT_DIR: &str = "environment/";
+
+    // Models
+    pub const PIECE_SET_MANIFEST: &str = "manifest.json";
+
+    /// Where `path` (relative to the asset folder) is on disk, found the way `AssetPlugin`
+    /// finds it: under `BEVY_ASSET_ROOT`, `CARGO_MANIFEST_DIR` or the executable's directory.
+    pub fn on_disk(path: &str) -> std::path::PathBuf {
+        bevy::asset::io::file::FileAssetReader::get_base_path().join(ASSETS_DIR).join(path)
+    }
 
     // Sounds
     pub const PIECE_MOVE_SOUND: &str = "sounds/piece_move.ogg";
@@ -184,6 +264,30 @@ pub mod physics {
     pub const PIECE_MASS: f32 = 1.0;
     pub const BOARD_FRICTION: f32 = 0.7;
     pub const PIECE_RESTITUTION: f32 = 0.3;
+
+    pub const PHYSICS_TIMESTEP: f32 = 1.0 / 120.0;
+    /// Steps per frame before the simulation starts running slow instead of catching up.
+    pub const MAX_PHYSICS_STEPS: u32 = 8;
+    pub const MAX_PHYSICS_BODIES: usize = 8;
+    pub const MAX_BODY_LIFETIME: f32 = 4.0;
+    pub const CONTACT_DAMPING: f32 = 3.0;
+    pub const BOUNCE_THRESHOLD: f32 = 0.5;
+    pub const CONTACT_ITERATIONS: u32 = 4;
+    /// Points this close above the surface still count as touching.
+    pub const CONTACT_SLOP: f32 = 0.01;
+
+    /// Heights a piece base rests at; pieces stand at half the board thickness.
+    pub const BOARD_SURFACE_HEIGHT: f32 = super::BOARD_THICKNESS / 2.0;
+    pub const FRAME_SURFACE_HEIGHT: f32 = super::BOARD_FRAME_TOP - super::BOARD_THICKNESS / 2.0;
+    pub const TABLE_SURFACE_HEIGHT: f32 = -super::BOARD_THICKNESS / 2.0;
+
+    pub const KNOCKOFF_SPEED: f32 = 4.5;
+    pub const KNOCKOFF_LIFT: f32 = 2.5;
+    pub const KNOCKOFF_SPIN: f32 = 6.0;
+
+    pub const REST_LINEAR_SPEED: f32 = 0.1;
+    pub const REST_ANGULAR_SPEED: f32 = 0.3;
+    pub const REST_DURATION: f32 = 0.5;
 }
 
 // Timing
@@ -200,6 +304,10 @@ pub mod game_rules {
     pub const FIFTY_MOVE_LIMIT: u32 = 50;
     pub const THREEFOLD_REPETITION_LIMIT: u32 = 3;
     pub const INSUFFICIENT_MATERIAL_PIECES: u32 = 3;
+    /// Clocks below this show a warning.
+    pub const LOW_TIME_WARNING_SECONDS: f32 = 20.0;
+    /// In fast games the warning comes at this share of the starting time instead.
+    pub const LOW_TIME_WARNING_FRACTION: f32 = 0.1;
 }
 
 // Debug
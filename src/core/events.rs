@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 use crate::game::board::BoardPosition;
 
 #[derive(Event)]
@@ -22,6 +23,11 @@ pub struct SendNetworkMessageEvent {
     pub message: NetworkMessage,
 }
 
+#[derive(Event)]
+pub struct SendClientRequestEvent {
+    pub request: ClientRequest,
+}
+
 #[derive(Event)]
 pub struct NetworkResponseEvent {
     pub response: ServerResponse,
@@ -65,6 +71,7 @@ pub enum NetworkEventType {
     ReconnectAttempt { attempt: u32 },
     MessageReceived { message: ServerResponse },
     MessageSent { message: ClientRequest },
+    SessionMessage { message: NetworkMessage },
     Error { error: NetworkError },
 }
 
@@ -75,11 +82,17 @@ pub struct NetworkError {
     pub is_recoverable: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum NetworkMessage {
     Connect {
         player_name: String,
         client_version: String,
+        /// Highest protocol version the client speaks.
+        protocol_version: String,
+        /// Session to resume after a dropped connection.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        session_id: Option<String>,
     },
     PlayerLeft {
         player_id: String,
@@ -130,6 +143,7 @@ pub enum GameAction {
     DeclineUndo,
     ShowLegalMoves(bool),
     ToggleCoordinates,
+    ToggleThreatOverlay,
     FlipBoard,
     SaveGame,
     LoadGame,
@@ -170,14 +184,28 @@ pub enum SettingType {
     Gameplay,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TimeControl {
     pub initial_time_seconds: u32,
+    /// Fischer increment, added after every move.
     pub increment_seconds: u32,
     pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay: Option<ClockDelay>,
 }
 
-#[derive(Debug, Clone)]
+/// Per-move delay in seconds, on top of any increment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "seconds", rename_all = "snake_case")]
+pub enum ClockDelay {
+    /// The clock only starts counting down once the delay has passed.
+    Simple(u32),
+    /// Time used on the move, up to the delay, is given back afterwards.
+    Bronstein(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ChatMessageType {
     Game,
     Global,
@@ -185,7 +213,7 @@ pub enum ChatMessageType {
     Private,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ServerInfo {
     pub server_name: String,
     pub version: String,
@@ -194,7 +222,7 @@ pub struct ServerInfo {
     pub features: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PlayerInfo {
     pub id: String,
     pub name: String,
@@ -205,7 +233,8 @@ pub struct PlayerInfo {
     pub status: PlayerStatus,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PlayerStatus {
     Online,
     Away,
@@ -213,7 +242,7 @@ pub enum PlayerStatus {
     Offline,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GameInfo {
     pub id: String,
     pub white_player: Option<PlayerInfo>,
@@ -224,7 +253,8 @@ pub struct GameInfo {
     pub created_at: u64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum GameStatus {
     Waiting,
     Active,
@@ -232,7 +262,7 @@ pub enum GameStatus {
     Paused,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GameStateSnapshot {
     pub board_fen: Vec<String>,
     pub move_history: Vec<String>,
@@ -248,14 +278,16 @@ pub struct GameStateSnapshot {
     pub last_move: Option<(BoardPosition, BoardPosition)>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "outcome", content = "reason", rename_all = "snake_case")]
 pub enum GameResult {
     WhiteWins(GameEndReason),
     BlackWins(GameEndReason),
     Draw(DrawReason),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum GameEndReason {
     Checkmate,
     Resignation,
@@ -263,7 +295,8 @@ pub enum GameEndReason {
     Disconnection,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DrawReason {
     Stalemate,
     InsufficientMaterial,
@@ -273,6 +306,42 @@ pub enum DrawReason {
     Timeout,
 }
 
+impl GameEndReason {
+    fn describe(&self) -> &'static str {
+        match self {
+            GameEndReason::Checkmate => "checkmate",
+            GameEndReason::Resignation => "resignation",
+            GameEndReason::Timeout => "timeout",
+            GameEndReason::Disconnection => "disconnection",
+        }
+    }
+}
+
+impl DrawReason {
+    fn describe(&self) -> &'static str {
+        match self {
+            DrawReason::Stalemate => "stalemate",
+            DrawReason::InsufficientMaterial => "insufficient material",
+            DrawReason::ThreefoldRepetition => "threefold repetition",
+            DrawReason::FiftyMoveRule => "the fifty-move rule",
+            DrawReason::Agreement => "agreement",
+            DrawReason::Timeout => "timeout vs insufficient material",
+        }
+    }
+}
+
+impl GameResult {
+    /// e.g. "White wins by resignation", "Draw by agreement".
+    pub fn describe(&self) -> String {
+        let (outcome, reason) = match self {
+            GameResult::WhiteWins(reason) => ("White wins", reason.describe()),
+            GameResult::BlackWins(reason) => ("Black wins", reason.describe()),
+            GameResult::Draw(reason) => ("Draw", reason.describe()),
+        };
+        format!("{} by {}", outcome, reason)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ErrorInfo {
     pub error_code: String,
@@ -341,12 +410,16 @@ pub enum AnimationType {
     },
 }
 
-#[derive(Debug, Clone)]
+/// Easing curve shared by every animation in the client.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum EaseType {
     Linear,
     EaseIn,
     EaseOut,
+    #[default]
     EaseInOut,
+    /// Smoothstep
+    Smooth,
     Bounce,
     Elastic,
 }
@@ -687,6 +760,12 @@ impl NetworkEvent {
         }
     }
 
+    pub fn reconnect_attempt(attempt: u32) -> Self {
+        Self {
+            event_type: NetworkEventType::ReconnectAttempt { attempt },
+        }
+    }
+
     pub fn message_received(message: ServerResponse) -> Self {
         Self {
             event_type: NetworkEventType::MessageReceived { message },
@@ -699,6 +778,12 @@ impl NetworkEvent {
         }
     }
 
+    pub fn session_message(message: NetworkMessage) -> Self {
+        Self {
+            event_type: NetworkEventType::SessionMessage { message },
+        }
+    }
+
     pub fn error(error: NetworkError) -> Self {
         Self {
             event_type: NetworkEventType::Error { error },
@@ -706,12 +791,36 @@ impl NetworkEvent {
     }
 }
 
-#[derive(Debug, Clone)]
+impl NetworkMessage {
+    /// Handshake announcing this client's version and protocol version.
+    pub fn connect(player_name: String) -> Self {
+        Self::handshake(player_name, None)
+    }
+
+    /// Handshake asking the server to resume `session_id`.
+    pub fn resume(player_name: String, session_id: String) -> Self {
+        Self::handshake(player_name, Some(session_id))
+    }
+
+    fn handshake(player_name: String, session_id: Option<String>) -> Self {
+        Self::Connect {
+            player_name,
+            client_version: crate::core::constants::CLIENT_VERSION.to_string(),
+            protocol_version: crate::core::constants::PROTOCOL_VERSION.to_string(),
+            session_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum ServerResponse {
     Connected {
         player_id: String,
         session_id: String,
         server_info: ServerInfo,
+        /// Version the server picked for this session.
+        protocol_version: String,
     },
     Authenticated {
         player_info: PlayerInfo,
@@ -771,11 +880,14 @@ pub enum ServerResponse {
     Pong,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum ClientRequest {
     CreateGame {
         time_control: Option<TimeControl>,
         is_private: bool,
+        /// Required from whoever takes the other seat.
+        password: Option<String>,
     },
     JoinGame {
         game_id: String,
@@ -793,6 +905,8 @@ pub enum ClientRequest {
     RequestUndo,
     AcceptUndo,
     DeclineUndo,
+    /// Asks for a `GameStateUpdate` with the full position, e.g. after missing moves.
+    RequestGameState,
     SendChatMessage {
         message: String,
         message_type: ChatMessageType,
@@ -805,3 +919,18 @@ pub enum ClientRequest {
     GetPlayerList,
 }
 
+impl EaseType {
+    /// Maps linear progress in `0..=1` onto the curve.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            EaseType::Linear => t,
+            EaseType::EaseIn => t * t * t,
+            EaseType::EaseOut => 1.0 - (1.0 - t).powi(3),
+            EaseType::EaseInOut => crate::core::constants::ease_in_out_cubic(t),
+            EaseType::Smooth => t * t * (3.0 - 2.0 * t),
+            EaseType::Bounce => crate::core::constants::ease_out_bounce(t),
+            EaseType::Elastic => crate::core::constants::ease_out_elastic(t),
+        }
+    }
+}
@@ -15,7 +15,6 @@ pub use states::{
     UIState,
     InputState,
     AudioState,
-    GraphicsState,
     StateTransitions,
     // debug_state_changes
 };
\ No newline at end of file
@@ -5,11 +5,24 @@ pub struct GameSettings {
     pub server_address: String,
     pub player_name: String,
     pub graphics_quality: GraphicsQuality,
+    /// Name of a glTF piece set under `models/`, `None` for the procedural pieces.
+    pub piece_set: Option<String>,
+    /// Key of the board/piece theme in `ThemeLibrary`.
+    pub theme: String,
+    pub lighting_mood: crate::graphics::LightingMood,
+    /// Seed of the generated wood and marble textures.
+    pub texture_seed: u32,
     pub camera_sensitivity: f32,
     pub auto_rotate_board: bool,
     pub show_legal_moves: bool,
     pub show_coordinates: bool,
+    /// Attack counts, hanging and pinned pieces drawn over the board.
+    pub show_threats: bool,
     pub animation_speed: f32,
+    /// Place pieces without move animations (blitz).
+    pub instant_moves: bool,
+    /// Simulate captured pieces being knocked off the board.
+    pub physics_effects: bool,
     pub sound_enabled: bool,
     pub music_enabled: bool,
     pub master_volume: f32,
@@ -23,11 +36,18 @@ impl Default for GameSettings {
             server_address: "127.0.0.1:8080".to_string(),
             player_name: "Player".to_string(),
             graphics_quality: GraphicsQuality::High,
+            piece_set: None,
+            theme: crate::core::constants::DEFAULT_THEME.to_string(),
+            lighting_mood: crate::graphics::LightingMood::TournamentHall,
+            texture_seed: crate::core::constants::PROCEDURAL_TEXTURE_SEED,
             camera_sensitivity: 1.0,
             auto_rotate_board: true,
             show_legal_moves: true,
             show_coordinates: true,
+            show_threats: false,
             animation_speed: 1.0,
+            instant_moves: false,
+            physics_effects: true,
             sound_enabled: true,
             music_enabled: true,
             master_volume: 0.8,
@@ -37,7 +57,8 @@ impl Default for GameSettings {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Renderer preset. Every quality-dependent setting is derived from here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum GraphicsQuality {
     Low,
     Medium,
@@ -45,6 +66,98 @@ pub enum GraphicsQuality {
     Ultra,
 }
 
+impl GraphicsQuality {
+    pub const ALL: [GraphicsQuality; 4] = [
+        GraphicsQuality::Low,
+        GraphicsQuality::Medium,
+        GraphicsQuality::High,
+        GraphicsQuality::Ultra,
+    ];
+
+    /// SSAO needs MSAA off, so Ultra falls back to FXAA.
+    pub fn msaa(&self) -> Msaa {
+        use crate::core::constants::graphics_quality::*;
+        let samples = match self {
+            GraphicsQuality::Low => LOW_MSAA_SAMPLES,
+            GraphicsQuality::Medium => MEDIUM_MSAA_SAMPLES,
+            GraphicsQuality::High => HIGH_MSAA_SAMPLES,
+            GraphicsQuality::Ultra => return Msaa::Off,
+        };
+        match samples {
+            8 => Msaa::Sample8,
+            4 => Msaa::Sample4,
+            2 => Msaa::Sample2,
+            _ => Msaa::Off,
+        }
+    }
+
+    pub fn fxaa_enabled(&self) -> bool {
+        matches!(self, GraphicsQuality::Ultra)
+    }
+
+    pub fn shadows_enabled(&self) -> bool {
+        !matches!(self, GraphicsQuality::Low)
+    }
+
+    pub fn shadow_map_size(&self) -> usize {
+        use crate::core::constants::graphics_quality::*;
+        (match self {
+            GraphicsQuality::Low => LOW_SHADOW_RESOLUTION,
+            GraphicsQuality::Medium => MEDIUM_SHADOW_RESOLUTION,
+            GraphicsQuality::High => HIGH_SHADOW_RESOLUTION,
+            GraphicsQuality::Ultra => ULTRA_SHADOW_RESOLUTION,
+        }) as usize
+    }
+
+    pub fn shadow_cascades(&self) -> usize {
+        match self {
+            GraphicsQuality::Low => 1,
+            GraphicsQuality::Medium => 2,
+            GraphicsQuality::High => 3,
+            GraphicsQuality::Ultra => 4,
+        }
+    }
+
+    pub fn shadow_quality(&self) -> f32 {
+        match self {
+            GraphicsQuality::Low => 0.5,
+            GraphicsQuality::Medium => 1.0,
+            GraphicsQuality::High => 1.5,
+            GraphicsQuality::Ultra => 2.0,
+        }
+    }
+
+    pub fn lighting_quality(&self) -> f32 {
+        match self {
+            GraphicsQuality::Low => 0.7,
+            GraphicsQuality::Medium => 1.0,
+            GraphicsQuality::High => 1.3,
+            GraphicsQuality::Ultra => 1.5,
+        }
+    }
+
+    pub fn bloom_enabled(&self) -> bool {
+        matches!(self, GraphicsQuality::High | GraphicsQuality::Ultra)
+    }
+
+    pub fn ssao_enabled(&self) -> bool {
+        matches!(self, GraphicsQuality::Ultra)
+    }
+
+    pub fn particles_enabled(&self) -> bool {
+        !matches!(self, GraphicsQuality::Low)
+    }
+
+    pub fn particle_density(&self) -> f32 {
+        match self {
+            GraphicsQuality::Low => 0.3,
+            GraphicsQuality::Medium => 0.6,
+            GraphicsQuality::High => 1.0,
+            GraphicsQuality::Ultra => 1.5,
+        }
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct UIState {
     pub active_ui_screens: Vec<crate::core::states::UIState>,
@@ -170,20 +283,51 @@ impl CameraController {
     }
 }
 
-#[derive(Resource, Default)]
+#[derive(Resource)]
 pub struct NetworkState {
     pub connection_status: ConnectionStatus,
     pub server_address: String,
     pub player_id: Option<String>,
+    /// Kept across reconnects so the server can resume the session.
+    pub session_id: Option<String>,
     pub game_id: Option<String>,
-    pub pind: u32,
+    /// Protocol version agreed in the `Connect` handshake.
+    pub protocol_version: Option<String>,
+    /// Last measured round-trip time in milliseconds.
+    pub ping: u32,
     pub last_ping_time: f32,
+    pub ping_pending: bool,
+    pub last_message_time: f32,
     pub reconnect_attempts: u32,
     pub max_reconnect_attempts: u32,
+    /// An attempt is in flight; cleared while waiting out the backoff.
     pub is_reconnecting: bool,
+    pub next_reconnect_time: f32,
     pub connection_start_time: f32,
 }
 
+impl Default for NetworkState {
+    fn default() -> Self {
+        Self {
+            connection_status: ConnectionStatus::Disconnected,
+            server_address: String::new(),
+            player_id: None,
+            session_id: None,
+            game_id: None,
+            protocol_version: None,
+            ping: 0,
+            last_ping_time: 0.0,
+            ping_pending: false,
+            last_message_time: 0.0,
+            reconnect_attempts: 0,
+            max_reconnect_attempts: crate::core::constants::RECONNECT_ATTEMPTS,
+            is_reconnecting: false,
+            next_reconnect_time: 0.0,
+            connection_start_time: 0.0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum ConnectionStatus {
     #[default]
@@ -211,16 +355,28 @@ impl NetworkState {
         )
     }
 
+    /// Waiting for the handshake, either on a fresh connection or a reconnect attempt.
+    pub fn is_handshaking(&self) -> bool {
+        self.connection_status == ConnectionStatus::Connecting
+            || (self.connection_status == ConnectionStatus::Reconnecting && self.is_reconnecting)
+    }
+
     pub fn start_connection(&mut self, address: String, time: f32) {
+        self.disconnect();
         self.server_address = address;
         self.connection_status = ConnectionStatus::Connecting;
         self.connection_start_time = time;
-        self.reconnect_attempts = 0;
     }
 
-    pub fn connection_established(&mut self, player_id: String) {
+    pub fn connection_established(&mut self, player_id: String, session_id: String, time: f32) {
         self.connection_status = ConnectionStatus::Connected;
         self.player_id = Some(player_id);
+        self.session_id = Some(session_id);
+        self.reconnect_attempts = 0;
+        self.is_reconnecting = false;
+        self.ping_pending = false;
+        self.last_ping_time = time;
+        self.last_message_time = time;
     }
 
     pub fn authenticated(&mut self) {
@@ -232,24 +388,90 @@ impl NetworkState {
         self.game_id = Some(game_id);
     }
 
-    pub fn disconenct(&mut self) {
+    pub fn leave_game(&mut self) {
+        self.connection_status = ConnectionStatus::Authenticated;
+        self.game_id = None;
+    }
+
+    pub fn disconnect(&mut self) {
         self.connection_status = ConnectionStatus::Disconnected;
         self.player_id = None;
+        self.session_id = None;
         self.game_id = None;
-        self.pind = 0;
+        self.protocol_version = None;
+        self.ping = 0;
+        self.ping_pending = false;
         self.reconnect_attempts = 0;
         self.is_reconnecting = false;
     }
 
+    /// Gives up on the connection; the session cannot be resumed any more.
     pub fn connection_error(&mut self) {
+        self.disconnect();
         self.connection_status = ConnectionStatus::Error;
-        self.reconnect_attempts += 1;
     }
 
-    pub fn should_reconnect(&self) -> bool {
-        self.connection_status == ConnectionStatus::Error &&
+    /// The connection dropped without being asked to. Schedules a reconnect while there is
+    /// a session to resume and attempts left; returns `false` when giving up.
+    pub fn connection_lost(&mut self, time: f32) -> bool {
+        self.ping_pending = false;
+        self.is_reconnecting = false;
+
+        if self.session_id.is_none() || self.reconnect_attempts >= self.max_reconnect_attempts {
+            self.connection_error();
+            return false;
+        }
+
+        self.connection_status = ConnectionStatus::Reconnecting;
+        self.next_reconnect_time = time + self.reconnect_delay();
+        true
+    }
+
+    /// Exponential backoff: 1s, 2s, 4s... capped.
+    pub fn reconnect_delay(&self) -> f32 {
+        use crate::core::constants::{RECONNECT_BASE_DELAY_SECONDS, RECONNECT_MAX_DELAY_SECONDS};
+        (RECONNECT_BASE_DELAY_SECONDS * 2f32.powi(self.reconnect_attempts.min(16) as i32))
+            .min(RECONNECT_MAX_DELAY_SECONDS)
+    }
+
+    pub fn should_reconnect(&self, time: f32) -> bool {
+        self.connection_status == ConnectionStatus::Reconnecting &&
         self.reconnect_attempts < self.max_reconnect_attempts &&
-        !self.is_reconnecting
+        !self.is_reconnecting &&
+        time >= self.next_reconnect_time
+    }
+
+    pub fn begin_reconnect(&mut self, time: f32) {
+        self.reconnect_attempts += 1;
+        self.is_reconnecting = true;
+        self.connection_start_time = time;
+    }
+
+    pub fn ping_due(&self, time: f32) -> bool {
+        self.is_connected()
+            && !self.ping_pending
+            && time - self.last_ping_time >= crate::core::constants::PING_INTERVAL_SECONDS
+    }
+
+    pub fn ping_sent(&mut self, time: f32) {
+        self.ping_pending = true;
+        self.last_ping_time = time;
+    }
+
+    pub fn pong_received(&mut self, time: f32) {
+        if self.ping_pending {
+            self.ping = ((time - self.last_ping_time).max(0.0) * 1000.0).round() as u32;
+            self.ping_pending = false;
+        }
+    }
+
+    /// No pong within `PING_TIMEOUT`, or silence for longer than a full ping cycle.
+    pub fn is_connection_dead(&self, time: f32) -> bool {
+        use crate::core::constants::{timing::PING_TIMEOUT, PING_INTERVAL_SECONDS};
+        self.is_connected() && (
+            (self.ping_pending && time - self.last_ping_time > PING_TIMEOUT) ||
+            time - self.last_message_time > PING_INTERVAL_SECONDS + PING_TIMEOUT
+        )
     }
 }
 
@@ -360,6 +582,8 @@ impl Default for InputSettings {
         key_bindings.insert("toggle_ui".to_string(), vec![KeyCode::Tab]);
         key_bindings.insert("screenshot".to_string(), vec![KeyCode::F12]);
         key_bindings.insert("fullscreen".to_string(), vec![KeyCode::F11]);
+        key_bindings.insert("toggle_coordinates".to_string(), vec![KeyCode::KeyC]);
+        key_bindings.insert("toggle_threats".to_string(), vec![KeyCode::KeyT]);
 
         Self {
             mouse_sensitivity: 1.0,
@@ -0,0 +1,288 @@
+//! Chess clock: counts down the side to move and adds the increment after each
+//! move, with an optional simple or Bronstein delay. The clock starts with
+//! White's first move. Online games correct it from the server's times in
+//! `network::clock_sync`.
+
+use bevy::prelude::*;
+
+use crate::{
+    core::{
+        constants::game_rules::{LOW_TIME_WARNING_FRACTION, LOW_TIME_WARNING_SECONDS},
+        events::{AudioAction, AudioEvent, ClockDelay, DrawReason, GameEndReason, GameResult, TimeControl},
+    },
+    game::{
+        fen::FenPosition,
+        state::{GameStateResource, GameStatus, MoveHistory},
+    },
+    PieceColor, PieceType,
+};
+
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ChessClock {
+    /// `None` for untimed games; the clock then does nothing.
+    pub time_control: Option<TimeControl>,
+    pub white_remaining: f32,
+    pub black_remaining: f32,
+    /// Side whose time is running; `None` before the first move and once a game is over.
+    pub running: Option<PieceColor>,
+    /// Half-moves the clock has seen.
+    pub moves_played: u32,
+    pub flagged: Option<PieceColor>,
+    /// Result of the flag fall, taken by whoever ends the game.
+    pub result: Option<GameResult>,
+    /// Time the side to move has spent on this move, for the delays.
+    turn_elapsed: f32,
+    low_time_warned: [bool; 2],
+}
+
+impl ChessClock {
+    pub fn new(time_control: Option<TimeControl>) -> Self {
+        let initial = time_control.as_ref().map_or(0.0, |time_control| time_control.initial_time_seconds as f32);
+        Self {
+            time_control,
+            white_remaining: initial,
+            black_remaining: initial,
+            ..default()
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.time_control.is_some()
+    }
+
+    pub fn remaining(&self, color: PieceColor) -> f32 {
+        match color {
+            PieceColor::White => self.white_remaining,
+            PieceColor::Black => self.black_remaining,
+        }
+    }
+
+    fn remaining_mut(&mut self, color: PieceColor) -> &mut f32 {
+        match color {
+            PieceColor::White => &mut self.white_remaining,
+            PieceColor::Black => &mut self.black_remaining,
+        }
+    }
+
+    fn delay(&self) -> Option<ClockDelay> {
+        self.time_control.as_ref().and_then(|time_control| time_control.delay)
+    }
+
+    /// Time taken off the running clock for `elapsed` seconds into a move.
+    fn charged(&self, elapsed: f32) -> f32 {
+        match self.delay() {
+            Some(ClockDelay::Simple(delay)) => (elapsed - delay as f32).max(0.0),
+            _ => elapsed,
+        }
+    }
+
+    /// Runs the clock of the side to move. Returns the side that just ran out of time.
+    pub fn tick(&mut self, delta: f32) -> Option<PieceColor> {
+        let color = self.running.filter(|_| self.is_enabled() && self.flagged.is_none())?;
+
+        let charged = self.charged(self.turn_elapsed + delta) - self.charged(self.turn_elapsed);
+        self.turn_elapsed += delta;
+        let remaining = self.remaining_mut(color);
+        *remaining -= charged;
+        if *remaining > 0.0 {
+            return None;
+        }
+
+        *remaining = 0.0;
+        self.flagged = Some(color);
+        self.running = None;
+        Some(color)
+    }
+
+    /// Catches up with `move_count` half-moves; each new one ends its mover's turn.
+    /// A lower count means moves were taken back.
+    pub fn advance_to(&mut self, move_count: u32) {
+        while self.moves_played < move_count {
+            self.moves_played += 1;
+            self.finish_turn(mover_of(self.moves_played));
+        }
+        if move_count < self.moves_played {
+            // The times come with the server's snapshot
+            self.moves_played = move_count;
+            self.turn_elapsed = 0.0;
+            self.running = (move_count > 0 && self.flagged.is_none()).then(|| mover_of(move_count).opposite());
+        }
+    }
+
+    fn finish_turn(&mut self, mover: PieceColor) {
+        if !self.is_enabled() || self.flagged.is_some() {
+            return;
+        }
+
+        let increment = self.time_control.as_ref().map_or(0, |time_control| time_control.increment_seconds) as f32;
+        let refund = match self.delay() {
+            Some(ClockDelay::Bronstein(delay)) => self.turn_elapsed.min(delay as f32),
+            _ => 0.0,
+        };
+        *self.remaining_mut(mover) += increment + refund;
+        self.running = Some(mover.opposite());
+        self.turn_elapsed = 0.0;
+    }
+
+    /// Puts the clock on a position the server describes, as for a snapshot.
+    pub fn restore(&mut self, move_count: u32, side_to_move: PieceColor, is_over: bool) {
+        self.moves_played = move_count;
+        self.turn_elapsed = 0.0;
+        self.running = (move_count > 0 && !is_over).then_some(side_to_move);
+    }
+
+    /// Takes the server's times, which are `latency` seconds old by now.
+    /// Returns how far our clocks had drifted from them.
+    pub fn sync(&mut self, white_remaining: f32, black_remaining: f32, latency: f32) -> f32 {
+        let (mut white, mut black) = (white_remaining, black_remaining);
+        if let Some(color) = self.running {
+            // The side to move kept thinking while the message travelled
+            let charged = self.charged(latency);
+            match color {
+                PieceColor::White => white = (white - charged).max(0.0),
+                PieceColor::Black => black = (black - charged).max(0.0),
+            }
+            self.turn_elapsed = latency;
+        }
+
+        let drift = (self.white_remaining - white).abs().max((self.black_remaining - black).abs());
+        self.white_remaining = white;
+        self.black_remaining = black;
+
+        // The server's clock is the one that counts
+        if let Some(flagged) = self.flagged.filter(|flagged| self.remaining(*flagged) > 0.0) {
+            self.flagged = None;
+            self.result = None;
+            self.running = Some(flagged);
+        }
+        drift
+    }
+
+    pub fn stop(&mut self) {
+        self.running = None;
+    }
+
+    /// Remaining time under which a clock counts as low: a bullet game
+    /// shouldn't start out in the warning.
+    pub fn low_time_threshold(&self) -> f32 {
+        let initial = self.time_control.as_ref().map_or(0, |time_control| time_control.initial_time_seconds) as f32;
+        LOW_TIME_WARNING_SECONDS.min(initial * LOW_TIME_WARNING_FRACTION)
+    }
+
+    pub fn is_low_on_time(&self, color: PieceColor) -> bool {
+        self.is_enabled() && self.remaining(color) < self.low_time_threshold()
+    }
+
+    /// True once each time `color` drops under the warning threshold.
+    pub fn take_low_time_warning(&mut self, color: PieceColor) -> bool {
+        let low = self.is_low_on_time(color);
+        let warned = &mut self.low_time_warned[color as usize];
+        let first = low && !*warned;
+        *warned = low;
+        first
+    }
+}
+
+/// Who played half-move `move_number` (1 is White's first move).
+pub fn mover_of(move_number: u32) -> PieceColor {
+    if move_number % 2 == 1 {
+        PieceColor::White
+    } else {
+        PieceColor::Black
+    }
+}
+
+/// Whether `color` could still mate by some sequence of legal moves, however
+/// unlikely, which is what FIDE asks before scoring a flag fall as a loss.
+/// A lone minor piece can, if the opponent has pieces to hem their own king in
+/// with; like most servers we only judge this from the material, not the
+/// placement, so the rare helpmates against a lone queen are scored as draws.
+pub fn has_mating_material(position: &FenPosition, color: PieceColor) -> bool {
+    let mut knights = 0;
+    let mut bishop_squares = [false; 2];
+    for (pos, piece_type, _) in position.pieces().filter(|(_, _, piece_color)| *piece_color == color) {
+        match piece_type {
+            PieceType::King => {}
+            PieceType::Pawn | PieceType::Rook | PieceType::Queen => return true,
+            PieceType::Knight => knights += 1,
+            PieceType::Bishop => bishop_squares[pos.is_light_square() as usize] = true,
+        }
+    }
+
+    let opponent: Vec<_> = position
+        .pieces()
+        .filter(|(_, piece_type, piece_color)| *piece_color != color && *piece_type != PieceType::King)
+        .collect();
+    let has_bishops = bishop_squares.iter().any(|&on_color| on_color);
+    match (knights, has_bishops) {
+        (0, false) => false,
+        // 相手の駒が自分のキングの逃げ道を塞げれば、ナイト1枚でもメイトできる
+        (1, false) => opponent.iter().any(|(_, piece_type, _)| *piece_type != PieceType::Queen),
+        // Bishops only: mate needs a square of the other colour covered or blocked
+        (0, true) => {
+            for (pos, piece_type, _) in &opponent {
+                match piece_type {
+                    PieceType::Pawn | PieceType::Knight => return true,
+                    PieceType::Bishop => bishop_squares[pos.is_light_square() as usize] = true,
+                    _ => {}
+                }
+            }
+            bishop_squares == [true, true]
+        }
+        _ => true,
+    }
+}
+
+/// `flagged` ran out of time: the opponent wins, unless no sequence of legal
+/// moves could get them a mate.
+pub fn timeout_result(flagged: PieceColor, position: &FenPosition) -> GameResult {
+    match flagged.opposite() {
+        winner if !has_mating_material(position, winner) => GameResult::Draw(DrawReason::Timeout),
+        PieceColor::White => GameResult::WhiteWins(GameEndReason::Timeout),
+        PieceColor::Black => GameResult::BlackWins(GameEndReason::Timeout),
+    }
+}
+
+pub fn update_chess_clock(
+    mut clock: ResMut<ChessClock>,
+    mut game_state: ResMut<GameStateResource>,
+    history: Res<MoveHistory>,
+    mut audio: EventWriter<AudioEvent>,
+    time: Res<Time<Real>>,
+) {
+    if !clock.is_enabled() {
+        return;
+    }
+
+    clock.advance_to(game_state.move_count);
+    if !matches!(game_state.game_status, GameStatus::InProgress | GameStatus::Check) {
+        clock.stop();
+    }
+
+    if let Some(flagged) = clock.tick(time.delta_secs()) {
+        let position = history
+            .positions
+            .last()
+            .and_then(|fen| FenPosition::parse(fen).ok())
+            .unwrap_or_default();
+        let result = timeout_result(flagged, &position);
+        info!("{:?} ran out of time: {}", flagged, result.describe());
+
+        game_state.game_status = match result {
+            GameResult::Draw(_) => GameStatus::Draw,
+            _ => GameStatus::Timeout,
+        };
+        clock.result = Some(result);
+    }
+
+    for color in [PieceColor::White, PieceColor::Black] {
+        if clock.take_low_time_warning(color) {
+            audio.write(AudioEvent {
+                action: AudioAction::PlaySFX {
+                    sound_name: "low_time".to_string(),
+                    volume: None,
+                },
+            });
+        }
+    }
+}
@@ -0,0 +1,361 @@
+//! FEN positions without any entities behind them. The server describes boards this
+//! way (`GameStateSnapshot::board_fen`), and online games keep the last position the
+//! server agreed on as one so the board can be rebuilt from it.
+
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::{
+    game::{
+        moves::Move,
+        state::{CastlingRights, ChessMove},
+    },
+    BoardPosition, PieceColor, PieceType,
+};
+
+pub const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum FenError {
+    #[error("empty FEN")]
+    Empty,
+    #[error("invalid piece placement: {0}")]
+    Placement(String),
+    #[error("invalid active color: {0}")]
+    ActiveColor(String),
+    #[error("invalid castling rights: {0}")]
+    Castling(String),
+    #[error("invalid en passant square: {0}")]
+    EnPassant(String),
+    #[error("invalid move counter: {0}")]
+    Counter(String),
+}
+
+/// `[rank][file]`, like `ChessBoard::squares`.
+pub type Squares = [[Option<(PieceType, PieceColor)>; 8]; 8];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FenPosition {
+    pub squares: Squares,
+    pub active_color: PieceColor,
+    pub castling_rights: CastlingRights,
+    pub en_passant_target: Option<BoardPosition>,
+    pub halfmove_clock: u32,
+    pub fullmove_number: u32,
+}
+
+impl Default for FenPosition {
+    fn default() -> Self {
+        Self::starting()
+    }
+}
+
+impl FenPosition {
+    pub fn starting() -> Self {
+        Self::parse(STARTING_FEN).expect("starting FEN is valid")
+    }
+
+    /// Parses a FEN string. Only the placement is required; missing fields
+    /// default to White to move, no castling, no en passant and move 1.
+    pub fn parse(fen: &str) -> Result<Self, FenError> {
+        let mut fields = fen.split_whitespace();
+        let placement = fields.next().ok_or(FenError::Empty)?;
+
+        Ok(Self {
+            squares: parse_placement(placement)?,
+            active_color: match fields.next() {
+                None | Some("w") => PieceColor::White,
+                Some("b") => PieceColor::Black,
+                Some(other) => return Err(FenError::ActiveColor(other.to_string())),
+            },
+            castling_rights: parse_castling(fields.next().unwrap_or("-"))?,
+            en_passant_target: match fields.next() {
+                None | Some("-") => None,
+                Some(square) => Some(
+                    BoardPosition::from_algebraic(square).ok_or_else(|| FenError::EnPassant(square.to_string()))?,
+                ),
+            },
+            halfmove_clock: parse_counter(fields.next(), 0)?,
+            fullmove_number: parse_counter(fields.next(), 1)?,
+        })
+    }
+
+    pub fn piece_at(&self, pos: BoardPosition) -> Option<(PieceType, PieceColor)> {
+        self.squares[pos.rank as usize][pos.file as usize]
+    }
+
+    pub fn set_piece_at(&mut self, pos: BoardPosition, piece: Option<(PieceType, PieceColor)>) {
+        self.squares[pos.rank as usize][pos.file as usize] = piece;
+    }
+
+    /// Every piece with its square, rank 1 first.
+    pub fn pieces(&self) -> impl Iterator<Item = (BoardPosition, PieceType, PieceColor)> + '_ {
+        (0..8u8).flat_map(move |rank| {
+            (0..8u8).filter_map(move |file| {
+                let pos = BoardPosition { file, rank };
+                self.piece_at(pos).map(|(piece_type, color)| (pos, piece_type, color))
+            })
+        })
+    }
+
+    /// First FEN field only; enough to tell whether two boards look the same.
+    pub fn placement(&self) -> String {
+        let mut placement = String::new();
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+            for file in 0..8 {
+                match self.squares[rank][file] {
+                    Some((piece_type, color)) => {
+                        if empty > 0 {
+                            placement.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        let symbol = piece_type.to_fen_char();
+                        placement.push(match color {
+                            PieceColor::White => symbol.to_ascii_uppercase(),
+                            PieceColor::Black => symbol.to_ascii_lowercase(),
+                        });
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                placement.push_str(&empty.to_string());
+            }
+            if rank > 0 {
+                placement.push('/');
+            }
+        }
+        placement
+    }
+
+    /// Placement of an arbitrary set of pieces, e.g. the ones on the 3D board.
+    pub fn placement_of(pieces: impl IntoIterator<Item = (BoardPosition, PieceType, PieceColor)>) -> String {
+        let mut position = Self {
+            squares: [[None; 8]; 8],
+            ..Self::starting()
+        };
+        for (pos, piece_type, color) in pieces {
+            position.set_piece_at(pos, Some((piece_type, color)));
+        }
+        position.placement()
+    }
+
+    /// Plays a move for the side to move, keeping castling rights, en passant
+    /// and the move counters up to date. Pawns reaching the last rank become
+    /// `promotion`, or a queen when none is given.
+    ///
+    /// Returns `None` when there is no piece of the side to move on `from`;
+    /// the move itself is not checked for legality.
+    pub fn apply_move(
+        &mut self,
+        from: BoardPosition,
+        to: BoardPosition,
+        promotion: Option<PieceType>,
+    ) -> Option<ChessMove> {
+        let (piece_type, color) = self.piece_at(from)?;
+        if color != self.active_color || from == to {
+            return None;
+        }
+
+        let is_castling = piece_type == PieceType::King && from.file.abs_diff(to.file) == 2;
+        let is_en_passant = piece_type == PieceType::Pawn
+            && from.file != to.file
+            && self.piece_at(to).is_none()
+            && self.en_passant_target == Some(to);
+        let captured_square = if is_en_passant { BoardPosition::new(to.file, from.rank)? } else { to };
+        let captured_piece = self.piece_at(captured_square).map(|(captured_type, _)| captured_type);
+
+        let last_rank = match color {
+            PieceColor::White => 7,
+            PieceColor::Black => 0,
+        };
+        let promotion = (piece_type == PieceType::Pawn && to.rank == last_rank)
+            .then(|| promotion.unwrap_or(PieceType::Queen));
+
+        self.set_piece_at(captured_square, None);
+        self.set_piece_at(from, None);
+        self.set_piece_at(to, Some((promotion.unwrap_or(piece_type), color)));
+
+        if is_castling {
+            let (rook_file, rook_target_file) = if to.file > from.file { (7, to.file - 1) } else { (0, to.file + 1) };
+            let rook_from = BoardPosition::new(rook_file, from.rank)?;
+            let rook = self.piece_at(rook_from);
+            self.set_piece_at(rook_from, None);
+            self.set_piece_at(BoardPosition::new(rook_target_file, from.rank)?, rook);
+        }
+
+        // 動いたキングとルーク、取られたルークの分のキャスリング権を失う
+        if piece_type == PieceType::King {
+            match color {
+                PieceColor::White => {
+                    self.castling_rights.white_kingside = false;
+                    self.castling_rights.white_queenside = false;
+                }
+                PieceColor::Black => {
+                    self.castling_rights.black_kingside = false;
+                    self.castling_rights.block_queenside = false;
+                }
+            }
+        }
+        for square in [from, to] {
+            match (square.file, square.rank) {
+                (0, 0) => self.castling_rights.white_queenside = false,
+                (7, 0) => self.castling_rights.white_kingside = false,
+                (0, 7) => self.castling_rights.block_queenside = false,
+                (7, 7) => self.castling_rights.black_kingside = false,
+                _ => {}
+            }
+        }
+
+        self.en_passant_target = if piece_type == PieceType::Pawn && from.rank.abs_diff(to.rank) == 2 {
+            BoardPosition::new(from.file, (from.rank + to.rank) / 2)
+        } else {
+            None
+        };
+        self.halfmove_clock = if piece_type == PieceType::Pawn || captured_piece.is_some() {
+            0
+        } else {
+            self.halfmove_clock + 1
+        };
+        if color == PieceColor::Black {
+            self.fullmove_number += 1;
+        }
+        self.active_color = color.opposite();
+
+        let mut notation = Move::new(from, to, piece_type, color);
+        if let Some(captured_piece) = captured_piece {
+            notation = notation.with_capture(captured_piece);
+        }
+        if let Some(promotion) = promotion {
+            notation = notation.with_promotion(promotion);
+        }
+        if is_castling {
+            notation = notation.with_castling();
+        }
+
+        Some(ChessMove {
+            from,
+            to,
+            piece_type,
+            piece_color: color,
+            captured_piece,
+            promotion,
+            is_castling,
+            is_en_passant,
+            notation: notation.to_algebraic_notation(),
+            timestamp: 0.0,
+        })
+    }
+}
+
+impl fmt::Display for FenPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rights = self.castling_rights;
+        let castling: String = [
+            (rights.white_kingside, 'K'),
+            (rights.white_queenside, 'Q'),
+            (rights.black_kingside, 'k'),
+            (rights.block_queenside, 'q'),
+        ]
+        .into_iter()
+        .filter_map(|(allowed, symbol)| allowed.then_some(symbol))
+        .collect();
+
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            self.placement(),
+            match self.active_color {
+                PieceColor::White => 'w',
+                PieceColor::Black => 'b',
+            },
+            if castling.is_empty() { "-" } else { &castling },
+            self.en_passant_target.map_or("-".to_string(), |square| square.to_algebraic()),
+            self.halfmove_clock,
+            self.fullmove_number,
+        )
+    }
+}
+
+/// Long algebraic move as the server writes it: `e2e4`, `e7e8q`.
+pub fn parse_uci_move(text: &str) -> Option<(BoardPosition, BoardPosition, Option<PieceType>)> {
+    let from = BoardPosition::from_algebraic(text.get(0..2)?)?;
+    let to = BoardPosition::from_algebraic(text.get(2..4)?)?;
+    let promotion = match text.get(4..) {
+        None | Some("") => None,
+        Some(symbol) => {
+            let mut chars = symbol.chars();
+            let piece_type = PieceType::from_fen_char(chars.next()?)?;
+            if chars.next().is_some() {
+                return None;
+            }
+            Some(piece_type)
+        }
+    };
+    Some((from, to, promotion))
+}
+
+pub fn to_uci_move(from: BoardPosition, to: BoardPosition, promotion: Option<PieceType>) -> String {
+    let mut text = format!("{}{}", from.to_algebraic(), to.to_algebraic());
+    if let Some(promotion) = promotion {
+        text.push(promotion.to_fen_char().to_ascii_lowercase());
+    }
+    text
+}
+
+fn parse_placement(placement: &str) -> Result<Squares, FenError> {
+    let invalid = || FenError::Placement(placement.to_string());
+    let ranks: Vec<&str> = placement.split('/').collect();
+    if ranks.len() != 8 {
+        return Err(invalid());
+    }
+
+    let mut squares = [[None; 8]; 8];
+    for (index, rank_text) in ranks.iter().enumerate() {
+        let rank = 7 - index;
+        let mut file = 0usize;
+        for symbol in rank_text.chars() {
+            if let Some(skip) = symbol.to_digit(10) {
+                file += skip as usize;
+                continue;
+            }
+            let piece_type = PieceType::from_fen_char(symbol).ok_or_else(invalid)?;
+            let color = if symbol.is_ascii_uppercase() { PieceColor::White } else { PieceColor::Black };
+            if file >= 8 {
+                return Err(invalid());
+            }
+            squares[rank][file] = Some((piece_type, color));
+            file += 1;
+        }
+        if file != 8 {
+            return Err(invalid());
+        }
+    }
+    Ok(squares)
+}
+
+fn parse_castling(castling: &str) -> Result<CastlingRights, FenError> {
+    let mut rights = CastlingRights::none();
+    if castling == "-" {
+        return Ok(rights);
+    }
+    for symbol in castling.chars() {
+        match symbol {
+            'K' => rights.white_kingside = true,
+            'Q' => rights.white_queenside = true,
+            'k' => rights.black_kingside = true,
+            'q' => rights.block_queenside = true,
+            _ => return Err(FenError::Castling(castling.to_string())),
+        }
+    }
+    Ok(rights)
+}
+
+fn parse_counter(field: Option<&str>, default: u32) -> Result<u32, FenError> {
+    match field {
+        None => Ok(default),
+        Some(text) => text.parse().map_err(|_| FenError::Counter(text.to_string())),
+    }
+}
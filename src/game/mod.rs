@@ -1,5 +1,7 @@
 
 pub mod board;
+pub mod clock;
+pub mod fen;
 pub mod moves;
 pub mod rules;
 pub mod state;
@@ -10,6 +12,8 @@ use bevy::prelude::*;
 use crate::core::{GameState, CoreSet};
 
 pub use board::*;
+pub use clock::*;
+pub use fen::*;
 pub use moves::*;
 pub use rules::*;
 pub use state::*;
@@ -20,16 +24,12 @@ use crate::game::pieces::{
     // PieceType,
     ChessPiece,
     // Selected,
-    // PieceAnimation,
-    // PieceAnimationType,
-    // EaseFunction,
     // PieceEffect,
     // PieceEffectType,
     // PieceMaterialHandle,
     // spawn_piece,
     // spawn_initial_pieces,
     handle_piece_selection,
-    animate_pieces as pieces_animate_pieces,
     // move_piece,
     // update_piece_effects
 };
@@ -44,6 +44,7 @@ impl Plugin for GamePlugin {
             .init_resource::<GameStateResource>()
             .init_resource::<MoveHistory>()
             .init_resource::<SelectionState>()
+            .init_resource::<ChessClock>()
             
             // システム追加
             .add_systems(OnEnter(GameState::InGame), (
@@ -66,12 +67,12 @@ impl Plugin for GamePlugin {
                 // 表示更新
                 update_piece_positions,
                 // update_board_highlights,
-                pieces_animate_pieces,
                 
                 // UI更新
                 // update_game_ui,
                 // update_move_history_display,
             ).in_set(CoreSet::Logic))
+            .add_systems(Update, update_chess_clock.in_set(CoreSet::Logic).run_if(in_state(GameState::InGame)))
             
             .add_systems(OnExit(GameState::InGame), (
                 cleanup_game_entities,
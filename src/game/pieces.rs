@@ -1,7 +1,9 @@
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::core::{constants::*, events::EaseType};
 use crate::game::BoardPosition;
+use crate::graphics::{capture_burst, promotion_tweens, Tween, TweenAction, TweenNode, Tweens};
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PieceColor {
@@ -40,6 +42,15 @@ pub enum PieceType {
 }
 
 impl PieceType {
+    pub const ALL: [PieceType; 6] = [
+        PieceType::Pawn,
+        PieceType::Rook,
+        PieceType::Knight,
+        PieceType::Bishop,
+        PieceType::Queen,
+        PieceType::King,
+    ];
+
     pub fn to_fen_char(self) -> char {
         match self {
             PieceType::Pawn => 'p',
@@ -176,91 +187,74 @@ pub struct Selected {
     pub selected_at: f32,
 }
 
-#[derive(Component)]
-pub struct PieceAnimation {
-    pub animation_type: PieceAnimationType,
-    pub start_time: f32,
-    pub duration: f32,
-    pub start_position: Vec3,
-    pub target_position: Vec3,
-    pub ease_function: EaseFunction,
-}
-
-#[derive(Debug, Clone)]
-pub enum PieceAnimationType {
-    Move,
-    Capture,
-    Castle,
-    Promotion,
-    Hover,
-    Return,
+/// Shape of a piece's route between two points, sampled by `TweenAction::Path`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MovePath {
+    Straight,
+    /// Parabolic hop peaking `height` above the straight line.
+    Arc { height: f32 },
+    /// Rise to `height`, glide across, then set down.
+    LiftGlideLand { height: f32 },
+    /// Shoved along `direction` while dropping below the board.
+    KnockAside { direction: Vec3 },
 }
 
-#[derive(Debug, Clone)]
-pub enum EaseFunction {
-    Linear,
-    EaseInOut,
-    EaseOut,
-    Bounce,
-}
-
-impl PieceAnimation {
-    pub fn new_move(start_pos: Vec3, target_pos: Vec3, time: f32) -> Self {
-        Self {
-            animation_type: PieceAnimationType::Move,
-            start_time: time,
-            duration: crate::core::constants::PIECE_MOVE_DURATION,
-            start_position: start_pos,
-            target_position: target_pos,
-            ease_function: EaseFunction::EaseOut,
-        }
-    }
-
-    pub fn new_capture(pos: Vec3, time: f32) -> Self {
-        Self {
-            animation_type: PieceAnimationType::Capture,
-            start_time: time,
-            duration: crate::core::constants::PIECE_CAPTURE_DURATION,
-            start_position: pos,
-            target_position: pos + Vec3::new(0.0, -2.0, 0.0),
-            ease_function: EaseFunction::EaseInOut,
+impl MovePath {
+    /// Knights hop over the board, everything else lifts, glides and lands.
+    pub fn for_piece(piece_type: PieceType) -> Self {
+        match piece_type {
+            PieceType::Knight => MovePath::Arc { height: KNIGHT_ARC_HEIGHT },
+            _ => MovePath::LiftGlideLand { height: PIECE_LIFT_HEIGHT },
         }
     }
 
-    pub fn new_hover(original_pos: Vec3, time: f32) -> Self {
-        let hover_pos = original_pos + Vec3::new(0.0, crate::core::constants::PIECE_HOVER_HEIGHT, 0.0);
-        Self {
-            animation_type: PieceAnimationType::Hover,
-            start_time: time,
-            duration: 0.2,
-            start_position: original_pos,
-            target_position: hover_pos,
-            ease_function: EaseFunction::EaseOut,
-        }
-    }
-
-    pub fn is_complete(&self, current_time: f32) -> bool {
-        current_time - self.start_time >= self.duration
-    }
-
-    pub fn progress(&self, current_time: f32) -> f32 {
-        ((current_time - self.start_time) / self.duration).clamp(0.0, 1.0)
-    }
-
-    pub fn eased_progress(&self, current_time: f32) -> f32 {
-        let t = self.progress(current_time);
-        match self.ease_function {
-            EaseFunction::Linear => t,
-            EaseFunction::EaseInOut => crate::core::constants::ease_in_out_cubic(t),
-            EaseFunction::EaseOut => 1.0 - (1.0 - t).powi(3),
-            EaseFunction::Bounce => crate::core::constants::ease_out_bounce(t),
+    /// Point `t` (0..1) of the way from `start` to `target`. Each path does its own easing.
+    pub fn sample(self, start: Vec3, target: Vec3, t: f32) -> Vec3 {
+        match self {
+            MovePath::Straight => start.lerp(target, t),
+            MovePath::Arc { height } => {
+                // Horizontal ease, vertical parabola on raw progress so the apex sits mid-flight
+                let mut position = start.lerp(target, EaseType::EaseInOut.apply(t));
+                position.y += 4.0 * height * t * (1.0 - t);
+                position
+            }
+            MovePath::LiftGlideLand { height } => {
+                const LIFT: f32 = 0.2;
+                const LAND: f32 = 0.8;
+
+                let glide = ((t - LIFT) / (LAND - LIFT)).clamp(0.0, 1.0);
+                let lift = if t < LIFT {
+                    EaseType::EaseOut.apply(t / LIFT)
+                } else if t > LAND {
+                    1.0 - EaseType::EaseIn.apply((t - LAND) / (1.0 - LAND))
+                } else {
+                    1.0
+                };
+
+                let mut position = start.lerp(target, EaseType::EaseInOut.apply(glide));
+                position.y += height * lift;
+                position
+            }
+            MovePath::KnockAside { direction } => {
+                // Skid off quickly, then drop out of sight
+                let slide = EaseType::EaseOut.apply(t);
+                let drop = EaseType::EaseIn.apply(t);
+                let mut position = start + direction * CAPTURE_KNOCK_DISTANCE * slide;
+                position.y = start.y + 0.3 * (std::f32::consts::PI * t).sin() * (1.0 - t)
+                    + (target.y - start.y) * drop;
+                position
+            }
         }
     }
+}
 
-    pub fn current_position(&self, current_time: f32) -> Vec3 {
-        let t = self.eased_progress(current_time);
-        self.start_position.lerp(self.target_position, t)
-    }
+/// Captured piece that is shoved off its square; the physics simulation may take it over
+/// from its tween.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct KnockedAside {
+    pub direction: Vec3,
+    /// Real seconds until the attacker reaches it.
+    pub delay: f32,
 }
 
 #[derive(Component)]
@@ -292,9 +286,16 @@ pub fn spawn_piece(
     let mesh_handle = meshes.get_piece_mesh(piece_type);
     let material_handle = materials.get_piece_material(color);
 
-    // Bevy 0.16.1対応: 基本的なコンポーネントのみを使用
+    // ナイトは相手側を向かせる
+    let rotation = match color {
+        PieceColor::White => Quat::IDENTITY,
+        PieceColor::Black => Quat::from_rotation_y(std::f32::consts::PI),
+    };
+
     commands.spawn((
-        Transform::from_translation(world_position),
+        Mesh3d(mesh_handle),
+        MeshMaterial3d(material_handle),
+        Transform::from_translation(world_position).with_rotation(rotation),
         ChessPiece::new(piece_type, color, position),
         Name::new(format!("{} {} at {}", 
             color.to_string(), 
@@ -407,75 +408,185 @@ pub fn handle_piece_selection(
     }
 }
 
-pub fn animate_pieces(
-    mut commands: Commands,
-    mut pieces: Query<(Entity, &mut Transform, &PieceAnimation)>,
-    time: Res<Time>,
-) {
-    let current_time = time.elapsed_secs();
-
-    for (entity, mut transform, animation) in pieces.iter_mut() {
-        if animation.is_complete(current_time) {
-            // アニメーション完了
-            transform.translation = animation.target_position;
-            commands.entity(entity).remove::<PieceAnimation>();
-            
-            // キャプチャアニメーションの場合、駒を削除
-            if matches!(animation.animation_type, PieceAnimationType::Capture) {
-                commands.entity(entity).despawn();
-            }
-        } else {
-            // アニメーション中
-            transform.translation = animation.current_position(current_time);
+/// Turns a captured piece into a graveyard piece; the graveyard lays it out beside the board.
+pub fn send_to_graveyard(commands: &mut Commands, entity: Entity, piece: Option<&ChessPiece>) {
+    match piece {
+        Some(piece) => {
+            commands
+                .entity(entity)
+                .remove::<ChessPiece>()
+                .insert(crate::graphics::GraveyardPiece {
+                    piece_type: piece.piece_type,
+                    color: piece.color,
+                });
         }
+        None => commands.entity(entity).despawn(),
     }
 }
 
+/// Moves the piece on `from` to `to` and starts the choreography for it.
+/// Also handles the rook of a castling move, en passant captures and promotion.
+/// With `instant_moves` pieces are placed directly.
 pub fn move_piece(
-    mut commands: Commands,
-    mut pieces: Query<(Entity, &mut ChessPiece, &mut Transform)>,
-    mut board: ResMut<crate::game::board::ChessBoard>,
-    time: Res<Time>,
+    commands: &mut Commands,
+    pieces: &mut Query<(Entity, &mut ChessPiece, &mut Transform)>,
+    board: &mut crate::game::board::ChessBoard,
+    tweens: &mut Tweens,
+    settings: &crate::core::resources::GameSettings,
     from: BoardPosition,
     to: BoardPosition,
+    promotion: Option<PieceType>,
     current_turn: u32,
 ) -> Result<Option<Entity>, String> {
     // 移動元の駒を検索
     let moving_piece_entity = board.get_piece_at(from)
         .ok_or("No piece at source position")?;
 
-    // 移動先に駒があるかチェック（キャプチャ）
-    let captured_piece = board.get_piece_at(to);
-
-    // 駒を移動
-    if let Ok((_, mut piece, transform)) = pieces.get_mut(moving_piece_entity) {
-        let start_pos = transform.translation;
-        let target_pos = to.to_world_position();
+    let (piece_type, start_pos) = match pieces.get(moving_piece_entity) {
+        Ok((_, piece, transform)) => (piece.piece_type, transform.translation),
+        Err(_) => return Err("Invalid piece entity".to_string()),
+    };
+
+    // 移動先に駒があるかチェック（キャプチャ）。空マスへの斜めのポーン移動はアンパッサン
+    let captured_square = match board.get_piece_at(to) {
+        Some(_) => Some(to),
+        None if piece_type == PieceType::Pawn && from.file != to.file => BoardPosition::new(to.file, from.rank),
+        None => None,
+    };
+    let captured_piece = captured_square.and_then(|square| board.get_piece_at(square));
+
+    // キングの2マス移動はキャスリング
+    let castling_rook = if piece_type == PieceType::King && from.file.abs_diff(to.file) == 2 {
+        let (rook_file, rook_target_file) = if to.file > from.file { (7, to.file - 1) } else { (0, to.file + 1) };
+        BoardPosition::new(rook_file, from.rank)
+            .zip(BoardPosition::new(rook_target_file, from.rank))
+            .and_then(|(rook_from, rook_to)| board.get_piece_at(rook_from).map(|rook| (rook, rook_from, rook_to)))
+    } else {
+        None
+    };
 
-        // アニメーションを開始
-        commands.entity(moving_piece_entity).insert(
-            PieceAnimation::new_move(start_pos, target_pos, time.elapsed_secs())
-        );
+    let instant = settings.instant_moves;
+    let target_pos = to.to_world_position();
 
-        // 駒の論理的な位置を更新
+    // 駒を移動。成りは駒の種類を先に変え、メッシュは演出の中で差し替える
+    if let Ok((_, mut piece, mut transform)) = pieces.get_mut(moving_piece_entity) {
         piece.set_position(to);
         piece.mark_moved(current_turn);
-
-        // 盤面を更新
-        board.set_piece_at(from, None);
-        board.set_piece_at(to, Some(moving_piece_entity));
-
-        // キャプチャされた駒にアニメーションを適用
-        if let Some(captured_entity) = captured_piece {
-            if let Ok((_, _, transform)) = pieces.get(captured_entity) {
-                commands.entity(captured_entity).insert(
-                    PieceAnimation::new_capture(transform.translation, time.elapsed_secs())
-                );
+        if let Some(promotion) = promotion {
+            piece.piece_type = promotion;
+        }
+        if instant {
+            transform.translation = target_pos;
+        }
+    }
+    if let Some(square) = captured_square {
+        board.set_piece_at(square, None);
+    }
+    board.set_piece_at(from, None);
+    board.set_piece_at(to, Some(moving_piece_entity));
+
+    if let Some((rook_entity, rook_from, rook_to)) = castling_rook {
+        board.set_piece_at(rook_from, None);
+        board.set_piece_at(rook_to, Some(rook_entity));
+        if let Ok((_, mut rook, mut transform)) = pieces.get_mut(rook_entity) {
+            rook.set_position(rook_to);
+            rook.mark_moved(current_turn);
+            if instant {
+                transform.translation = rook_to.to_world_position();
+            } else {
+                // キングが着地してから、キングを飛び越える
+                tweens.cancel_entity(rook_entity);
+                tweens.start(TweenNode::sequence([
+                    Tween::delay(PIECE_MOVE_DURATION),
+                    Tween::new(
+                        rook_entity,
+                        TweenAction::Path {
+                            from: Some(transform.translation),
+                            to: rook_to.to_world_position(),
+                            path: MovePath::Arc { height: KNIGHT_ARC_HEIGHT },
+                        },
+                        PIECE_MOVE_DURATION,
+                    ),
+                ]));
             }
         }
+    }
 
-        Ok(captured_piece)
-    } else {
-        Err("Invalid piece entity".to_string())
+    if instant {
+        if let Some(captured_entity) = captured_piece {
+            let piece = pieces.get(captured_entity).ok().map(|(_, piece, _)| piece);
+            send_to_graveyard(commands, captured_entity, piece);
+        }
+        if let Some(promotion) = promotion {
+            tweens.start(Tween::new(moving_piece_entity, TweenAction::Promote(promotion), 0.0));
+        }
+        return Ok(captured_piece);
+    }
+
+    let path = MovePath::for_piece(piece_type);
+    let mut move_delay = 0.0;
+
+    // キャプチャされた駒にアニメーションを適用
+    if let Some(captured_entity) = captured_piece
+        && let Ok((_, _, transform)) = pieces.get(captured_entity)
+    {
+        let capture_pos = transform.translation;
+        let (capture, impact) = if matches!(path, MovePath::Arc { .. }) {
+            // 跳ぶ駒は相手が沈むのを待ってから着地する
+            move_delay = PIECE_CAPTURE_DURATION * 0.5;
+            let capture = TweenNode::sequence([
+                Tween::new(
+                    captured_entity,
+                    TweenAction::Translation { from: Some(capture_pos), to: capture_pos - Vec3::Y * 2.0 },
+                    PIECE_CAPTURE_DURATION,
+                ),
+                Tween::new(captured_entity, TweenAction::ToGraveyard, 0.0),
+            ]);
+            (capture, move_delay + PIECE_MOVE_DURATION)
+        } else {
+            // 滑る駒は到着した時点で相手を弾き飛ばす
+            let direction = Vec3::new(target_pos.x - start_pos.x, 0.0, target_pos.z - start_pos.z).normalize_or_zero();
+            let contact = PIECE_MOVE_DURATION * 0.75;
+            commands.entity(captured_entity).insert(KnockedAside {
+                direction,
+                delay: contact / settings.animation_speed.max(MIN_ANIMATION_SPEED),
+            });
+            let capture = TweenNode::sequence([
+                Tween::delay(contact),
+                Tween::new(
+                    captured_entity,
+                    TweenAction::Path {
+                        from: Some(capture_pos),
+                        to: capture_pos + direction * CAPTURE_KNOCK_DISTANCE - Vec3::Y * 2.0,
+                        path: MovePath::KnockAside { direction },
+                    },
+                    PIECE_CAPTURE_DURATION * 1.5,
+                ),
+                Tween::new(captured_entity, TweenAction::ToGraveyard, 0.0),
+            ]);
+            (capture, contact)
+        };
+        tweens.cancel_entity(captured_entity);
+        tweens.start(capture);
+        // 衝突の瞬間に爆発させる
+        tweens.start(TweenNode::sequence([Tween::delay(impact).into(), capture_burst(capture_pos)]));
+    }
+
+    // アニメーションを開始。成る駒は着地してから変身する
+    let mut choreography = vec![
+        Tween::delay(move_delay).into(),
+        Tween::new(
+            moving_piece_entity,
+            TweenAction::Path { from: Some(start_pos), to: target_pos, path },
+            PIECE_MOVE_DURATION,
+        )
+        .into(),
+    ];
+    if let Some(promotion) = promotion {
+        choreography.push(promotion_tweens(moving_piece_entity, target_pos, promotion, PIECE_PROMOTION_DURATION));
     }
-}
\ No newline at end of file
+    tweens.cancel_entity(moving_piece_entity);
+    tweens.start(TweenNode::Sequence(choreography));
+
+    Ok(captured_piece)
+}
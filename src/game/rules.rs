@@ -217,4 +217,174 @@ pub fn is_stalemate(
     }
 
     true
-}
\ No newline at end of file
+}
+
+/// Whether the piece on `from` attacks `to`, regardless of what stands on `to`.
+/// Unlike move legality, pawns attack diagonally even onto empty or friendly squares,
+/// which is what defence and square control need.
+pub fn attacks_square(
+    piece: &ChessPiece,
+    from: BoardPosition,
+    to: BoardPosition,
+    board: &crate::game::ChessBoard,
+) -> bool {
+    if from == to {
+        return false;
+    }
+
+    match piece.piece_type {
+        PieceType::Pawn => {
+            let direction = if piece.color == PieceColor::White { 1 } else { -1 };
+            let file_diff = (to.file as i8 - from.file as i8).abs();
+            file_diff == 1 && to.rank as i8 - from.rank as i8 == direction
+        }
+        PieceType::Rook => is_legal_rook_move(from, to, board),
+        PieceType::Knight => is_legal_knight_move(from, to),
+        PieceType::Bishop => is_legal_bishop_move(from, to, board),
+        PieceType::Queen => is_legal_queen_move(from, to, board),
+        PieceType::King => is_legal_king_move(from, to),
+    }
+}
+
+/// Positions of `color` pieces attacking `square`.
+pub fn attackers_of(
+    square: BoardPosition,
+    color: PieceColor,
+    board: &crate::game::ChessBoard,
+    pieces: &Query<&ChessPiece>,
+) -> Vec<BoardPosition> {
+    board.get_pieces_by_color(color, pieces)
+        .into_iter()
+        .filter(|(pos, entity)| {
+            pieces.get(*entity)
+                .map(|piece| attacks_square(piece, *pos, square, board))
+                .unwrap_or(false)
+        })
+        .map(|(pos, _)| pos)
+        .collect()
+}
+
+/// How many pieces of each side attack every square.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AttackMap {
+    white: [[u8; 8]; 8],
+    black: [[u8; 8]; 8],
+}
+
+impl AttackMap {
+    pub fn compute(board: &crate::game::ChessBoard, pieces: &Query<&ChessPiece>) -> Self {
+        let mut map = Self::default();
+
+        for (from, entity) in board.get_all_pieces() {
+            let Ok(piece) = pieces.get(entity) else {
+                continue;
+            };
+            let counts = match piece.color {
+                PieceColor::White => &mut map.white,
+                PieceColor::Black => &mut map.black,
+            };
+
+            for rank in 0..8 {
+                for file in 0..8 {
+                    let to = BoardPosition { file, rank };
+                    if attacks_square(piece, from, to, board) {
+                        counts[rank as usize][file as usize] += 1;
+                    }
+                }
+            }
+        }
+
+        map
+    }
+
+    pub fn count(&self, pos: BoardPosition, color: PieceColor) -> u8 {
+        if !pos.is_valid() {
+            return 0;
+        }
+        match color {
+            PieceColor::White => self.white[pos.rank as usize][pos.file as usize],
+            PieceColor::Black => self.black[pos.rank as usize][pos.file as usize],
+        }
+    }
+
+    pub fn is_attacked_by(&self, pos: BoardPosition, color: PieceColor) -> bool {
+        self.count(pos, color) > 0
+    }
+}
+
+/// Pieces attacked by the opponent and defended by nobody. Kings are never hanging.
+pub fn hanging_pieces(
+    board: &crate::game::ChessBoard,
+    pieces: &Query<&ChessPiece>,
+    attack_map: &AttackMap,
+) -> Vec<BoardPosition> {
+    board.get_all_pieces()
+        .into_iter()
+        .filter_map(|(pos, entity)| {
+            let piece = pieces.get(entity).ok()?;
+            let hanging = piece.piece_type != PieceType::King
+                && attack_map.is_attacked_by(pos, piece.color.opposite())
+                && !attack_map.is_attacked_by(pos, piece.color);
+            hanging.then_some(pos)
+        })
+        .collect()
+}
+
+/// A piece that cannot leave the line between its king and an enemy slider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pin {
+    pub pinned: BoardPosition,
+    pub pinner: BoardPosition,
+    pub king: BoardPosition,
+}
+
+/// Absolute pins against the `color` king.
+pub fn pinned_pieces(
+    color: PieceColor,
+    board: &crate::game::ChessBoard,
+    pieces: &Query<&ChessPiece>,
+) -> Vec<Pin> {
+    const DIRECTIONS: [(i8, i8); 8] = [
+        (1, 0), (-1, 0), (0, 1), (0, -1),
+        (1, 1), (1, -1), (-1, 1), (-1, -1),
+    ];
+
+    let Some(king) = board.find_king(color, pieces) else {
+        return Vec::new();
+    };
+
+    let mut pins = Vec::new();
+    for (file_delta, rank_delta) in DIRECTIONS {
+        let diagonal = file_delta != 0 && rank_delta != 0;
+        let mut own_piece = None;
+        let mut current = king;
+
+        while let Some(next) = current.offset(file_delta, rank_delta) {
+            current = next;
+            let Some(piece) = board.get_piece_at(current).and_then(|entity| pieces.get(entity).ok()) else {
+                continue;
+            };
+
+            if piece.color == color {
+                if own_piece.is_some() {
+                    break; // two own pieces shield the king
+                }
+                own_piece = Some(current);
+                continue;
+            }
+
+            let slides_this_way = match piece.piece_type {
+                PieceType::Queen => true,
+                PieceType::Rook => !diagonal,
+                PieceType::Bishop => diagonal,
+                _ => false,
+            };
+            if let (Some(pinned), true) = (own_piece, slides_this_way) {
+                pins.push(Pin { pinned, pinner: current, king });
+            }
+            break;
+        }
+    }
+
+    pins
+}
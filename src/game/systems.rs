@@ -39,9 +39,10 @@ pub fn check_game_end_conditions() { todo!() }
 // TODO: 合法手を更新すること
 pub fn update_legal_moves() { todo!() }
 
-pub fn update_piece_positions(
-    mut pieces: Query<(&mut Transform, &ChessPiece), Without<crate::game::pieces::PieceAnimation>>,
-) {
+/// Pieces no tween or physics body is moving right now.
+type AtRest = (Without<crate::graphics::Tweening>, Without<crate::graphics::RigidBody>);
+
+pub fn update_piece_positions(mut pieces: Query<(&mut Transform, &ChessPiece), AtRest>) {
     for (mut transform, piece) in pieces.iter_mut() {
         let target_position = piece.position.to_world_position();
         transform.translation = target_position;
@@ -51,14 +52,6 @@ pub fn update_piece_positions(
 // TODO: 盤面のハイライトを更新すること
 pub fn update_board_highlights() { todo!() }
 
-pub fn animate_pieces(
-    commands: Commands,
-    pieces: Query<(Entity, &mut Transform, &crate::game::pieces::PieceAnimation)>,
-    time: Res<Time>,
-) {
-    crate::game::pieces::animate_pieces(commands, pieces, time);
-}
-
 // TODO: ゲームUIを更新すること
 pub fn update_game_ui() { todo!() }
 // TODO: 手履歴の表示を更新すること
@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+
+use crate::{
+    core::{
+        constants::*,
+        events::{GameAction, GameActionEvent},
+        resources::GameSettings,
+    },
+    game::board::ChessBoard,
+    graphics::{BoardEntity, ChessMaterials, ChessMeshes},
+};
+
+#[derive(Component)]
+pub struct BoardFrame;
+
+/// A file (a-h) or rank (1-8) label on the board frame.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct CoordinateLabel {
+    pub text: char,
+}
+
+/// Spawns the frame and a row of labels along each of its four edges.
+pub fn spawn_board_frame(commands: &mut Commands, meshes: &ChessMeshes, materials: &ChessMaterials) {
+    commands.spawn((
+        Mesh3d(meshes.board_frame.clone()),
+        MeshMaterial3d(materials.board_frame.clone()),
+        Transform::IDENTITY,
+        BoardFrame,
+        BoardEntity,
+        Name::new("Board Frame"),
+    ));
+
+    let edge = BOARD_SIZE * SQUARE_SIZE / 2.0 + BOARD_FRAME_WIDTH / 2.0;
+    let y = BOARD_FRAME_TOP;
+
+    for i in 0..8 {
+        let offset = (i as f32 - 3.5) * SQUARE_SIZE;
+        let file = (b'a' + i as u8) as char;
+        let rank = (b'1' + i as u8) as char;
+
+        for z in [-edge, edge] {
+            spawn_label(commands, meshes.file_labels[i].clone(), materials, file, Vec3::new(offset, y, z));
+        }
+        for x in [-edge, edge] {
+            spawn_label(commands, meshes.rank_labels[i].clone(), materials, rank, Vec3::new(x, y, offset));
+        }
+    }
+}
+
+fn spawn_label(
+    commands: &mut Commands,
+    mesh: Handle<Mesh>,
+    materials: &ChessMaterials,
+    text: char,
+    position: Vec3,
+) {
+    commands.spawn((
+        Mesh3d(mesh),
+        MeshMaterial3d(materials.coordinate_label.clone()),
+        Transform::from_translation(position).with_rotation(label_rotation(false)),
+        Visibility::Hidden,
+        CoordinateLabel { text },
+        BoardEntity,
+        Name::new(format!("Coordinate {}", text)),
+    ));
+}
+
+/// Labels read upright from White's side (rank 1 at -Z), or from Black's side when flipped.
+fn label_rotation(is_flipped: bool) -> Quat {
+    if is_flipped {
+        Quat::IDENTITY
+    } else {
+        Quat::from_rotation_y(std::f32::consts::PI)
+    }
+}
+
+pub fn handle_coordinate_actions(
+    mut events: EventReader<GameActionEvent>,
+    mut settings: ResMut<GameSettings>,
+) {
+    for event in events.read() {
+        if let GameAction::ToggleCoordinates = event.action {
+            settings.show_coordinates = !settings.show_coordinates;
+        }
+    }
+}
+
+pub fn update_coordinate_labels(
+    settings: Res<GameSettings>,
+    board: Res<ChessBoard>,
+    added: Query<(), Added<CoordinateLabel>>,
+    mut labels: Query<(&mut Transform, &mut Visibility), With<CoordinateLabel>>,
+) {
+    if !settings.is_changed() && !board.is_changed() && added.is_empty() {
+        return;
+    }
+
+    let rotation = label_rotation(board.is_flipped);
+    let visibility = if settings.show_coordinates {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+
+    for (mut transform, mut label_visibility) in labels.iter_mut() {
+        if transform.rotation != rotation {
+            transform.rotation = rotation;
+        }
+        label_visibility.set_if_neq(visibility);
+    }
+}
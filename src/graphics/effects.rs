@@ -8,6 +8,7 @@ pub struct EffectSettings {
     pub enable_glow: bool,
     pub enable_shadows: bool,
     pub particle_density: f32,
+    pub enable_physics: bool,
 }
 
 #[derive(Component)]
@@ -118,7 +119,7 @@ pub struct ParticleEffect {
     pub start_time: f32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ParticleType {
     Sparkle,
     Smoke,
@@ -132,34 +133,404 @@ pub fn handle_graphics_settings(
     mut lighting_settings: ResMut<crate::graphics::LightingSettings>,
 ) {
     if settings.is_changed() {
-        match settings.graphics_quality {
-            crate::core::resources::GraphicsQuality::Low => {
-                effect_settings.enable_particles = false;
-                effect_settings.enable_glow = false;
-                effect_settings.particle_density = 0.3;
-                lighting_settings.enable_shadows = false;
-            },
-            crate::core::resources::GraphicsQuality::Medium => {
-                effect_settings.enable_particles = true;
-                effect_settings.enable_glow = false;
-                effect_settings.particle_density = 0.6;
-                lighting_settings.enable_shadows = true;
-                lighting_settings.shadow_quality = 0.5;
-            },
-            crate::core::resources::GraphicsQuality::High => {
-                effect_settings.enable_particles = true;
-                effect_settings.enable_glow = true;
-                effect_settings.particle_density = 1.0;
-                lighting_settings.enable_shadows = true;
-                lighting_settings.shadow_quality = 1.0;
-            },
-            crate::core::resources::GraphicsQuality::Ultra => {
-                effect_settings.enable_particles = true;
-                effect_settings.enable_glow = true;
-                effect_settings.particle_density = 1.5;
-                lighting_settings.enable_shadows = true;
-                lighting_settings.shadow_quality = 2.0;
-            },
+        let quality = settings.graphics_quality;
+
+        effect_settings.enable_particles = quality.particles_enabled();
+        effect_settings.enable_glow = quality.bloom_enabled();
+        effect_settings.enable_shadows = quality.shadows_enabled();
+        effect_settings.particle_density = quality.particle_density();
+        effect_settings.enable_physics = settings.physics_effects && quality != crate::core::resources::GraphicsQuality::Low;
+        lighting_settings.enable_shadows = quality.shadows_enabled();
+        lighting_settings.shadow_quality = quality.shadow_quality();
+    }
+}
+
+/// Piece under the mouse cursor.
+#[derive(Component)]
+pub struct Hovered;
+
+/// Shared material of an entity whose material was cloned so effects stay local to it.
+/// The entity's own copy is kept in `MaterialHandle`.
+#[derive(Component)]
+pub struct SharedMaterial(pub Handle<StandardMaterial>);
+
+/// Inverted-hull child rendered behind a piece.
+#[derive(Component)]
+pub struct PieceOutline {
+    pub color: Color,
+    pub thickness: f32,
+}
+
+#[derive(Component)]
+pub struct OutlineLink(pub Entity);
+
+#[derive(Component)]
+pub struct BlobShadow {
+    pub piece: Entity,
+    pub offset: Vec3,
+    pub opacity: f32,
+}
+
+#[derive(Component)]
+pub struct BlobShadowLink(pub Entity);
+
+#[derive(Resource, Default)]
+pub struct PieceEffectAssets {
+    pub blob_mesh: Handle<Mesh>,
+    pub blob_texture: Handle<Image>,
+}
+
+pub fn setup_piece_effect_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    commands.insert_resource(PieceEffectAssets {
+        blob_mesh: meshes.add(Plane3d::default().mesh().size(1.0, 1.0)),
+        blob_texture: images.add(crate::graphics::generate_blob_shadow_texture(64)),
+    });
+}
+
+/// Marks the piece on the square under the cursor with `Hovered`.
+pub fn update_hovered_piece(
+    mut commands: Commands,
+    windows: Query<&Window, With<bevy::window::PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    board: Res<crate::game::board::ChessBoard>,
+    hovered: Query<Entity, With<Hovered>>,
+) {
+    use crate::core::constants::*;
+
+    let target = (|| {
+        let cursor = windows.single().ok()?.cursor_position()?;
+        let (camera, camera_transform) = cameras.iter().find(|(camera, _)| camera.is_active)?;
+        let ray = camera.viewport_to_world(camera_transform, cursor).ok()?;
+        let distance = ray.intersect_plane(Vec3::Y * BOARD_THICKNESS, InfinitePlane3d::new(Vec3::Y))?;
+        let point = ray.get_point(distance);
+
+        let half_board = BOARD_SIZE * SQUARE_SIZE / 2.0;
+        if point.x.abs() > half_board || point.z.abs() > half_board {
+            return None;
+        }
+        let position = crate::game::board::BoardPosition::from_world_position(point)?;
+        board.get_piece_at(position)
+    })();
+
+    for entity in hovered.iter() {
+        if Some(entity) != target {
+            commands.entity(entity).remove::<Hovered>();
+        }
+    }
+    if let Some(entity) = target
+        && !hovered.contains(entity)
+    {
+        commands.entity(entity).try_insert(Hovered);
+    }
+}
+
+/// Glow on the selected piece, pulse on a king in check.
+pub fn update_piece_effect_triggers(
+    mut commands: Commands,
+    game_state: Res<crate::game::state::GameStateResource>,
+    pieces: Query<(
+        Entity,
+        &crate::game::pieces::ChessPiece,
+        Has<crate::game::pieces::Selected>,
+        Option<&crate::game::pieces::PieceEffect>,
+    )>,
+    time: Res<Time>,
+) {
+    use crate::{
+        core::constants::*,
+        game::{pieces::{PieceEffect, PieceEffectType, PieceType}, state::CheckStatus},
+    };
+
+    let checked_king = match game_state.check_status {
+        CheckStatus::Check(color) | CheckStatus::Checkmate(color) => Some(color),
+        CheckStatus::None => None,
+    };
+
+    for (entity, piece, is_selected, current) in pieces.iter() {
+        let in_check = piece.piece_type == PieceType::King && checked_king == Some(piece.color);
+        let desired = if in_check {
+            Some(PieceEffectType::Pulse { color: CHECK_COLOR, frequency: CHECK_PULSE_FREQUENCY })
+        } else if is_selected {
+            Some(PieceEffectType::Glow { color: SELECTED_COLOR })
+        } else {
+            None
+        };
+
+        // Timed effects and other effect types are left to whoever inserted them
+        let current_is_managed = current.is_some_and(|effect| {
+            effect.duration.is_none()
+                && matches!(effect.effect_type, PieceEffectType::Glow { .. } | PieceEffectType::Pulse { .. })
+        });
+
+        match desired {
+            Some(effect_type) => {
+                let unchanged = current.is_some_and(|effect| {
+                    std::mem::discriminant(&effect.effect_type) == std::mem::discriminant(&effect_type)
+                });
+                if !unchanged && (current.is_none() || current_is_managed) {
+                    commands.entity(entity).insert(PieceEffect {
+                        effect_type,
+                        intensity: 1.0,
+                        start_time: time.elapsed_secs(),
+                        duration: None,
+                    });
+                }
+            }
+            None if current_is_managed => {
+                commands.entity(entity).remove::<PieceEffect>();
+            }
+            None => {}
+        }
+    }
+}
+
+/// Entities with an effect that changes their material, still on the shared one.
+type NeedsOwnMaterial = (
+    Or<(
+        With<crate::game::pieces::PieceEffect>,
+        With<HighlightEffect>,
+        With<crate::graphics::AnimatedMaterial>,
+    )>,
+    Without<SharedMaterial>,
+);
+
+type WithoutMaterialEffect = (
+    Without<crate::game::pieces::PieceEffect>,
+    Without<HighlightEffect>,
+    Without<crate::graphics::AnimatedMaterial>,
+);
+
+/// Gives entities with a material effect their own copy of the material.
+pub fn isolate_effect_materials(
+    mut commands: Commands,
+    mut entities: Query<(Entity, &mut MeshMaterial3d<StandardMaterial>), NeedsOwnMaterial>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (entity, mut material) in entities.iter_mut() {
+        let Some(copy) = materials.get(&material.0).cloned() else {
+            continue;
+        };
+        let shared = std::mem::replace(&mut material.0, materials.add(copy));
+        commands.entity(entity).insert((SharedMaterial(shared), MaterialHandle(material.0.clone())));
+    }
+}
+
+/// Puts the shared material back once every effect is gone; the copy is dropped with its handle.
+pub fn restore_shared_materials(
+    mut commands: Commands,
+    mut entities: Query<(Entity, &SharedMaterial, &mut MeshMaterial3d<StandardMaterial>), WithoutMaterialEffect>,
+) {
+    for (entity, shared, mut material) in entities.iter_mut() {
+        material.0 = shared.0.clone();
+        commands.entity(entity).remove::<(SharedMaterial, MaterialHandle)>();
+    }
+}
+
+pub fn update_piece_effects(
+    mut commands: Commands,
+    effects: Query<(Entity, &crate::game::pieces::PieceEffect, &MaterialHandle, &SharedMaterial)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    effect_settings: Res<EffectSettings>,
+    time: Res<Time>,
+) {
+    use crate::{core::constants::*, game::pieces::PieceEffectType};
+
+    let now = time.elapsed_secs();
+
+    for (entity, effect, own, shared) in effects.iter() {
+        let elapsed = now - effect.start_time;
+        if effect.duration.is_some_and(|duration| elapsed >= duration) {
+            commands.entity(entity).remove::<crate::game::pieces::PieceEffect>();
+            continue;
+        }
+
+        // Re-derive from the shared material so theme changes still come through
+        let Some((base_color, base_emissive)) = materials.get(&shared.0).map(|m| (m.base_color, m.emissive)) else {
+            continue;
+        };
+        let Some(material) = materials.get_mut(&own.0) else {
+            continue;
+        };
+        material.base_color = base_color;
+        material.emissive = base_emissive;
+
+        // Without bloom a strong emissive just flattens the piece, so keep it subtle
+        let strength = if effect_settings.enable_glow { GLOW_INTENSITY } else { 0.4 };
+
+        match effect.effect_type {
+            PieceEffectType::Glow { color } => {
+                material.emissive = base_emissive + scale_color_linear(color, effect.intensity * strength);
+            }
+            PieceEffectType::Pulse { color, frequency } => {
+                let pulse = (elapsed * frequency * std::f32::consts::TAU).sin() * 0.5 + 0.5;
+                material.base_color = lerp_color(base_color, color, 0.35 * pulse);
+                material.emissive =
+                    base_emissive + scale_color_linear(color, effect.intensity * strength * (0.2 + 0.8 * pulse));
+            }
+            PieceEffectType::Sparkle { .. } | PieceEffectType::Outline { .. } | PieceEffectType::Shadow { .. } => {}
+        }
+    }
+}
+
+/// Inverted-hull shell scaled about the middle of the mesh, so it surrounds the piece
+/// evenly instead of growing upwards from the base.
+fn outline_transform(mesh: Option<&Mesh>, thickness: f32) -> Transform {
+    use bevy::render::mesh::MeshAabb;
+
+    let scale = 1.0 + thickness;
+    let center = mesh.and_then(MeshAabb::compute_aabb).map_or(Vec3::ZERO, |aabb| Vec3::from(aabb.center));
+    Transform::from_translation(center * (1.0 - scale)).with_scale(Vec3::splat(scale))
+}
+
+type OutlinedPiece<'a> = (
+    Entity,
+    &'a Mesh3d,
+    Has<Hovered>,
+    Option<&'a crate::game::pieces::PieceEffect>,
+    Option<&'a OutlineLink>,
+);
+
+/// Outline for the hovered piece or a `PieceEffectType::Outline`.
+pub fn update_piece_outlines(
+    mut commands: Commands,
+    pieces: Query<OutlinedPiece, With<crate::game::pieces::ChessPiece>>,
+    mut outlines: Query<(&mut PieceOutline, &mut Mesh3d, &mut Transform), Without<crate::game::pieces::ChessPiece>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut outline_materials: Query<&MeshMaterial3d<StandardMaterial>, With<PieceOutline>>,
+    meshes: Res<Assets<Mesh>>,
+) {
+    use crate::{core::constants::*, game::pieces::PieceEffectType};
+
+    for (entity, mesh, is_hovered, effect, link) in pieces.iter() {
+        let desired = match effect.map(|effect| &effect.effect_type) {
+            Some(PieceEffectType::Outline { color, thickness }) => Some((*color, *thickness)),
+            _ if is_hovered => Some((OUTLINE_COLOR, OUTLINE_THICKNESS)),
+            _ => None,
+        };
+
+        match (desired, link) {
+            (Some((color, thickness)), None) => {
+                let outline = commands.spawn((
+                    Mesh3d(mesh.0.clone()),
+                    MeshMaterial3d(materials.add(StandardMaterial {
+                        base_color: color,
+                        unlit: true,
+                        cull_mode: Some(bevy::render::render_resource::Face::Front),
+                        ..default()
+                    })),
+                    outline_transform(meshes.get(&mesh.0), thickness),
+                    bevy::pbr::NotShadowCaster,
+                    PieceOutline { color, thickness },
+                    Name::new("Piece Outline"),
+                )).id();
+                commands.entity(entity).add_child(outline).insert(OutlineLink(outline));
+            }
+            (Some((color, thickness)), Some(link)) => {
+                let Ok((mut outline, mut outline_mesh, mut transform)) = outlines.get_mut(link.0) else {
+                    commands.entity(entity).remove::<OutlineLink>();
+                    continue;
+                };
+                if outline_mesh.0 != mesh.0 || outline.thickness != thickness {
+                    outline_mesh.0 = mesh.0.clone();
+                    outline.thickness = thickness;
+                    *transform = outline_transform(meshes.get(&mesh.0), thickness);
+                }
+                if outline.color != color {
+                    outline.color = color;
+                    let material = outline_materials.get_mut(link.0).ok().and_then(|handle| materials.get_mut(&handle.0));
+                    if let Some(material) = material {
+                        material.base_color = color;
+                    }
+                }
+            }
+            (None, Some(link)) => {
+                commands.entity(link.0).despawn();
+                commands.entity(entity).remove::<OutlineLink>();
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+type ShadowedPiece<'a> = (
+    Entity,
+    &'a Transform,
+    Option<&'a crate::game::pieces::PieceEffect>,
+    Option<&'a BlobShadowLink>,
+);
+
+/// Soft contact shadows under the pieces while real shadow maps are off.
+pub fn update_blob_shadows(
+    mut commands: Commands,
+    pieces: Query<ShadowedPiece, With<crate::game::pieces::ChessPiece>>,
+    mut blobs: Query<(Entity, &mut BlobShadow, &mut Transform, &MeshMaterial3d<StandardMaterial>), Without<crate::game::pieces::ChessPiece>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    assets: Res<PieceEffectAssets>,
+    lighting: Res<crate::graphics::LightingSettings>,
+    effect_settings: Res<EffectSettings>,
+) {
+    use crate::{core::constants::*, game::pieces::PieceEffectType};
+
+    let real_shadows = lighting.enable_shadows && effect_settings.enable_shadows;
+
+    for (entity, _, effect, link) in pieces.iter() {
+        let forced = match effect.map(|effect| &effect.effect_type) {
+            Some(PieceEffectType::Shadow { offset, opacity }) => Some((*offset, *opacity)),
+            _ => None,
+        };
+        let desired = forced.or((!real_shadows).then_some((Vec3::ZERO, BLOB_SHADOW_OPACITY)));
+
+        match (desired, link) {
+            (Some((offset, opacity)), None) => {
+                let blob = commands.spawn((
+                    Mesh3d(assets.blob_mesh.clone()),
+                    MeshMaterial3d(materials.add(StandardMaterial {
+                        base_color: Color::BLACK.with_alpha(opacity),
+                        base_color_texture: Some(assets.blob_texture.clone()),
+                        unlit: true,
+                        alpha_mode: AlphaMode::Blend,
+                        ..default()
+                    })),
+                    Transform::from_scale(Vec3::splat(BLOB_SHADOW_SIZE)),
+                    bevy::pbr::NotShadowCaster,
+                    BlobShadow { piece: entity, offset, opacity },
+                    Name::new("Blob Shadow"),
+                )).id();
+                commands.entity(entity).insert(BlobShadowLink(blob));
+            }
+            (Some((offset, opacity)), Some(link)) => {
+                if let Ok((_, mut blob, _, _)) = blobs.get_mut(link.0) {
+                    blob.offset = offset;
+                    blob.opacity = opacity;
+                }
+            }
+            (None, Some(link)) => {
+                commands.entity(link.0).despawn();
+                commands.entity(entity).remove::<BlobShadowLink>();
+            }
+            (None, None) => {}
+        }
+    }
+
+    let rest_height = BOARD_THICKNESS / 2.0;
+    for (blob_entity, blob, mut transform, material) in blobs.iter_mut() {
+        let Ok((_, piece_transform, _, _)) = pieces.get(blob.piece) else {
+            commands.entity(blob_entity).despawn();
+            continue;
+        };
+
+        // Lifted pieces cast a wider, fainter shadow
+        let lift = (piece_transform.translation.y - rest_height).max(0.0);
+        let position = piece_transform.translation + blob.offset;
+        transform.translation = Vec3::new(position.x, BOARD_THICKNESS + 0.005, position.z);
+        transform.scale = Vec3::splat(BLOB_SHADOW_SIZE * (1.0 + lift * 0.5));
+
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.base_color = Color::BLACK.with_alpha(blob.opacity / (1.0 + lift * 2.0));
         }
     }
 }
@@ -0,0 +1,276 @@
+//! Captured pieces lined up beside the board, plus the material balance.
+//! Both are derived from the pieces still on the board, so undo, FEN loads and
+//! server resyncs rebuild them without replaying the move history.
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use crate::{
+    core::constants::*,
+    game::{
+        board::ChessBoard,
+        pieces::{ChessPiece, PieceColor, PieceType},
+    },
+    graphics::{ChessAssets, RigidBody, Tween, TweenAction, TweenNode, Tweens},
+};
+
+/// Starting count of every capturable piece type, most valuable first.
+const STARTING_PIECES: [(PieceType, u32); 5] = [
+    (PieceType::Queen, 1),
+    (PieceType::Rook, 2),
+    (PieceType::Bishop, 2),
+    (PieceType::Knight, 2),
+    (PieceType::Pawn, 8),
+];
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct GraveyardPiece {
+    pub piece_type: PieceType,
+    pub color: PieceColor,
+}
+
+/// Material still on the board for each side, kings excluded.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaterialBalance {
+    pub white: u32,
+    pub black: u32,
+}
+
+impl MaterialBalance {
+    /// Positive when White is ahead.
+    pub fn advantage(&self) -> i32 {
+        self.white as i32 - self.black as i32
+    }
+
+    pub fn label(&self) -> String {
+        match self.advantage() {
+            0 => "Material =".to_string(),
+            diff if diff > 0 => format!("White +{}", diff),
+            diff => format!("Black +{}", -diff),
+        }
+    }
+}
+
+/// Row index a graveyard piece was last sent to.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GraveyardSlot(pub usize);
+
+#[derive(Component)]
+pub struct MaterialBalanceText;
+
+/// Pieces of `color` missing from `on_board`, most valuable first.
+/// Promoted pieces beyond the starting count are paid for with pawns.
+pub fn captured_pieces(on_board: &[(PieceType, PieceColor)], color: PieceColor) -> Vec<PieceType> {
+    let count = |piece_type: PieceType| {
+        on_board
+            .iter()
+            .filter(|(on_board_type, on_board_color)| *on_board_type == piece_type && *on_board_color == color)
+            .count() as u32
+    };
+
+    let promoted: u32 = STARTING_PIECES
+        .iter()
+        .filter(|(piece_type, _)| *piece_type != PieceType::Pawn)
+        .map(|(piece_type, start)| count(*piece_type).saturating_sub(*start))
+        .sum();
+
+    let mut captured = Vec::new();
+    for (piece_type, start) in STARTING_PIECES {
+        let missing = if piece_type == PieceType::Pawn {
+            start.saturating_sub(count(piece_type) + promoted)
+        } else {
+            start.saturating_sub(count(piece_type))
+        };
+        captured.extend(std::iter::repeat_n(piece_type, missing as usize));
+    }
+    captured
+}
+
+/// World position of the `index`-th captured piece of `color`.
+/// White's losses sit on the -X side of the board, Black's on +X, in columns of eight.
+pub fn graveyard_slot(color: PieceColor, index: usize) -> Vec3 {
+    let side = match color {
+        PieceColor::White => -1.0,
+        PieceColor::Black => 1.0,
+    };
+    let column = (index / 8) as f32;
+    let row = (index % 8) as f32;
+
+    Vec3::new(
+        side * (GRAVEYARD_OFFSET + column * GRAVEYARD_SPACING),
+        BOARD_THICKNESS / 2.0,
+        (row - 3.5) * GRAVEYARD_SPACING * -side,
+    )
+}
+
+/// Same facing as pieces on the board.
+fn upright(color: PieceColor) -> Quat {
+    match color {
+        PieceColor::White => Quat::IDENTITY,
+        PieceColor::Black => Quat::from_rotation_y(std::f32::consts::PI),
+    }
+}
+
+pub fn setup_material_balance_display(mut commands: Commands) {
+    commands.spawn((
+        Text::new(MaterialBalance::default().label()),
+        TextColor(Color::WHITE),
+        TextFont {
+            font_size: 18.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(10.0),
+            ..default()
+        },
+        MaterialBalanceText,
+    ));
+}
+
+/// Pieces in the graveyard, and whether any came or went since the last frame.
+#[derive(SystemParam)]
+pub struct GraveyardPieces<'w, 's> {
+    pieces: Query<'w, 's, (Entity, &'static GraveyardPiece, Option<&'static GraveyardSlot>)>,
+    added: Query<'w, 's, (), Added<GraveyardPiece>>,
+    removed: RemovedComponents<'w, 's, GraveyardPiece>,
+}
+
+impl GraveyardPieces<'_, '_> {
+    fn changed(&mut self) -> bool {
+        !self.added.is_empty() || self.removed.read().count() > 0
+    }
+}
+
+/// Keeps the graveyard in line with the board: spawns pieces that are missing
+/// (e.g. after a resync), removes ones that came back (undo) and tidies the rows.
+pub fn update_graveyard(
+    mut commands: Commands,
+    board: Res<ChessBoard>,
+    pieces: Query<(Entity, &ChessPiece, Has<RigidBody>)>,
+    mut graveyard: GraveyardPieces,
+    mut balance: ResMut<MaterialBalance>,
+    mut tweens: ResMut<Tweens>,
+    assets: ChessAssets,
+) {
+    if !board.is_changed() && !graveyard.changed() {
+        return;
+    }
+
+    let board_entities: Vec<Entity> = board.get_all_pieces().into_iter().map(|(_, entity)| entity).collect();
+    let on_board: Vec<(PieceType, PieceColor)> = board_entities
+        .iter()
+        .filter_map(|entity| pieces.get(*entity).ok())
+        .map(|(_, piece, _)| (piece.piece_type, piece.color))
+        .collect();
+
+    let material = |color: PieceColor| {
+        on_board
+            .iter()
+            .filter(|(_, piece_color)| *piece_color == color)
+            .map(|(piece_type, _)| piece_type.value())
+            .sum()
+    };
+    let new_balance = MaterialBalance {
+        white: material(PieceColor::White),
+        black: material(PieceColor::Black),
+    };
+    if *balance != new_balance {
+        *balance = new_balance;
+    }
+
+    for color in [PieceColor::White, PieceColor::Black] {
+        let captured = captured_pieces(&on_board, color);
+        let mut slots: Vec<(Option<Entity>, PieceType)> = Vec::new();
+
+        for (piece_type, _) in STARTING_PIECES {
+            let needed = captured.iter().filter(|captured_type| **captured_type == piece_type).count();
+            // Captured pieces still playing their capture animation will arrive on their own
+            let in_flight = pieces
+                .iter()
+                .filter(|(entity, piece, falling)| {
+                    piece.piece_type == piece_type
+                        && piece.color == color
+                        && !board_entities.contains(entity)
+                        && (tweens.is_animating(*entity) || *falling)
+                })
+                .count();
+            let mut resting: Vec<Entity> = graveyard
+                .pieces
+                .iter()
+                .filter(|(_, piece, _)| piece.piece_type == piece_type && piece.color == color)
+                .map(|(entity, _, _)| entity)
+                .collect();
+            resting.sort();
+
+            let keep = needed.saturating_sub(in_flight).min(resting.len());
+            for entity in resting.drain(keep..) {
+                commands.entity(entity).despawn();
+            }
+            slots.extend(resting.into_iter().map(|entity| (Some(entity), piece_type)));
+            for _ in keep + in_flight..needed {
+                slots.push((None, piece_type));
+            }
+        }
+
+        for (index, (entity, piece_type)) in slots.into_iter().enumerate() {
+            let slot = graveyard_slot(color, index);
+            match entity {
+                Some(entity) => {
+                    let current = graveyard.pieces.get(entity).ok().and_then(|(_, _, current)| current.copied());
+                    if current == Some(GraveyardSlot(index)) {
+                        continue;
+                    }
+                    commands.entity(entity).insert(GraveyardSlot(index));
+                    tweens.cancel_entity(entity);
+                    tweens.start(TweenNode::parallel([
+                        Tween::new(entity, TweenAction::Translation { from: None, to: slot }, GRAVEYARD_MOVE_DURATION),
+                        Tween::new(
+                            entity,
+                            TweenAction::Scale { from: None, to: Vec3::splat(GRAVEYARD_PIECE_SCALE) },
+                            GRAVEYARD_MOVE_DURATION,
+                        ),
+                        // Knocked-over pieces are stood back up
+                        Tween::new(entity, TweenAction::Rotation { from: None, to: upright(color) }, GRAVEYARD_MOVE_DURATION),
+                    ]));
+                }
+                None => {
+                    commands.spawn((
+                        Mesh3d(assets.meshes.get_piece_mesh(piece_type)),
+                        MeshMaterial3d(assets.materials.get_piece_material(color)),
+                        Transform::from_translation(slot)
+                            .with_rotation(upright(color))
+                            .with_scale(Vec3::splat(GRAVEYARD_PIECE_SCALE)),
+                        GraveyardPiece { piece_type, color },
+                        GraveyardSlot(index),
+                        Name::new(format!("Captured {} {}", color.to_string(), piece_type.name())),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+pub fn update_material_balance_display(
+    balance: Res<MaterialBalance>,
+    mut texts: Query<&mut Text, With<MaterialBalanceText>>,
+) {
+    if !balance.is_changed() {
+        return;
+    }
+    for mut text in texts.iter_mut() {
+        text.0 = balance.label();
+    }
+}
+
+type GraveyardEntity = Or<(With<GraveyardPiece>, With<MaterialBalanceText>)>;
+
+pub fn cleanup_graveyard(
+    mut commands: Commands,
+    entities: Query<Entity, GraveyardEntity>,
+    mut balance: ResMut<MaterialBalance>,
+) {
+    for entity in entities.iter() {
+        commands.entity(entity).despawn();
+    }
+    *balance = MaterialBalance::default();
+}
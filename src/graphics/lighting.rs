@@ -1,118 +1,323 @@
-use bevy::prelude::*;
-use crate::core::constants::*;
+use bevy::{core_pipeline::Skybox, prelude::*};
+use crate::core::{constants::*, resources::GameSettings};
+use crate::graphics::MainCamera;
+
+/// Overall look of the scene. Each mood fills in every field of `LightingSettings`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum LightingMood {
+    #[default]
+    TournamentHall,
+    Night,
+    Sunset,
+}
+
+impl LightingMood {
+    pub const ALL: [LightingMood; 3] = [LightingMood::TournamentHall, LightingMood::Night, LightingMood::Sunset];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LightingMood::TournamentHall => "tournament_hall",
+            LightingMood::Night => "night",
+            LightingMood::Sunset => "sunset",
+        }
+    }
+}
 
 #[derive(Resource)]
 pub struct LightingSettings {
+    pub mood: LightingMood,
+    pub ambient_color: Color,
     pub ambient_intensity: f32,
+    /// Key light
     pub directional_intensity: f32,
     pub directional_direction: Vec3,
+    pub key_color: Color,
+    pub fill_intensity: f32,
+    pub fill_direction: Vec3,
+    pub fill_color: Color,
+    pub rim_intensity: f32,
+    pub rim_direction: Vec3,
+    pub rim_color: Color,
+    /// Relative flicker of the key light, 0 for a steady light.
+    pub flicker_amount: f32,
+    pub background_color: Color,
     pub enable_shadows: bool,
     pub shadow_quality: f32,
+    /// Use `environment/<mood>_{diffuse,specular}.ktx2` when present.
+    pub enable_environment_map: bool,
+    pub show_skybox: bool,
 }
 
 impl Default for LightingSettings {
     fn default() -> Self {
-        Self {
+        let mut settings = Self {
+            mood: LightingMood::TournamentHall,
+            ambient_color: Color::WHITE,
             ambient_intensity: AMBIENT_LIGHT_STRENGTH,
             directional_intensity: DIRECTIONAL_LIGHT_STRENGTH,
             directional_direction: Vec3::new(-0.5, -1.0, -0.5).normalize(),
+            key_color: Color::WHITE,
+            fill_intensity: 0.3,
+            fill_direction: Vec3::new(0.5, -0.6, 0.5).normalize(),
+            fill_color: Color::srgb(0.9, 0.9, 1.0),
+            rim_intensity: 0.2,
+            rim_direction: Vec3::new(0.3, -0.4, 1.0).normalize(),
+            rim_color: Color::srgb(1.0, 0.9, 0.8),
+            flicker_amount: 0.0,
+            background_color: Color::srgb(0.08, 0.08, 0.12),
             enable_shadows: true,
             shadow_quality: 1.0,
+            enable_environment_map: true,
+            show_skybox: false,
+        };
+        settings.apply_mood(LightingMood::TournamentHall);
+        settings
+    }
+}
+
+impl LightingSettings {
+    /// Overwrites colours, intensities and directions with the mood's preset.
+    /// Shadow and environment toggles are left alone.
+    pub fn apply_mood(&mut self, mood: LightingMood) {
+        self.mood = mood;
+        match mood {
+            LightingMood::TournamentHall => {
+                // Even overhead lighting, as under hall ceiling panels
+                self.ambient_color = Color::WHITE;
+                self.ambient_intensity = AMBIENT_LIGHT_STRENGTH;
+                self.directional_intensity = DIRECTIONAL_LIGHT_STRENGTH;
+                self.directional_direction = Vec3::new(-0.3, -1.0, -0.4).normalize();
+                self.key_color = Color::srgb(1.0, 0.98, 0.95);
+                self.fill_intensity = 0.35;
+                self.fill_direction = Vec3::new(0.6, -0.7, 0.4).normalize();
+                self.fill_color = Color::srgb(0.92, 0.95, 1.0);
+                self.rim_intensity = 0.2;
+                self.rim_direction = Vec3::new(0.2, -0.4, 1.0).normalize();
+                self.rim_color = Color::WHITE;
+                self.flicker_amount = 0.0;
+                self.background_color = Color::srgb(0.1, 0.1, 0.15);
+            }
+            LightingMood::Night => {
+                self.ambient_color = Color::srgb(0.4, 0.5, 0.8);
+                self.ambient_intensity = 0.08;
+                self.directional_intensity = 0.35;
+                self.directional_direction = Vec3::new(-0.4, -1.0, 0.2).normalize();
+                self.key_color = Color::srgb(1.0, 0.8, 0.55);
+                self.fill_intensity = 0.08;
+                self.fill_direction = Vec3::new(0.7, -0.5, -0.3).normalize();
+                self.fill_color = Color::srgb(0.5, 0.6, 1.0);
+                self.rim_intensity = 0.3;
+                self.rim_direction = Vec3::new(0.0, -0.3, 1.0).normalize();
+                self.rim_color = Color::srgb(0.6, 0.7, 1.0);
+                // Lamp-like breathing of the key light
+                self.flicker_amount = 0.03;
+                self.background_color = Color::srgb(0.02, 0.02, 0.05);
+            }
+            LightingMood::Sunset => {
+                self.ambient_color = Color::srgb(1.0, 0.75, 0.6);
+                self.ambient_intensity = 0.2;
+                self.directional_intensity = 0.6;
+                self.directional_direction = Vec3::new(-1.0, -0.35, -0.2).normalize();
+                self.key_color = Color::srgb(1.0, 0.6, 0.35);
+                self.fill_intensity = 0.15;
+                self.fill_direction = Vec3::new(0.8, -0.5, 0.3).normalize();
+                self.fill_color = Color::srgb(0.6, 0.5, 0.9);
+                self.rim_intensity = 0.35;
+                self.rim_direction = Vec3::new(0.9, -0.2, 0.4).normalize();
+                self.rim_color = Color::srgb(1.0, 0.5, 0.3);
+                self.flicker_amount = 0.0;
+                self.background_color = Color::srgb(0.25, 0.13, 0.1);
+            }
         }
     }
 }
 
+/// Key light; the only light that casts shadows.
+#[derive(Component)]
+pub struct MainLight;
+
+#[derive(Component)]
+pub struct FillLight;
+
+#[derive(Component)]
+pub struct RimLight;
+
+#[derive(Component)]
+pub struct DynamicLight {
+    pub base_intensity: f32,
+    pub flicker_speed: f32,
+    pub flicker_amount: f32,
+}
+
+fn light_transform(direction: Vec3) -> Transform {
+    Transform::IDENTITY.looking_to(direction, Vec3::Y)
+}
+
 pub fn setup_lighting(
     mut commands: Commands,
     lighting_settings: Res<LightingSettings>,
 ) {
     // Environment lighting
     commands.insert_resource(AmbientLight {
-        color: Color::WHITE,
-        brightness: lighting_settings.ambient_intensity,
+        color: lighting_settings.ambient_color,
+        brightness: lighting_settings.ambient_intensity * AMBIENT_BRIGHTNESS_SCALE,
         affects_lightmapped_meshes: true,
     });
 
-    // Sunshine
+    // Key light
     commands.spawn((
         DirectionalLight {
-            color: Color::WHITE,
-            illuminance: lighting_settings.directional_intensity * 10_000.0,
+            color: lighting_settings.key_color,
+            illuminance: lighting_settings.directional_intensity * DIRECTIONAL_ILLUMINANCE_SCALE,
             shadows_enabled: lighting_settings.enable_shadows,
             ..default()
         },
-        Transform::from_rotation(Quat::from_rotation_x(DIRECTIONAL_LIGHT_ANGLE.to_radians()))
-            .looking_at(Vec3::ZERO, Vec3::Y),
-        GlobalTransform::default(),
-        Visibility::Visible,
-        InheritedVisibility::VISIBLE,
-        ViewVisibility::default(),
-        Name::new("Directional Light"),
+        light_transform(lighting_settings.directional_direction),
+        DynamicLight {
+            base_intensity: lighting_settings.directional_intensity * DIRECTIONAL_ILLUMINANCE_SCALE,
+            flicker_speed: 0.5,
+            flicker_amount: lighting_settings.flicker_amount,
+        },
+        Name::new("Key Light"),
         MainLight,
     ));
-    
 
     // Fill light
     commands.spawn((
         DirectionalLight {
-            color: Color::srgb(0.9, 0.9, 1.0),
-            illuminance: lighting_settings.directional_intensity * 3000.0,
+            color: lighting_settings.fill_color,
+            illuminance: lighting_settings.fill_intensity * DIRECTIONAL_ILLUMINANCE_SCALE,
             shadows_enabled: false,
             ..default()
         },
-        Transform::from_rotation(Quat::from_rotation_x((-30.0_f32).to_radians()))
-            .with_rotation(Quat::from_rotation_y(45.0_f32.to_radians())),
-        GlobalTransform::default(),
-        Visibility::Visible,
-        InheritedVisibility::VISIBLE,
-        ViewVisibility::default(),
-        Name::new("Fill Light")
+        light_transform(lighting_settings.fill_direction),
+        Name::new("Fill Light"),
+        FillLight,
     ));
 
     // Rim light
     commands.spawn((
         DirectionalLight {
-            color: Color::srgb(1.0, 0.9, 0.8),
-            illuminance: lighting_settings.directional_intensity * 2000.0,
+            color: lighting_settings.rim_color,
+            illuminance: lighting_settings.rim_intensity * DIRECTIONAL_ILLUMINANCE_SCALE,
             shadows_enabled: false,
             ..default()
         },
-        Transform::from_rotation(Quat::from_rotation_x(30.0_f32.to_radians()))
-            .with_rotation(Quat::from_rotation_y((-135.0_f32).to_radians())),
-        GlobalTransform::default(),
-        Visibility::Visible,
-        InheritedVisibility::VISIBLE,
-        ViewVisibility::default(),
-        Name::new("Rim Light")
+        light_transform(lighting_settings.rim_direction),
+        Name::new("Rim Light"),
+        RimLight,
     ));
 }
 
+/// Keeps `LightingSettings::mood` in line with `GameSettings::lighting_mood`.
+pub fn sync_lighting_mood(
+    settings: Res<GameSettings>,
+    mut lighting_settings: ResMut<LightingSettings>,
+) {
+    if settings.is_changed() && lighting_settings.mood != settings.lighting_mood {
+        lighting_settings.apply_mood(settings.lighting_mood);
+    }
+}
+
+type LightMut<'a> = (&'a mut DirectionalLight, &'a mut Transform);
+type RimOnly = (With<RimLight>, Without<MainLight>, Without<FillLight>);
+
 pub fn update_lighting(
-    mut lights: Query<&mut DirectionalLight, With<MainLight>>,
+    mut commands: Commands,
+    mut key_lights: Query<(&mut DirectionalLight, &mut Transform, &mut DynamicLight), With<MainLight>>,
+    mut fill_lights: Query<LightMut, (With<FillLight>, Without<MainLight>)>,
+    mut rim_lights: Query<LightMut, RimOnly>,
     lighting_settings: Res<LightingSettings>,
-    time: Res<Time>,
 ) {
-    if lighting_settings.is_changed() {
-        for mut light in lights.iter_mut() {
-            light.illuminance = lighting_settings.directional_intensity * 10000.0;
-            light.shadows_enabled = lighting_settings.enable_shadows;
-        }
+    if !lighting_settings.is_changed() {
+        return;
+    }
+
+    commands.insert_resource(AmbientLight {
+        color: lighting_settings.ambient_color,
+        brightness: lighting_settings.ambient_intensity * AMBIENT_BRIGHTNESS_SCALE,
+        affects_lightmapped_meshes: true,
+    });
+
+    // Flicker is applied by `update_dynamic_lights` on top of the base intensity
+    for (mut light, mut transform, mut dynamic) in key_lights.iter_mut() {
+        dynamic.base_intensity = lighting_settings.directional_intensity * DIRECTIONAL_ILLUMINANCE_SCALE;
+        dynamic.flicker_amount = lighting_settings.flicker_amount;
+        light.illuminance = dynamic.base_intensity;
+        light.color = lighting_settings.key_color;
+        light.shadows_enabled = lighting_settings.enable_shadows;
+        *transform = light_transform(lighting_settings.directional_direction);
+    }
+
+    for (mut light, mut transform) in fill_lights.iter_mut() {
+        light.illuminance = lighting_settings.fill_intensity * DIRECTIONAL_ILLUMINANCE_SCALE;
+        light.color = lighting_settings.fill_color;
+        *transform = light_transform(lighting_settings.fill_direction);
     }
 
-    let flicker = (time.elapsed_secs() * 0.5).sin() * 0.02 + 1.0;
-    for mut light in lights.iter_mut() {
-        light.illuminance *= flicker;
+    for (mut light, mut transform) in rim_lights.iter_mut() {
+        light.illuminance = lighting_settings.rim_intensity * DIRECTIONAL_ILLUMINANCE_SCALE;
+        light.color = lighting_settings.rim_color;
+        *transform = light_transform(lighting_settings.rim_direction);
     }
 }
 
-#[derive(Component)]
-pub struct MainLight;
+fn environment_map_paths(mood: LightingMood) -> Option<(String, String)> {
+    let diffuse = format!("{}{}_diffuse.ktx2", asset_paths::ENVIRONMENT_DIR, mood.name());
+    let specular = format!("{}{}_specular.ktx2", asset_paths::ENVIRONMENT_DIR, mood.name());
+    (asset_paths::on_disk(&diffuse).is_file() && asset_paths::on_disk(&specular).is_file()).then_some((diffuse, specular))
+}
 
-#[derive(Component)]
-pub struct DynamicLight {
-    pub base_intensity: f32,
-    pub flicker_speed: f32,
-    pub flicker_amount: f32,
+/// Image-based lighting and skybox for the current mood, with a flat background colour as fallback.
+pub fn update_environment(
+    mut commands: Commands,
+    lighting_settings: Res<LightingSettings>,
+    cameras: Query<(Entity, Ref<MainCamera>)>,
+    asset_server: Res<AssetServer>,
+    mut clear_color: ResMut<ClearColor>,
+) {
+    let camera_added = cameras.iter().any(|(_, marker)| marker.is_added());
+    if !lighting_settings.is_changed() && !camera_added {
+        return;
+    }
+
+    let environment = lighting_settings
+        .enable_environment_map
+        .then(|| environment_map_paths(lighting_settings.mood))
+        .flatten();
+
+    let mut show_background = true;
+    for (camera, _) in cameras.iter() {
+        let mut camera = commands.entity(camera);
+        match &environment {
+            Some((diffuse, specular)) => {
+                let specular_map: Handle<Image> = asset_server.load(specular.clone());
+                camera.insert(EnvironmentMapLight {
+                    diffuse_map: asset_server.load(diffuse.clone()),
+                    specular_map: specular_map.clone(),
+                    intensity: lighting_settings.ambient_intensity * ENVIRONMENT_MAP_INTENSITY_SCALE,
+                    ..default()
+                });
+                if lighting_settings.show_skybox {
+                    camera.insert(Skybox {
+                        image: specular_map,
+                        brightness: ENVIRONMENT_MAP_INTENSITY_SCALE,
+                        ..default()
+                    });
+                    show_background = false;
+                } else {
+                    camera.remove::<Skybox>();
+                }
+            }
+            None => {
+                camera.remove::<(EnvironmentMapLight, Skybox)>();
+            }
+        }
+    }
+
+    if show_background {
+        clear_color.0 = lighting_settings.background_color;
+    }
 }
 
 pub fn update_dynamic_lights(
@@ -122,6 +327,12 @@ pub fn update_dynamic_lights(
     let time_seconds = time.elapsed_secs();
 
     for (mut light, dynamic) in lights.iter_mut() {
+        if dynamic.flicker_amount == 0.0 {
+            if light.illuminance != dynamic.base_intensity {
+                light.illuminance = dynamic.base_intensity;
+            }
+            continue;
+        }
         let flicker = (time_seconds * dynamic.flicker_speed).sin() * dynamic.flicker_amount;
         light.illuminance = dynamic.base_intensity * (1.0 + flicker);
     }
@@ -8,6 +8,8 @@ pub struct ChessMaterials {
     pub black_piece: Handle<StandardMaterial>,
     pub light_square: Handle<StandardMaterial>,
     pub dark_square: Handle<StandardMaterial>,
+    pub board_frame: Handle<StandardMaterial>,
+    pub coordinate_label: Handle<StandardMaterial>,
     pub selected: Handle<StandardMaterial>,
     pub legal_move: Handle<StandardMaterial>,
     pub last_move: Handle<StandardMaterial>,
@@ -23,6 +25,8 @@ impl Default for ChessMaterials {
             black_piece: Handle::default(),
             light_square: Handle::default(),
             dark_square: Handle::default(),
+            board_frame: Handle::default(),
+            coordinate_label: Handle::default(),
             selected: Handle::default(),
             legal_move: Handle::default(),
             last_move: Handle::default(),
@@ -67,6 +71,20 @@ impl ChessMaterials {
             ..default()
         });
 
+        self.board_frame = materials.add(StandardMaterial {
+            base_color: BOARD_FRAME_COLOR,
+            perceptual_roughness: 0.6,
+            metallic: 0.0,
+            ..default()
+        });
+
+        self.coordinate_label = materials.add(StandardMaterial {
+            base_color: COORDINATE_LABEL_COLOR,
+            perceptual_roughness: 0.9,
+            metallic: 0.0,
+            ..default()
+        });
+
         // Highlight
         self.selected = materials.add(StandardMaterial {
             base_color: SELECTED_COLOR,
@@ -127,31 +145,380 @@ impl ChessMaterials {
     }
 }
 
-pub fn animate_materials(
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    chess_materials: Res<ChessMaterials>,
-    time: Res<Time>,
-) {
-    let time_seconds = time.elapsed_secs();
+/// Emissive the check square pulses towards, from its current colour.
+pub fn check_pulse_color(check: &StandardMaterial) -> Color {
+    scale_color_linear(check.base_color, 0.9).into()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MaterialAnimationId(u64);
 
-    if let Some(check_material) = materials.get_mut(&chess_materials.check) {
-        let pulse = (time_seconds * 3.0).sin() * 0.5 + 0.5;
-        check_material.emissive = scale_color_linear(CHECK_COLOR, 0.3 + pulse * 0.4);
+/// What a material animation writes to: a material asset directly, or an entity's own copy of its material.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MaterialTarget {
+    Handle(Handle<StandardMaterial>),
+    Entity(Entity),
+}
+
+impl From<Handle<StandardMaterial>> for MaterialTarget {
+    fn from(handle: Handle<StandardMaterial>) -> Self {
+        MaterialTarget::Handle(handle)
     }
 }
 
+impl From<Entity> for MaterialTarget {
+    fn from(entity: Entity) -> Self {
+        MaterialTarget::Entity(entity)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaterialChannel {
+    Emissive,
+    BaseColor,
+    /// Alpha of the base colour; switches the material to blending while below 1.
+    Alpha,
+}
+
+/// Blend between the material's original value and the animation colour over time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MaterialCurve {
+    /// Loops until stopped.
+    Pulse { frequency: f32 },
+    /// Ends on the animation colour and keeps it.
+    FadeIn { duration: f32 },
+    /// Starts on the animation colour and ends on the original value.
+    FadeOut { duration: f32 },
+    /// `count` sharp flashes, each decaying back to the original value.
+    Flash { count: u32, duration: f32 },
+}
+
+impl MaterialCurve {
+    pub fn duration(&self) -> Option<f32> {
+        match *self {
+            MaterialCurve::Pulse { .. } => None,
+            MaterialCurve::FadeIn { duration }
+            | MaterialCurve::FadeOut { duration }
+            | MaterialCurve::Flash { duration, .. } => Some(duration),
+        }
+    }
+
+    /// 0 = original value, 1 = animation colour.
+    pub fn weight(&self, elapsed: f32) -> f32 {
+        let progress = self
+            .duration()
+            .map_or(0.0, |duration| (elapsed / duration.max(f32::EPSILON)).clamp(0.0, 1.0));
+
+        match *self {
+            MaterialCurve::Pulse { frequency } => {
+                0.5 - (elapsed * frequency * std::f32::consts::TAU).cos() * 0.5
+            }
+            MaterialCurve::FadeIn { .. } => ease_in_out_cubic(progress),
+            MaterialCurve::FadeOut { .. } => 1.0 - ease_in_out_cubic(progress),
+            MaterialCurve::Flash { count, .. } => {
+                if progress >= 1.0 {
+                    0.0
+                } else {
+                    let phase = (progress * count.max(1) as f32).fract();
+                    (1.0 - phase) * (1.0 - phase)
+                }
+            }
+        }
+    }
+
+    fn holds_target(&self) -> bool {
+        matches!(self, MaterialCurve::FadeIn { .. })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct MaterialSnapshot {
+    base_color: Color,
+    emissive: LinearRgba,
+    alpha_mode: AlphaMode,
+}
+
+impl MaterialSnapshot {
+    fn capture(material: &StandardMaterial) -> Self {
+        Self {
+            base_color: material.base_color,
+            emissive: material.emissive,
+            alpha_mode: material.alpha_mode,
+        }
+    }
+}
+
+impl MaterialChannel {
+    fn apply(self, material: &mut StandardMaterial, original: &MaterialSnapshot, color: Color, weight: f32) {
+        match self {
+            MaterialChannel::Emissive => {
+                material.emissive = original.emissive.mix(&color.to_linear(), weight);
+            }
+            MaterialChannel::BaseColor => {
+                material.base_color = lerp_color(original.base_color, color, weight);
+            }
+            MaterialChannel::Alpha => {
+                let alpha = original.base_color.alpha() + (color.alpha() - original.base_color.alpha()) * weight;
+                material.base_color.set_alpha(alpha);
+                material.alpha_mode = if alpha < 1.0 && original.alpha_mode == AlphaMode::Opaque {
+                    AlphaMode::Blend
+                } else {
+                    original.alpha_mode
+                };
+            }
+        }
+    }
+
+    fn restore(self, material: &mut StandardMaterial, original: &MaterialSnapshot) {
+        match self {
+            MaterialChannel::Emissive => material.emissive = original.emissive,
+            MaterialChannel::BaseColor => material.base_color = original.base_color,
+            MaterialChannel::Alpha => {
+                material.base_color.set_alpha(original.base_color.alpha());
+                material.alpha_mode = original.alpha_mode;
+            }
+        }
+    }
+}
+
+pub struct MaterialAnimation {
+    pub target: MaterialTarget,
+    pub channel: MaterialChannel,
+    pub curve: MaterialCurve,
+    pub color: Color,
+    pub elapsed: f32,
+    /// Resting value, captured the first time the animation touches the material.
+    original: Option<MaterialSnapshot>,
+    stopped: bool,
+}
+
+/// Sent when an animation ends on its own (`cancelled == false`) or is stopped.
+#[derive(Event, Debug, Clone)]
+pub struct MaterialAnimationFinished {
+    pub id: MaterialAnimationId,
+    pub target: MaterialTarget,
+    pub cancelled: bool,
+}
+
+/// Marks an entity whose own material copy is being animated by `MaterialAnimations`.
+#[derive(Component)]
+pub struct AnimatedMaterial;
+
 #[derive(Resource, Default)]
 pub struct MaterialAnimations {
-    /// handle, base_color, frequency
-    pub pulsing_materials: Vec<(Handle<StandardMaterial>, Color, f32)>,
+    animations: Vec<(MaterialAnimationId, MaterialAnimation)>,
+    next_id: u64,
 }
 
 impl MaterialAnimations {
-    pub fn add_pulsing(&mut self, material: Handle<StandardMaterial>, color: Color, frequency: f32) {
-        self.pulsing_materials.push((material, color, frequency));
+    /// A new animation replaces any running one on the same target and channel,
+    /// taking over its original value so the material still ends where it started.
+    pub fn add(
+        &mut self,
+        target: impl Into<MaterialTarget>,
+        channel: MaterialChannel,
+        curve: MaterialCurve,
+        color: Color,
+    ) -> MaterialAnimationId {
+        let target = target.into();
+        let mut original = None;
+        self.animations.retain(|(_, animation)| {
+            let replaced = animation.target == target && animation.channel == channel && !animation.stopped;
+            if replaced {
+                original = animation.original;
+            }
+            !replaced
+        });
+
+        let id = MaterialAnimationId(self.next_id);
+        self.next_id += 1;
+        self.animations.push((id, MaterialAnimation {
+            target,
+            channel,
+            curve,
+            color,
+            elapsed: 0.0,
+            original,
+            stopped: false,
+        }));
+        id
+    }
+
+    pub fn add_pulsing(&mut self, material: Handle<StandardMaterial>, color: Color, frequency: f32) -> MaterialAnimationId {
+        self.add(material, MaterialChannel::Emissive, MaterialCurve::Pulse { frequency }, color)
+    }
+
+    pub fn flash(&mut self, target: impl Into<MaterialTarget>, color: Color, count: u32, duration: f32) -> MaterialAnimationId {
+        self.add(target, MaterialChannel::Emissive, MaterialCurve::Flash { count, duration }, color)
+    }
+
+    pub fn fade_in(&mut self, target: impl Into<MaterialTarget>, channel: MaterialChannel, color: Color, duration: f32) -> MaterialAnimationId {
+        self.add(target, channel, MaterialCurve::FadeIn { duration }, color)
+    }
+
+    pub fn fade_out(&mut self, target: impl Into<MaterialTarget>, channel: MaterialChannel, color: Color, duration: f32) -> MaterialAnimationId {
+        self.add(target, channel, MaterialCurve::FadeOut { duration }, color)
+    }
+
+    pub fn get(&self, id: MaterialAnimationId) -> Option<&MaterialAnimation> {
+        self.animations
+            .iter()
+            .find(|(animation_id, _)| *animation_id == id)
+            .map(|(_, animation)| animation)
+    }
+
+    pub fn is_running(&self, id: MaterialAnimationId) -> bool {
+        self.get(id).is_some_and(|animation| !animation.stopped)
+    }
+
+    /// The material is restored on the next update.
+    pub fn stop(&mut self, id: MaterialAnimationId) {
+        for (animation_id, animation) in self.animations.iter_mut() {
+            if *animation_id == id {
+                animation.stopped = true;
+            }
+        }
+    }
+
+    /// Changes the colour running animations on `target` blend towards.
+    pub fn recolor(&mut self, target: &MaterialTarget, color: Color) {
+        for (_, animation) in self.animations.iter_mut() {
+            if animation.target == *target {
+                animation.color = color;
+            }
+        }
+    }
+
+    pub fn stop_target(&mut self, target: &MaterialTarget) {
+        for (_, animation) in self.animations.iter_mut() {
+            if animation.target == *target {
+                animation.stopped = true;
+            }
+        }
+    }
+
+    /// Forget captured originals of handle targets, e.g. after a theme rewrote the materials.
+    pub fn rebase(&mut self) {
+        for (_, animation) in self.animations.iter_mut() {
+            if matches!(animation.target, MaterialTarget::Handle(_)) {
+                animation.original = None;
+            }
+        }
     }
 
     pub fn clear(&mut self) {
-        self.pulsing_materials.clear();
+        for (_, animation) in self.animations.iter_mut() {
+            animation.stopped = true;
+        }
+    }
+}
+
+pub fn animate_materials(
+    mut commands: Commands,
+    mut animations: ResMut<MaterialAnimations>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    entity_materials: Query<Option<&crate::graphics::MaterialHandle>>,
+    mut finished_events: EventWriter<MaterialAnimationFinished>,
+    time: Res<Time>,
+) {
+    if animations.animations.is_empty() {
+        return;
+    }
+
+    let delta = time.delta_secs();
+    let mut finished = Vec::new();
+
+    for (id, animation) in animations.animations.iter_mut() {
+        let handle = match &animation.target {
+            MaterialTarget::Handle(handle) => Some(handle.clone()),
+            MaterialTarget::Entity(entity) => match entity_materials.get(*entity) {
+                Ok(Some(material)) => Some(material.0.clone()),
+                // The copy is made by `isolate_effect_materials`; wait for it
+                Ok(None) => {
+                    commands.entity(*entity).try_insert(AnimatedMaterial);
+                    None
+                }
+                Err(_) => {
+                    finished.push((*id, true));
+                    continue;
+                }
+            },
+        };
+
+        let Some(handle) = handle else {
+            if animation.stopped {
+                finished.push((*id, true));
+            }
+            continue;
+        };
+        // The material asset was removed from under the animation
+        let Some(material) = materials.get_mut(&handle) else {
+            finished.push((*id, true));
+            continue;
+        };
+
+        let original = *animation.original.get_or_insert_with(|| MaterialSnapshot::capture(material));
+
+        if animation.stopped {
+            animation.channel.restore(material, &original);
+            finished.push((*id, true));
+            continue;
+        }
+
+        animation.elapsed += delta;
+        let done = animation.curve.duration().is_some_and(|duration| animation.elapsed >= duration);
+        if done && !animation.curve.holds_target() {
+            animation.channel.restore(material, &original);
+        } else {
+            let weight = if done { 1.0 } else { animation.curve.weight(animation.elapsed) };
+            animation.channel.apply(material, &original, animation.color, weight);
+        }
+        if done {
+            finished.push((*id, false));
+        }
+    }
+
+    for (id, cancelled) in finished {
+        let Some(index) = animations.animations.iter().position(|(animation_id, _)| *animation_id == id) else {
+            continue;
+        };
+        let (_, animation) = animations.animations.remove(index);
+
+        if let MaterialTarget::Entity(entity) = animation.target {
+            let still_animated = animations
+                .animations
+                .iter()
+                .any(|(_, other)| other.target == MaterialTarget::Entity(entity));
+            if !still_animated {
+                commands.entity(entity).try_remove::<AnimatedMaterial>();
+            }
+        }
+
+        finished_events.write(MaterialAnimationFinished {
+            id,
+            target: animation.target,
+            cancelled,
+        });
+    }
+}
+
+/// Flash the board square at `position`; the square gets its own material copy for the duration.
+#[derive(Event, Debug, Clone)]
+pub struct FlashSquareEvent {
+    pub position: crate::game::board::BoardPosition,
+    pub color: Color,
+    pub count: u32,
+    pub duration: f32,
+}
+
+pub fn flash_squares(
+    mut events: EventReader<FlashSquareEvent>,
+    squares: Query<(Entity, &crate::graphics::BoardSquare)>,
+    mut animations: ResMut<MaterialAnimations>,
+) {
+    for event in events.read() {
+        if let Some((entity, _)) = squares.iter().find(|(_, square)| square.position == event.position) {
+            animations.flash(entity, event.color, event.count, event.duration);
+        }
     }
 }
@@ -1,9 +1,20 @@
-use bevy::{prelude::*, render::mesh::{Indices, VertexAttributeValues}};
-use crate::{core::constants::*, graphics::ChessMaterials};
+use bevy::{ecs::system::SystemParam, prelude::*, render::mesh::{Indices, VertexAttributeValues}};
+use crate::{
+    core::{constants::*, resources::GraphicsQuality},
+    game::pieces::PieceType,
+    graphics::{
+        ChessMaterials, MeshDetail, generate_board_frame_mesh, generate_detailed_piece_mesh, generate_glyph_mesh,
+    },
+};
 
 #[derive(Resource)]
 pub struct ChessMeshes {
     pub board_square: Handle<Mesh>,
+    pub board_frame: Handle<Mesh>,
+    /// Glyphs for a-h
+    pub file_labels: [Handle<Mesh>; 8],
+    /// Glyphs for 1-8
+    pub rank_labels: [Handle<Mesh>; 8],
     pub pawn: Handle<Mesh>,
     pub rook: Handle<Mesh>,
     pub knight: Handle<Mesh>,
@@ -18,6 +29,9 @@ impl Default for ChessMeshes {
     fn default() -> Self {
         Self {
             board_square: Handle::default(),
+            board_frame: Handle::default(),
+            file_labels: Default::default(),
+            rank_labels: Default::default(),
             pawn: Handle::default(),
             rook: Handle::default(),
             knight: Handle::default(),
@@ -31,17 +45,35 @@ impl Default for ChessMeshes {
 }
 
 impl ChessMeshes {
-    pub fn initialize(&mut self, meshes: &mut Assets<Mesh>) {
-        self.board_square = meshes.add(
+    pub fn initialize(&mut self, meshes: &mut Assets<Mesh>, quality: GraphicsQuality) {
+        self.board_square = meshes.add(with_tangents(
             Mesh::from(Cuboid::new(SQUARE_SIZE, BOARD_THICKNESS, SQUARE_SIZE))
-        );
+        ));
+        self.board_frame = meshes.add(with_tangents(generate_board_frame_mesh(
+            BOARD_SIZE * SQUARE_SIZE / 2.0,
+            BOARD_FRAME_WIDTH,
+            0.0,
+            BOARD_FRAME_TOP,
+        )));
+
+        for (i, (file_label, rank_label)) in self.file_labels.iter_mut().zip(&mut self.rank_labels).enumerate() {
+            let glyph = |ch: u8| {
+                generate_glyph_mesh(ch as char, COORDINATE_LABEL_SIZE, COORDINATE_LABEL_STROKE, COORDINATE_LABEL_DEPTH)
+            };
+            if let Some(mesh) = glyph(b'a' + i as u8) {
+                *file_label = meshes.add(mesh);
+            }
+            if let Some(mesh) = glyph(b'1' + i as u8) {
+                *rank_label = meshes.add(mesh);
+            }
+        }
 
-        self.pawn = meshes.add(generate_pawn_mesh());
-        self.rook = meshes.add(generate_rook_mesh());
-        self.knight = meshes.add(generate_knight_mesh());
-        self.bishop = meshes.add(generate_bishop_mesh());
-        self.queen = meshes.add(generate_queen_mesh());
-        self.king = meshes.add(generate_king_mesh());
+        self.pawn = meshes.add(generate_piece_mesh(PieceType::Pawn, quality));
+        self.rook = meshes.add(generate_piece_mesh(PieceType::Rook, quality));
+        self.knight = meshes.add(generate_piece_mesh(PieceType::Knight, quality));
+        self.bishop = meshes.add(generate_piece_mesh(PieceType::Bishop, quality));
+        self.queen = meshes.add(generate_piece_mesh(PieceType::Queen, quality));
+        self.king = meshes.add(generate_piece_mesh(PieceType::King, quality));
 
         self.highlight_circle = meshes.add(
             Mesh::from(Cylinder::new(SQUARE_SIZE * 0.4, 0.05))
@@ -52,6 +84,17 @@ impl ChessMeshes {
         );
     }
 
+    pub fn set_piece_mesh(&mut self, piece_type: PieceType, mesh: Handle<Mesh>) {
+        match piece_type {
+            PieceType::Pawn => self.pawn = mesh,
+            PieceType::Rook => self.rook = mesh,
+            PieceType::Knight => self.knight = mesh,
+            PieceType::Bishop => self.bishop = mesh,
+            PieceType::Queen => self.queen = mesh,
+            PieceType::King => self.king = mesh,
+        }
+    }
+
     pub fn get_piece_mesh(&self, piece_type: crate::game::pieces::PieceType) -> Handle<Mesh> {
         match piece_type {
             crate::game::pieces::PieceType::Pawn => self.pawn.clone(),
@@ -64,6 +107,37 @@ impl ChessMeshes {
     }
 }
 
+/// The shared meshes and materials, for systems that spawn board dressing.
+#[derive(SystemParam)]
+pub struct ChessAssets<'w> {
+    pub meshes: Res<'w, ChessMeshes>,
+    pub materials: Res<'w, ChessMaterials>,
+}
+
+/// Low quality keeps the cheap primitive pieces, everything above uses the lathed Staunton set.
+pub fn generate_piece_mesh(piece_type: PieceType, quality: GraphicsQuality) -> Mesh {
+    if quality == GraphicsQuality::Low {
+        return with_tangents(match piece_type {
+            PieceType::Pawn => generate_pawn_mesh(),
+            PieceType::Rook => generate_rook_mesh(),
+            PieceType::Knight => generate_knight_mesh(),
+            PieceType::Bishop => generate_bishop_mesh(),
+            PieceType::Queen => generate_queen_mesh(),
+            PieceType::King => generate_king_mesh(),
+        });
+    }
+
+    with_tangents(generate_detailed_piece_mesh(piece_type, MeshDetail::from_quality(quality)))
+}
+
+/// Normal-mapped theme materials need tangents; meshes without UVs simply go without.
+pub fn with_tangents(mut mesh: Mesh) -> Mesh {
+    if let Err(err) = mesh.generate_tangents() {
+        debug!("Could not generate tangents: {}", err);
+    }
+    mesh
+}
+
 fn generate_pawn_mesh() -> Mesh {
     let mut mesh = Mesh::from(Sphere::new(PIECE_RADIUS * 0.7).mesh().uv(8, 6));
 
@@ -189,8 +263,9 @@ pub fn spawn_board(
             let world_pos = position.to_world_position();
             let is_light = position.is_light_square();
 
-            // Bevy 0.16.1対応: 基本的なコンポーネントのみを使用
             let entity = commands.spawn((
+                Mesh3d(meshes.board_square.clone()),
+                MeshMaterial3d(materials.get_square_material(is_light)),
                 Transform::from_translation(world_pos),
                 super::BoardSquare { position, is_light },
                 super::BoardEntity,
@@ -200,4 +275,6 @@ pub fn spawn_board(
             board.set_square_entity(position, entity);
         }
     }
+
+    super::spawn_board_frame(commands, meshes, materials);
 }
\ No newline at end of file
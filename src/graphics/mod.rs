@@ -4,6 +4,15 @@ pub mod lighting;
 pub mod effects;
 pub mod animations;
 pub mod procedural;
+pub mod piece_sets;
+pub mod themes;
+pub mod coordinates;
+pub mod particles;
+pub mod quality;
+pub mod tween;
+pub mod graveyard;
+pub mod physics;
+pub mod threats;
 
 use bevy::prelude::*;
 use crate::core::{GameState, CoreSet};
@@ -14,6 +23,15 @@ pub use lighting::*;
 pub use effects::*;
 pub use animations::*;
 pub use procedural::*;
+pub use piece_sets::*;
+pub use themes::*;
+pub use coordinates::*;
+pub use particles::*;
+pub use quality::*;
+pub use tween::*;
+pub use graveyard::*;
+pub use physics::*;
+pub use threats::*;
 
 pub struct GraphicsPlugin;
 
@@ -23,25 +41,103 @@ impl Plugin for GraphicsPlugin {
             // Resources
             .init_resource::<ChessMeshes>()
             .init_resource::<ChessMaterials>()
+            .init_resource::<PieceSetState>()
+            .init_resource::<ProceduralTextures>()
+            .init_resource::<EffectSettings>()
+            .init_resource::<LightingSettings>()
+            .init_resource::<MaterialAnimations>()
+            .init_resource::<Tweens>()
+            .init_resource::<MaterialBalance>()
+            .init_resource::<PhysicsWorld>()
+            .init_resource::<ThreatOverlayAssets>()
+            .add_event::<ParticleBurstEvent>()
+            .add_event::<MaterialAnimationFinished>()
+            .add_event::<FlashSquareEvent>()
+            .add_event::<TweenCompleted>()
 
             // Startup
             .add_systems(Startup, (
                 setup_graphics,
+                setup_camera,
+                setup_lighting,
+                load_theme_library,
+                discover_piece_sets_on_startup,
+                setup_particle_pool,
+                setup_piece_effect_assets,
+                setup_threat_overlay,
             ).chain())
 
             // Update
             .add_systems(Update, (
-                animate_materials,
+                (
+                    handle_animation_events,
+                    update_tweens,
+                    flash_squares,
+                    animate_materials,
+                ).chain(),
+                (
+                    handle_graphics_settings,
+                    apply_graphics_quality,
+                ).chain().before(request_piece_set),
+                (
+                    request_procedural_textures,
+                    finish_procedural_textures,
+                    apply_selected_theme,
+                ).chain(),
+                (
+                    sync_lighting_mood,
+                    update_lighting,
+                    update_environment,
+                    update_dynamic_lights,
+                ).chain().after(handle_graphics_settings),
+                (
+                    handle_coordinate_actions,
+                    update_coordinate_labels,
+                ).chain(),
+                (
+                    request_piece_set,
+                    finish_piece_set_loading,
+                    apply_piece_meshes,
+                ).chain(),
+                (
+                    emit_check_particles,
+                    emit_piece_sparkles,
+                    update_particle_emitters,
+                    spawn_particle_bursts,
+                    update_particles,
+                ).chain().after(handle_graphics_settings),
+                (
+                    update_hovered_piece,
+                    update_piece_effect_triggers,
+                    isolate_effect_materials,
+                    restore_shared_materials,
+                    update_effects,
+                    update_piece_effects,
+                    update_piece_outlines,
+                    update_blob_shadows,
+                ).chain().after(handle_graphics_settings),
             ).in_set(CoreSet::Graphics))
+            .add_systems(Update, (
+                launch_knockoff_bodies,
+                simulate_rigid_bodies,
+                update_graveyard,
+                update_material_balance_display,
+            ).chain().in_set(CoreSet::Graphics).run_if(in_state(GameState::InGame)))
+            .add_systems(Update, (
+                handle_threat_overlay_actions,
+                update_threat_overlay,
+            ).chain().in_set(CoreSet::Graphics).run_if(in_state(GameState::InGame)))
 
             // System when main game started
             .add_systems(OnEnter(GameState::InGame), (
                 setup_board_graphics,
+                setup_material_balance_display,
                 // setup_piece_graphics,
             ).chain())
 
             .add_systems(OnExit(GameState::InGame), (
                 cleanup_game_graphics,
+                cleanup_graveyard,
             ));
     }
 }
@@ -51,16 +147,32 @@ fn setup_graphics(
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut chess_meshes: ResMut<ChessMeshes>,
     mut chess_materials: ResMut<ChessMaterials>,
+    mut material_animations: ResMut<MaterialAnimations>,
+    settings: Res<crate::core::resources::GameSettings>,
 ) {
     info!("Setting up graphics...");
 
-    chess_meshes.initialize(&mut meshes);
+    chess_meshes.initialize(&mut meshes, settings.graphics_quality);
     chess_materials.initialize(&mut materials);
+
+    // 王手のマスは常に脈打つ。色はテーマ適用時に合わせ直す
+    if let Some(check) = materials.get(&chess_materials.check) {
+        material_animations.add_pulsing(
+            chess_materials.check.clone(),
+            check_pulse_color(check),
+            crate::core::constants::CHECK_PULSE_FREQUENCY,
+        );
+    }
 }
 
 fn setup_camera(mut commands: Commands) {
     commands.spawn((
         Camera3d::default(),
+        // Bloom needs an HDR target
+        Camera {
+            hdr: true,
+            ..default()
+        },
         Transform::from_xyz(0.0, 10.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
         GlobalTransform::default(),
         Visibility::Visible,
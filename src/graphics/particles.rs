@@ -0,0 +1,390 @@
+use std::collections::HashMap;
+
+use bevy::{pbr::NotShadowCaster, prelude::*};
+
+use crate::{
+    core::constants::*,
+    game::{
+        pieces::{ChessPiece, PieceEffect, PieceEffectType, PieceType},
+        state::{CheckStatus, GameStateResource},
+    },
+    graphics::{EffectSettings, ParticleEffect, ParticleType, Tween, TweenNode},
+};
+
+/// Request for a one-off burst of particles at a world position.
+#[derive(Event, Debug, Clone)]
+pub struct ParticleBurstEvent {
+    pub particle_type: ParticleType,
+    pub position: Vec3,
+    /// Particle count at density 1.0.
+    pub count: u32,
+}
+
+/// A pooled billboard quad. Dead particles stay spawned but hidden until they are reused.
+/// Particles of a type share one material, so they fade out by shrinking.
+#[derive(Component)]
+pub struct Particle {
+    pub alive: bool,
+    pub particle_type: ParticleType,
+    pub velocity: Vec3,
+    pub age: f32,
+    pub lifetime: f32,
+    pub start_size: f32,
+    pub end_size: f32,
+    pub gravity: f32,
+    pub drag: f32,
+}
+
+#[derive(Resource, Default)]
+pub struct ParticlePool {
+    quad: Handle<Mesh>,
+    materials: HashMap<ParticleType, Handle<StandardMaterial>>,
+    free: Vec<Entity>,
+    spawned: usize,
+    rng_state: u32,
+}
+
+impl ParticlePool {
+    pub fn live_count(&self) -> usize {
+        self.spawned - self.free.len()
+    }
+
+    /// xorshift32, good enough for visual jitter.
+    fn random(&mut self) -> f32 {
+        let mut x = self.rng_state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.rng_state = x;
+        (x >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    fn random_range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.random()
+    }
+
+    fn random_direction(&mut self) -> Vec3 {
+        let theta = self.random() * std::f32::consts::TAU;
+        let z = self.random_range(-1.0, 1.0);
+        let r = (1.0 - z * z).sqrt();
+        Vec3::new(r * theta.cos(), z, r * theta.sin())
+    }
+}
+
+pub fn setup_particle_pool(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let particle_materials = ParticleType::ALL
+        .into_iter()
+        .map(|particle_type| (particle_type, materials.add(particle_type.material())))
+        .collect();
+
+    commands.insert_resource(ParticlePool {
+        quad: meshes.add(Rectangle::new(1.0, 1.0)),
+        materials: particle_materials,
+        free: Vec::with_capacity(MAX_PARTICLES),
+        spawned: 0,
+        rng_state: 0x9E37_79B9,
+    });
+}
+
+/// Particle count multiplier; `EffectSettings` follows the graphics quality preset.
+pub fn particle_density(effect_settings: &EffectSettings) -> f32 {
+    if !effect_settings.enable_particles {
+        return 0.0;
+    }
+    effect_settings.particle_density
+}
+
+impl ParticleType {
+    pub const ALL: [ParticleType; 4] = [
+        ParticleType::Sparkle,
+        ParticleType::Smoke,
+        ParticleType::Magic,
+        ParticleType::Explosion,
+    ];
+
+    fn uses_additive_blending(&self) -> bool {
+        !matches!(self, ParticleType::Smoke)
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            ParticleType::Sparkle => Color::srgb(1.0, 0.9, 0.6),
+            ParticleType::Smoke => Color::srgba(0.4, 0.4, 0.4, 0.4),
+            ParticleType::Magic => Color::srgb(0.5, 0.55, 1.0),
+            ParticleType::Explosion => Color::srgb(1.0, 0.55, 0.2),
+        }
+    }
+
+    fn material(&self) -> StandardMaterial {
+        let color = self.color();
+        StandardMaterial {
+            base_color: color,
+            emissive: color.into(),
+            unlit: true,
+            alpha_mode: if self.uses_additive_blending() {
+                AlphaMode::Add
+            } else {
+                AlphaMode::Blend
+            },
+            ..default()
+        }
+    }
+}
+
+/// Size multiplier over a particle's life: full size, then shrinking away in the last part.
+fn particle_fade(t: f32) -> f32 {
+    const FADE_START: f32 = 0.6;
+    1.0 - ((t - FADE_START) / (1.0 - FADE_START)).clamp(0.0, 1.0).powi(2)
+}
+
+/// Resets `particle` for a new life and returns its spawn offset from the emitter.
+fn init_particle(particle: &mut Particle, particle_type: ParticleType, pool: &mut ParticlePool) -> Vec3 {
+    particle.alive = true;
+    particle.particle_type = particle_type;
+    particle.age = 0.0;
+
+    match particle_type {
+        ParticleType::Sparkle => {
+            particle.velocity = pool.random_direction() * pool.random_range(0.3, 0.8) + Vec3::Y * 0.6;
+            particle.lifetime = pool.random_range(0.6, 1.2);
+            particle.start_size = pool.random_range(0.04, 0.08);
+            particle.end_size = 0.0;
+            particle.gravity = -0.4;
+            particle.drag = 1.5;
+            pool.random_direction() * 0.3
+        }
+        ParticleType::Smoke => {
+            particle.velocity = Vec3::new(pool.random_range(-0.15, 0.15), pool.random_range(0.3, 0.6), pool.random_range(-0.15, 0.15));
+            particle.lifetime = pool.random_range(1.2, 2.0);
+            particle.start_size = pool.random_range(0.12, 0.2);
+            particle.end_size = pool.random_range(0.45, 0.6);
+            particle.gravity = 0.0;
+            particle.drag = 0.8;
+            Vec3::new(pool.random_range(-0.2, 0.2), 0.1, pool.random_range(-0.2, 0.2))
+        }
+        ParticleType::Magic => {
+            let angle = pool.random() * std::f32::consts::TAU;
+            let radial = Vec3::new(angle.cos(), 0.0, angle.sin());
+            // Tangential velocity makes the cloud swirl around the piece while rising
+            particle.velocity = radial.cross(Vec3::Y) * 1.2 + Vec3::Y * pool.random_range(0.8, 1.4);
+            particle.lifetime = pool.random_range(1.0, 1.6);
+            particle.start_size = pool.random_range(0.06, 0.1);
+            particle.end_size = 0.02;
+            particle.gravity = 0.0;
+            particle.drag = 0.6;
+            radial * 0.35
+        }
+        ParticleType::Explosion => {
+            particle.velocity = pool.random_direction() * pool.random_range(1.5, 3.5) + Vec3::Y * 1.5;
+            particle.lifetime = EXPLOSION_DURATION * pool.random_range(0.4, 0.9);
+            particle.start_size = pool.random_range(0.08, 0.14);
+            particle.end_size = 0.02;
+            particle.gravity = -6.0;
+            particle.drag = 1.0;
+            Vec3::ZERO
+        }
+    }
+}
+
+pub fn spawn_particle_bursts(
+    mut commands: Commands,
+    mut bursts: EventReader<ParticleBurstEvent>,
+    mut pool: ResMut<ParticlePool>,
+    mut particles: Query<(&mut Particle, &mut Transform, &mut Visibility, &mut MeshMaterial3d<StandardMaterial>)>,
+    effect_settings: Res<EffectSettings>,
+) {
+    let density = particle_density(&effect_settings);
+
+    for burst in bursts.read() {
+        let count = (burst.count as f32 * density).round() as usize;
+
+        for _ in 0..count {
+            if let Some(entity) = pool.free.pop() {
+                let Ok((mut particle, mut transform, mut visibility, mut material)) = particles.get_mut(entity) else {
+                    continue;
+                };
+                let offset = init_particle(&mut particle, burst.particle_type, &mut pool);
+                transform.translation = burst.position + offset;
+                transform.scale = Vec3::splat(particle.start_size);
+                *visibility = Visibility::Visible;
+                if let Some(handle) = pool.materials.get(&burst.particle_type)
+                    && material.0 != *handle
+                {
+                    material.0 = handle.clone();
+                }
+            } else if pool.spawned < MAX_PARTICLES {
+                let mut particle = Particle {
+                    alive: false,
+                    particle_type: burst.particle_type,
+                    velocity: Vec3::ZERO,
+                    age: 0.0,
+                    lifetime: 1.0,
+                    start_size: 0.0,
+                    end_size: 0.0,
+                    gravity: 0.0,
+                    drag: 0.0,
+                };
+                let offset = init_particle(&mut particle, burst.particle_type, &mut pool);
+
+                commands.spawn((
+                    Mesh3d(pool.quad.clone()),
+                    MeshMaterial3d(pool.materials.get(&burst.particle_type).cloned().unwrap_or_default()),
+                    Transform::from_translation(burst.position + offset)
+                        .with_scale(Vec3::splat(particle.start_size)),
+                    Visibility::Visible,
+                    NotShadowCaster,
+                    particle,
+                    Name::new("Particle"),
+                ));
+                pool.spawned += 1;
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+pub fn update_particles(
+    mut pool: ResMut<ParticlePool>,
+    mut particles: Query<(Entity, &mut Particle, &mut Transform, &mut Visibility)>,
+    cameras: Query<&GlobalTransform, With<Camera3d>>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+    let camera_rotation = cameras.iter().next().map(|camera| camera.compute_transform().rotation);
+
+    for (entity, mut particle, mut transform, mut visibility) in particles.iter_mut() {
+        if !particle.alive {
+            continue;
+        }
+
+        particle.age += dt;
+        if particle.age >= particle.lifetime {
+            particle.alive = false;
+            *visibility = Visibility::Hidden;
+            pool.free.push(entity);
+            continue;
+        }
+
+        let drag = (1.0 - particle.drag * dt).max(0.0);
+        particle.velocity = particle.velocity * drag + Vec3::Y * particle.gravity * dt;
+        transform.translation += particle.velocity * dt;
+
+        let t = particle.age / particle.lifetime;
+        let size = particle.start_size + (particle.end_size - particle.start_size) * t;
+        transform.scale = Vec3::splat(size * particle_fade(t));
+        if let Some(rotation) = camera_rotation {
+            transform.rotation = rotation;
+        }
+    }
+}
+
+/// Continuous emitters: `spawn_rate` particles per second for `lifetime` seconds.
+pub fn update_particle_emitters(
+    mut commands: Commands,
+    emitters: Query<(Entity, &ParticleEffect, &GlobalTransform)>,
+    mut bursts: EventWriter<ParticleBurstEvent>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    let previous = now - time.delta_secs();
+
+    for (entity, effect, transform) in emitters.iter() {
+        let elapsed = now - effect.start_time;
+        if elapsed >= effect.lifetime {
+            commands.entity(entity).remove::<ParticleEffect>();
+            continue;
+        }
+
+        let emitted_before = ((previous - effect.start_time).max(0.0) * effect.spawn_rate).floor();
+        let emitted_now = (elapsed * effect.spawn_rate).floor();
+        let count = (emitted_now - emitted_before) as u32;
+        if count > 0 {
+            bursts.write(ParticleBurstEvent {
+                particle_type: effect.effect_type,
+                position: transform.translation(),
+                count,
+            });
+        }
+    }
+}
+
+/// Explosion and smoke where a piece is taken.
+pub fn capture_burst(position: Vec3) -> TweenNode {
+    TweenNode::parallel([
+        Tween::burst(ParticleType::Explosion, position + Vec3::Y * 0.3, PARTICLE_COUNT),
+        Tween::burst(ParticleType::Smoke, position, PARTICLE_COUNT / 4),
+    ])
+}
+
+/// Magic swirling around a promoted piece, sparkles over its top.
+pub fn promotion_burst(position: Vec3, new_piece_type: PieceType) -> TweenNode {
+    TweenNode::parallel([
+        Tween::burst(ParticleType::Magic, position, PARTICLE_COUNT),
+        Tween::burst(
+            ParticleType::Sparkle,
+            position + Vec3::Y * piece_height_by_type(new_piece_type),
+            PARTICLE_COUNT / 2,
+        ),
+    ])
+}
+
+/// Bursts around the king whenever a check or checkmate is first reported.
+pub fn emit_check_particles(
+    game_state: Res<GameStateResource>,
+    mut last_status: Local<Option<CheckStatus>>,
+    pieces: Query<(&ChessPiece, &GlobalTransform)>,
+    mut bursts: EventWriter<ParticleBurstEvent>,
+) {
+    let status = game_state.check_status;
+    if last_status.replace(status) == Some(status) {
+        return;
+    }
+
+    let (king_color, bursts_for_status) = match status {
+        CheckStatus::None => return,
+        CheckStatus::Check(color) => (color, &[(ParticleType::Sparkle, PARTICLE_COUNT / 2)][..]),
+        CheckStatus::Checkmate(color) => (
+            color,
+            &[
+                (ParticleType::Explosion, PARTICLE_COUNT * 2),
+                (ParticleType::Magic, PARTICLE_COUNT),
+                (ParticleType::Smoke, PARTICLE_COUNT / 2),
+            ][..],
+        ),
+    };
+
+    let king = pieces
+        .iter()
+        .find(|(piece, _)| piece.piece_type == PieceType::King && piece.color == king_color);
+    let Some((_, transform)) = king else {
+        return;
+    };
+
+    let position = transform.translation() + Vec3::Y * KING_HEIGHT * 0.5;
+    for &(particle_type, count) in bursts_for_status {
+        bursts.write(ParticleBurstEvent { particle_type, position, count });
+    }
+}
+
+/// `PieceEffectType::Sparkle` on a piece emits its particles once, then the effect is removed.
+pub fn emit_piece_sparkles(
+    mut commands: Commands,
+    effects: Query<(Entity, &PieceEffect, &GlobalTransform), Added<PieceEffect>>,
+    mut bursts: EventWriter<ParticleBurstEvent>,
+) {
+    for (entity, effect, transform) in effects.iter() {
+        if let PieceEffectType::Sparkle { particle_count } = effect.effect_type {
+            bursts.write(ParticleBurstEvent {
+                particle_type: ParticleType::Sparkle,
+                position: transform.translation() + Vec3::Y * 0.5,
+                count: (particle_count as f32 * effect.intensity).round() as u32,
+            });
+            commands.entity(entity).remove::<PieceEffect>();
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! Small fixed-step rigid-body simulation for captured pieces knocked off the board.
+//! Pieces are treated as cylinders colliding with the board, frame and table
+//! planes only; no bodies collide with each other. Fixed steps keep it deterministic.
+
+use bevy::prelude::*;
+use crate::{
+    core::constants::{physics::*, *},
+    game::pieces::{ChessPiece, KnockedAside},
+    graphics::Tweens,
+};
+
+#[derive(Component, Debug, Clone)]
+pub struct RigidBody {
+    pub velocity: Vec3,
+    pub angular_velocity: Vec3,
+    pub mass: f32,
+    pub radius: f32,
+    pub height: f32,
+    /// Elapsed time at which the body starts moving.
+    pub start_time: f32,
+    pub age: f32,
+    /// Seconds spent nearly motionless.
+    pub rest_time: f32,
+}
+
+impl RigidBody {
+    fn inertia(&self) -> f32 {
+        // Solid cylinder about a transverse axis; good enough for a tumbling piece
+        self.mass * (3.0 * self.radius * self.radius + self.height * self.height) / 12.0
+    }
+
+    fn center_of_mass(&self, transform: &Transform) -> Vec3 {
+        transform.translation + transform.rotation * Vec3::Y * (self.height * 0.5)
+    }
+
+    /// Rim samples of the base and top discs: the lowest point of each plus four around it,
+    /// so a piece standing or lying flat is supported at more than one point.
+    fn contact_points(&self, transform: &Transform) -> [Vec3; 10] {
+        let axis = transform.rotation * Vec3::Y;
+        let base = transform.translation;
+        let top = base + axis * self.height;
+
+        let sideways = Vec3::NEG_Y - axis * axis.dot(Vec3::NEG_Y);
+        let down = sideways.normalize_or_zero() * self.radius;
+        let u = transform.rotation * Vec3::X * self.radius;
+        let w = transform.rotation * Vec3::Z * self.radius;
+
+        let mut points = [Vec3::ZERO; 10];
+        for (disc, center) in [base, top].into_iter().enumerate() {
+            let rim = [down, u, -u, w, -w];
+            for (i, offset) in rim.into_iter().enumerate() {
+                points[disc * 5 + i] = center + offset;
+            }
+        }
+        points
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct PhysicsWorld {
+    accumulator: f32,
+}
+
+/// Height of the surface a piece base rests on at `(x, z)`.
+fn ground_height(x: f32, z: f32) -> f32 {
+    let board_half = BOARD_SIZE * SQUARE_SIZE / 2.0;
+    let extent = x.abs().max(z.abs());
+
+    if extent <= board_half {
+        BOARD_SURFACE_HEIGHT
+    } else if extent <= board_half + BOARD_FRAME_WIDTH {
+        FRAME_SURFACE_HEIGHT
+    } else {
+        TABLE_SURFACE_HEIGHT
+    }
+}
+
+fn resolve_contact(body: &mut RigidBody, transform: &Transform, contact: Vec3) {
+    let normal = Vec3::Y;
+    let arm = contact - body.center_of_mass(transform);
+    let inverse_mass = 1.0 / body.mass;
+    let inverse_inertia = 1.0 / body.inertia();
+    let contact_velocity = body.velocity + body.angular_velocity.cross(arm);
+    let normal_speed = contact_velocity.dot(normal);
+    if normal_speed >= 0.0 {
+        return;
+    }
+
+    let denominator = inverse_mass + arm.cross(normal).length_squared() * inverse_inertia;
+    // Slow contacts don't bounce, otherwise resting pieces jitter on gravity alone
+    let restitution = if -normal_speed > BOUNCE_THRESHOLD { PIECE_RESTITUTION } else { 0.0 };
+    let impulse = -(1.0 + restitution) * normal_speed / denominator;
+    body.velocity += normal * impulse * inverse_mass;
+    body.angular_velocity += arm.cross(normal * impulse) * inverse_inertia;
+
+    // Coulomb friction along the sliding direction
+    let tangent_velocity = contact_velocity - normal * normal_speed;
+    let tangent_speed = tangent_velocity.length();
+    if tangent_speed > f32::EPSILON {
+        let tangent = tangent_velocity / tangent_speed;
+        let denominator = inverse_mass + arm.cross(tangent).length_squared() * inverse_inertia;
+        let friction = (tangent_speed / denominator).min(BOARD_FRICTION * impulse);
+        body.velocity -= tangent * friction * inverse_mass;
+        body.angular_velocity -= arm.cross(tangent * friction) * inverse_inertia;
+    }
+}
+
+fn step_body(body: &mut RigidBody, transform: &mut Transform, dt: f32) {
+    body.velocity.y += GRAVITY * dt;
+
+    let center = body.center_of_mass(transform) + body.velocity * dt;
+    let spin = Quat::from_scaled_axis(body.angular_velocity * dt);
+    transform.rotation = (spin * transform.rotation).normalize();
+    transform.translation = center - transform.rotation * Vec3::Y * (body.height * 0.5);
+
+    let contacts = body.contact_points(transform);
+    let penetration = contacts
+        .iter()
+        .map(|contact| ground_height(contact.x, contact.z) - contact.y)
+        .fold(0.0, f32::max);
+    if penetration <= 0.0 {
+        return;
+    }
+    transform.translation.y += penetration;
+
+    for _ in 0..CONTACT_ITERATIONS {
+        for contact in contacts {
+            let contact = contact + Vec3::Y * penetration;
+            if contact.y <= ground_height(contact.x, contact.z) + CONTACT_SLOP {
+                resolve_contact(body, transform, contact);
+            }
+        }
+    }
+
+    // Rolling resistance so pieces settle instead of rocking forever
+    let damping = (1.0 - CONTACT_DAMPING * dt).max(0.0);
+    body.angular_velocity *= damping;
+    body.velocity.x *= damping;
+    body.velocity.z *= damping;
+}
+
+/// Takes knock-aside captures off their tweens while the simulation is enabled and below the body cap.
+pub fn launch_knockoff_bodies(
+    mut commands: Commands,
+    captures: Query<(Entity, &KnockedAside, Option<&ChessPiece>), Added<KnockedAside>>,
+    bodies: Query<(), With<RigidBody>>,
+    mut tweens: ResMut<Tweens>,
+    effect_settings: Res<crate::graphics::EffectSettings>,
+    time: Res<Time>,
+) {
+    let mut body_count = bodies.iter().count();
+    for (entity, knock, piece) in captures.iter() {
+        commands.entity(entity).remove::<KnockedAside>();
+        if !effect_settings.enable_physics || body_count >= MAX_PHYSICS_BODIES {
+            continue;
+        }
+        body_count += 1;
+
+        let height = piece.map_or(PAWN_HEIGHT, |piece| piece_height_by_type(piece.piece_type));
+        // Tip the piece over the direction it was hit in
+        let tumble_axis = Vec3::Y.cross(knock.direction).normalize_or_zero();
+
+        tweens.cancel_entity(entity);
+        commands.entity(entity).insert(RigidBody {
+            velocity: knock.direction * KNOCKOFF_SPEED + Vec3::Y * KNOCKOFF_LIFT,
+            angular_velocity: tumble_axis * KNOCKOFF_SPIN,
+            mass: PIECE_MASS,
+            radius: PIECE_RADIUS,
+            height,
+            start_time: time.elapsed_secs() + knock.delay,
+            age: 0.0,
+            rest_time: 0.0,
+        });
+    }
+}
+
+pub fn simulate_rigid_bodies(
+    mut commands: Commands,
+    mut world: ResMut<PhysicsWorld>,
+    mut bodies: Query<(Entity, &mut RigidBody, &mut Transform, Option<&ChessPiece>)>,
+    time: Res<Time>,
+) {
+    if bodies.is_empty() {
+        world.accumulator = 0.0;
+        return;
+    }
+
+    world.accumulator = (world.accumulator + time.delta_secs()).min(PHYSICS_TIMESTEP * MAX_PHYSICS_STEPS as f32);
+    let steps = (world.accumulator / PHYSICS_TIMESTEP) as u32;
+    world.accumulator -= steps as f32 * PHYSICS_TIMESTEP;
+
+    let current_time = time.elapsed_secs();
+    for (entity, mut body, mut transform, piece) in bodies.iter_mut() {
+        if current_time < body.start_time {
+            continue;
+        }
+
+        for _ in 0..steps {
+            step_body(&mut body, &mut transform, PHYSICS_TIMESTEP);
+        }
+        body.age += steps as f32 * PHYSICS_TIMESTEP;
+
+        let resting = body.velocity.length() < REST_LINEAR_SPEED && body.angular_velocity.length() < REST_ANGULAR_SPEED;
+        body.rest_time = if resting { body.rest_time + steps as f32 * PHYSICS_TIMESTEP } else { 0.0 };
+
+        // Settled (or simulated long enough): hand the piece to the graveyard
+        if body.rest_time >= REST_DURATION || body.age >= MAX_BODY_LIFETIME {
+            commands.entity(entity).remove::<RigidBody>();
+            crate::game::pieces::send_to_graveyard(&mut commands, entity, piece);
+        }
+    }
+}
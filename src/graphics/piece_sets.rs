@@ -0,0 +1,357 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+};
+
+use bevy::{
+    ecs::system::SystemParam,
+    gltf::{Gltf, GltfMesh, GltfNode},
+    prelude::*,
+    render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::{constants::asset_paths, resources::GameSettings},
+    game::pieces::{ChessPiece, PieceType},
+    graphics::{ChessMeshes, generate_piece_mesh, with_tangents},
+};
+
+/// Description of a glTF piece set, stored as `models/<set>/manifest.json`.
+///
+/// ```json
+/// {
+///   "name": "Walnut",
+///   "scene": "walnut.glb",
+///   "pieces": {
+///     "Pawn": { "node": "pawn", "scale": 0.01, "origin": [0.0, 0.0, 0.0] },
+///     "Knight": { "node": "knight", "scale": 0.01 }
+///   }
+/// }
+/// ```
+///
+/// Pieces that are missing from the manifest or the scene keep their procedural mesh.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PieceSetManifest {
+    pub name: String,
+    /// glTF/GLB file, relative to the manifest directory.
+    pub scene: String,
+    pub pieces: HashMap<PieceType, PieceNodeEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PieceNodeEntry {
+    /// Name of the node inside the glTF scene.
+    pub node: String,
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    /// Point of the model (in model units) that should sit on the square.
+    #[serde(default)]
+    pub origin: [f32; 3],
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PieceSetError {
+    #[error("failed to read manifest {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("malformed manifest {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("manifest {0} does not list any pieces")]
+    Empty(PathBuf),
+}
+
+impl PieceSetManifest {
+    pub fn manifest_path(set_name: &str) -> PathBuf {
+        asset_paths::on_disk(asset_paths::MODELS_DIR)
+            .join(set_name)
+            .join(asset_paths::PIECE_SET_MANIFEST)
+    }
+
+    pub fn load(set_name: &str) -> Result<Self, PieceSetError> {
+        let path = Self::manifest_path(set_name);
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|source| PieceSetError::Io { path: path.clone(), source })?;
+        let manifest: Self = serde_json::from_str(&contents)
+            .map_err(|source| PieceSetError::Parse { path: path.clone(), source })?;
+
+        if manifest.pieces.is_empty() {
+            return Err(PieceSetError::Empty(path));
+        }
+        Ok(manifest)
+    }
+
+    /// Asset path of the scene, as understood by the `AssetServer`.
+    pub fn scene_asset_path(&self, set_name: &str) -> String {
+        format!("{}{}/{}", asset_paths::MODELS_DIR, set_name, self.scene)
+    }
+}
+
+/// Lists every directory under `models/` that contains a manifest.
+pub fn discover_piece_sets() -> Vec<String> {
+    let models_dir = asset_paths::on_disk(asset_paths::MODELS_DIR);
+    let Ok(entries) = std::fs::read_dir(models_dir) else {
+        return Vec::new();
+    };
+
+    let mut sets: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().join(asset_paths::PIECE_SET_MANIFEST).is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    sets.sort();
+    sets
+}
+
+#[derive(Resource, Default)]
+pub struct PieceSetState {
+    pub available: Vec<String>,
+    /// Set currently applied to `ChessMeshes`, `None` for the procedural pieces.
+    pub active: Option<String>,
+    /// Last value of `GameSettings::piece_set` that was acted upon.
+    requested: Option<String>,
+    pending: Option<PendingPieceSet>,
+}
+
+struct PendingPieceSet {
+    name: String,
+    manifest: PieceSetManifest,
+    gltf: Handle<Gltf>,
+}
+
+impl PieceSetState {
+    pub fn is_loading(&self) -> bool {
+        self.pending.is_some()
+    }
+}
+
+pub fn discover_piece_sets_on_startup(mut state: ResMut<PieceSetState>) {
+    state.available = discover_piece_sets();
+    if !state.available.is_empty() {
+        info!("Found piece sets: {:?}", state.available);
+    }
+}
+
+pub fn request_piece_set(
+    settings: Res<GameSettings>,
+    asset_server: Res<AssetServer>,
+    mut state: ResMut<PieceSetState>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut chess_meshes: ResMut<ChessMeshes>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    if settings.piece_set == state.requested {
+        return;
+    }
+    state.requested = settings.piece_set.clone();
+    state.pending = None;
+
+    let Some(set_name) = settings.piece_set.clone() else {
+        if state.active.take().is_some() {
+            use_procedural_pieces(&mut meshes, &mut chess_meshes, &settings);
+        }
+        return;
+    };
+
+    match PieceSetManifest::load(&set_name) {
+        Ok(manifest) => {
+            info!("Loading piece set '{}' ({})", set_name, manifest.name);
+            let gltf = asset_server.load(manifest.scene_asset_path(&set_name));
+            state.pending = Some(PendingPieceSet { name: set_name, manifest, gltf });
+        }
+        Err(err) => {
+            warn!("Piece set '{}' unavailable, using procedural pieces: {}", set_name, err);
+            if state.active.take().is_some() {
+                use_procedural_pieces(&mut meshes, &mut chess_meshes, &settings);
+            }
+        }
+    }
+}
+
+/// A loaded scene is only usable together with the nodes and meshes it refers to.
+#[derive(SystemParam)]
+pub struct GltfAssets<'w> {
+    scenes: Res<'w, Assets<Gltf>>,
+    nodes: Res<'w, Assets<GltfNode>>,
+    meshes: Res<'w, Assets<GltfMesh>>,
+}
+
+pub fn finish_piece_set_loading(
+    settings: Res<GameSettings>,
+    asset_server: Res<AssetServer>,
+    gltf_assets: GltfAssets,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut chess_meshes: ResMut<ChessMeshes>,
+    mut state: ResMut<PieceSetState>,
+) {
+    let Some(pending) = state.pending.as_ref() else {
+        return;
+    };
+
+    if asset_server.recursive_dependency_load_state(&pending.gltf).is_failed() {
+        warn!("Failed to load piece set '{}', using procedural pieces", pending.name);
+        state.pending = None;
+        state.active = None;
+        use_procedural_pieces(&mut meshes, &mut chess_meshes, &settings);
+        return;
+    }
+
+    if !asset_server.is_loaded_with_dependencies(&pending.gltf) {
+        return;
+    }
+
+    // Only give up the pending set once the scene is really there
+    let Some(gltf) = gltf_assets.scenes.get(&pending.gltf) else {
+        return;
+    };
+    let Some(pending) = state.pending.take() else {
+        return;
+    };
+
+    for piece_type in PieceType::ALL {
+        let baked = pending.manifest.pieces.get(&piece_type).and_then(|entry| {
+            let node = gltf.named_nodes.get(entry.node.as_str())?;
+            let mesh = bake_node_mesh(node, Transform::IDENTITY, &gltf_assets.nodes, &gltf_assets.meshes, &meshes)?;
+            Some(with_tangents(normalize_piece_mesh(mesh, entry)))
+        });
+
+        let mesh = match baked {
+            Some(mesh) => mesh,
+            None => {
+                warn!(
+                    "Piece set '{}' has no usable mesh for {}, using procedural mesh",
+                    pending.name,
+                    piece_type.name()
+                );
+                generate_piece_mesh(piece_type, settings.graphics_quality)
+            }
+        };
+        chess_meshes.set_piece_mesh(piece_type, meshes.add(mesh));
+    }
+
+    info!("Piece set '{}' applied", pending.name);
+    state.active = Some(pending.name);
+}
+
+fn use_procedural_pieces(
+    meshes: &mut Assets<Mesh>,
+    chess_meshes: &mut ChessMeshes,
+    settings: &GameSettings,
+) {
+    for piece_type in PieceType::ALL {
+        chess_meshes.set_piece_mesh(piece_type, meshes.add(generate_piece_mesh(piece_type, settings.graphics_quality)));
+    }
+}
+
+/// Flattens a node and its children into a single triangle mesh in the node's parent space.
+fn bake_node_mesh(
+    node_handle: &Handle<GltfNode>,
+    parent: Transform,
+    gltf_nodes: &Assets<GltfNode>,
+    gltf_meshes: &Assets<GltfMesh>,
+    meshes: &Assets<Mesh>,
+) -> Option<Mesh> {
+    let node = gltf_nodes.get(node_handle)?;
+    let transform = parent * node.transform;
+    let mut baked: Option<Mesh> = None;
+
+    if let Some(gltf_mesh) = node.mesh.as_ref().and_then(|handle| gltf_meshes.get(handle)) {
+        for primitive in &gltf_mesh.primitives {
+            if let Some(mesh) = meshes.get(&primitive.mesh) {
+                append_mesh(&mut baked, mesh.clone().transformed_by(transform));
+            }
+        }
+    }
+
+    for child in &node.children {
+        if let Some(mesh) = bake_node_mesh(child, transform, gltf_nodes, gltf_meshes, meshes) {
+            append_mesh(&mut baked, mesh);
+        }
+    }
+
+    baked
+}
+
+fn append_mesh(target: &mut Option<Mesh>, mesh: Mesh) {
+    let Some(mesh) = keep_piece_attributes(mesh) else {
+        return;
+    };
+    match target {
+        Some(base) => {
+            if let Err(err) = base.merge(&mesh) {
+                warn!("Skipping incompatible glTF primitive: {}", err);
+            }
+        }
+        None => *target = Some(mesh),
+    }
+}
+
+/// Keeps position/normal/uv and 32-bit indices so primitives from different exporters can be merged.
+/// Lines, points and strips are dropped; only triangle lists make up a piece.
+fn keep_piece_attributes(mesh: Mesh) -> Option<Mesh> {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        debug!("Skipping {:?} glTF primitive", mesh.primitive_topology());
+        return None;
+    }
+
+    let positions = mesh.attribute(Mesh::ATTRIBUTE_POSITION)?.as_float3()?.to_vec();
+    let vertex_count = positions.len();
+
+    let mut result = Mesh::new(mesh.primitive_topology(), mesh.asset_usage);
+    result.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+
+    match mesh.attribute(Mesh::ATTRIBUTE_UV_0) {
+        Some(VertexAttributeValues::Float32x2(uvs)) => {
+            result.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs.clone());
+        }
+        _ => result.insert_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0.0, 0.0]; vertex_count]),
+    }
+
+    let indices: Vec<u32> = match mesh.indices() {
+        Some(indices) => indices.iter().map(|i| i as u32).collect(),
+        None => (0..vertex_count as u32).collect(),
+    };
+    result.insert_indices(Indices::U32(indices));
+
+    match mesh.attribute(Mesh::ATTRIBUTE_NORMAL).and_then(VertexAttributeValues::as_float3) {
+        Some(normals) => result.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals.to_vec()),
+        None => result.compute_smooth_normals(),
+    }
+
+    Some(result)
+}
+
+/// Applies the manifest origin and scale so the piece stands on the square like the procedural ones.
+fn normalize_piece_mesh(mesh: Mesh, entry: &PieceNodeEntry) -> Mesh {
+    mesh.translated_by(-Vec3::from_array(entry.origin))
+        .scaled_by(Vec3::splat(entry.scale))
+}
+
+/// Points every spawned piece at the current mesh handles after a set change.
+pub fn apply_piece_meshes(
+    chess_meshes: Res<ChessMeshes>,
+    mut pieces: Query<(&mut Mesh3d, &ChessPiece)>,
+) {
+    if !chess_meshes.is_changed() {
+        return;
+    }
+
+    for (mut mesh, piece) in pieces.iter_mut() {
+        let handle = chess_meshes.get_piece_mesh(piece.piece_type);
+        if mesh.0 != handle {
+            mesh.0 = handle;
+        }
+    }
+}
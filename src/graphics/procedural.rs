@@ -1,256 +1,484 @@
 use bevy::prelude::*;
+use bevy::tasks::{futures::check_ready, AsyncComputeTaskPool, Task};
 use crate::core::constants::*;
+use crate::core::resources::{GameSettings, GraphicsQuality};
+use crate::game::pieces::PieceType;
+use crate::graphics::ThemeLibrary;
 
-pub fn generate_detailed_pawn_mesh() -> Mesh {
-    let mut mesh_data = MeshData::new();
+/// Tessellation settings for the procedural piece meshes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MeshDetail {
+    /// Number of segments around the Y axis.
+    pub radial_segments: u32,
+    /// Number of points used for every arc in a silhouette.
+    pub arc_steps: u32,
+}
 
-    add_cylinder_to_mesh(
-        &mut mesh_data,
-        Vec3::new(0.0, 0.0, 0.0),
-        PIECE_RADIUS,
-        PAWN_HEIGHT * 0.2,
-        8
-    );
+impl MeshDetail {
+    pub fn from_quality(quality: GraphicsQuality) -> Self {
+        match quality {
+            // Low quality draws the primitive pieces instead; see `generate_piece_mesh`
+            GraphicsQuality::Low | GraphicsQuality::Medium => Self { radial_segments: 16, arc_steps: 4 },
+            GraphicsQuality::High => Self { radial_segments: 32, arc_steps: 6 },
+            GraphicsQuality::Ultra => Self { radial_segments: 64, arc_steps: 10 },
+        }
+    }
+}
 
-    add_tapered_cylinder_to_mesh(
-        &mut mesh_data,
-        Vec3::new(0.0, PAWN_HEIGHT * 0.2, 0.0),
-        PIECE_RADIUS * 0.8,
-        PIECE_RADIUS * 0.6,
-        PAWN_HEIGHT * 0.5,
-        8
-    );
+impl Default for MeshDetail {
+    fn default() -> Self {
+        Self::from_quality(GraphicsQuality::High)
+    }
+}
 
-    add_sphere_to_mesh(
-        &mut mesh_data,
-        Vec3::new(0.0, PAWN_HEIGHT * 0.8, 0.0),
-        PIECE_RADIUS * 0.4,
-        6,
-        4,
-    );
+pub fn generate_detailed_piece_mesh(piece_type: PieceType, detail: MeshDetail) -> Mesh {
+    match piece_type {
+        PieceType::Pawn => generate_detailed_pawn_mesh(detail),
+        PieceType::Rook => generate_detailed_rook_mesh(detail),
+        PieceType::Knight => generate_detailed_knight_mesh(detail),
+        PieceType::Bishop => generate_detailed_bishop_mesh(detail),
+        PieceType::Queen => generate_detailed_queen_mesh(detail),
+        PieceType::King => generate_detailed_king_mesh(detail),
+    }
+}
 
-    mesh_data.to_mesh()
+/// Foot shared by every Staunton piece: flat bottom, rounded rim, a bead and the start of the cove.
+fn staunton_base(profile: LatheProfile, radius: f32, height: f32) -> LatheProfile {
+    profile
+        .point(0.0, 0.0)
+        .point(radius, 0.0)
+        .point(radius, height * 0.035)
+        .arc(Vec2::new(radius * 0.92, height * 0.035), radius * 0.08, 0.0, 90.0)
+        .point(radius * 0.86, height * 0.035 + radius * 0.08)
+        .arc(Vec2::new(radius * 0.84, height * 0.1), height * 0.022, -90.0, 90.0)
+        .point(radius * 0.74, height * 0.13)
 }
 
-pub fn generate_detailed_rook_mesh() -> Mesh {
-    let mut mesh_data = MeshData::new();
+/// Decorative collar: a flat disc followed by an inward bevel.
+fn staunton_collar(profile: LatheProfile, radius: f32, inner: f32, y: f32, thickness: f32) -> LatheProfile {
+    profile
+        .point(radius, y)
+        .point(radius, y + thickness)
+        .point(inner, y + thickness * 1.6)
+}
 
-    add_cylinder_to_mesh(
-        &mut mesh_data,
-        Vec3::ZERO,
-        PIECE_RADIUS * 0.9,
-        ROOK_HEIGHT * 0.3,
-        8
-    );
+pub fn generate_detailed_pawn_mesh(detail: MeshDetail) -> Mesh {
+    let r = PIECE_RADIUS;
+    let h = PAWN_HEIGHT;
+    let head_radius = r * 0.45;
+    let head_center = h - head_radius;
 
-    add_cylinder_to_mesh(
-        &mut mesh_data,
-        Vec3::new(0.0, ROOK_HEIGHT * 0.3, 0.0),
-        PIECE_RADIUS * 0.7,
-        ROOK_HEIGHT * 0.5,
-        8
-    );
+    let profile = staunton_base(LatheProfile::new(detail.arc_steps), r, h)
+        .point(r * 0.62, h * 0.2)
+        .point(r * 0.42, h * 0.45)
+        .point(r * 0.36, h * 0.55);
+    let profile = staunton_collar(profile, r * 0.62, r * 0.3, h * 0.57, h * 0.03)
+        .arc(Vec2::new(0.0, head_center), head_radius, -60.0, 90.0);
 
-    let wall_height = ROOK_HEIGHT * 0.2;
-    let wall_thickness = 0.08;
-    let wall_y = ROOK_HEIGHT * 0.8;
+    profile.to_mesh(detail.radial_segments)
+}
 
-    for i in 0..8 {
-        let angle = i as f32 * std::f32::consts::PI * 0.25;
-        let x = angle.cos() * PIECE_RADIUS * 0.8;
-        let z = angle.sin() * PIECE_RADIUS * 0.8;
+pub fn generate_detailed_rook_mesh(detail: MeshDetail) -> Mesh {
+    let r = PIECE_RADIUS;
+    let h = ROOK_HEIGHT;
+    let rim_y = h * 0.86;
 
-        if i % 2 == 0 {
-            // High part
-            add_box_to_mesh(
-                &mut mesh_data,
-                Vec3::new(x, wall_y + wall_height * 0.5, z),
-                Vec3::new(wall_thickness, wall_height, wall_thickness)
-            );
-        } else {
-            // Low part
-            add_box_to_mesh(
-                &mut mesh_data,
-                Vec3::new(z, wall_y + wall_height * 0.3, z),
-                Vec3::new(wall_thickness, wall_height * 0.6, wall_thickness)
-            );
-        }
+    let profile = staunton_base(LatheProfile::new(detail.arc_steps), r, h)
+        .point(r * 0.7, h * 0.16)
+        .point(r * 0.6, h * 0.5)
+        .point(r * 0.64, h * 0.6);
+    let profile = staunton_collar(profile, r * 0.8, r * 0.7, h * 0.63, h * 0.03)
+        .point(r * 0.78, h * 0.7)
+        .point(r * 0.78, rim_y)
+        .point(r * 0.6, rim_y)
+        .point(r * 0.6, rim_y - h * 0.05)
+        .point(0.0, rim_y - h * 0.05);
+
+    let mut mesh_data = MeshData::new();
+    profile.add_to_mesh(&mut mesh_data, Vec3::ZERO, detail.radial_segments);
+
+    // Crenellations
+    let merlons = 6;
+    let merlon_height = h * 0.12;
+    for i in 0..merlons {
+        let angle = i as f32 * std::f32::consts::TAU / merlons as f32;
+        let center = Vec3::new(
+            angle.cos() * r * 0.69,
+            rim_y + merlon_height * 0.5,
+            angle.sin() * r * 0.69,
+        );
+        add_oriented_box_to_mesh(
+            &mut mesh_data,
+            center,
+            Vec3::new(r * 0.18, merlon_height, r * 0.38),
+            Quat::from_rotation_y(-angle),
+        );
     }
 
     mesh_data.to_mesh()
 }
 
-pub fn generate_detailed_knight_mesh() -> Mesh {
-    let mut mesh_data = MeshData::new();
+pub fn generate_detailed_knight_mesh(detail: MeshDetail) -> Mesh {
+    let r = PIECE_RADIUS;
+    let h = KNIGHT_HEIGHT;
 
-    add_cylinder_to_mesh(
-        &mut mesh_data,
-        Vec3::ZERO,
-        PIECE_RADIUS * 0.8,
-        KNIGHT_HEIGHT * 0.3,
-        8
-    );
+    let profile = staunton_base(LatheProfile::new(detail.arc_steps), r, h)
+        .point(r * 0.72, h * 0.16);
+    let profile = staunton_collar(profile, r * 0.78, r * 0.6, h * 0.17, h * 0.03)
+        .point(r * 0.6, h * 0.24)
+        .point(0.0, h * 0.24);
 
-    add_cylinder_to_mesh(
-        &mut mesh_data,
-        Vec3::new(0.0, KNIGHT_HEIGHT * 0.4, PIECE_RADIUS * 0.2),
-        PIECE_RADIUS * 0.4,
-        KNIGHT_HEIGHT * 0.3,
-        6
-    );
+    let mut mesh_data = MeshData::new();
+    profile.add_to_mesh(&mut mesh_data, Vec3::ZERO, detail.radial_segments);
 
-    add_elongated_sphere_to_mesh(
-        &mut mesh_data,
-        Vec3::new(0.0, KNIGHT_HEIGHT * 0.7, PIECE_RADIUS * 0.4),
-        Vec3::new(PIECE_RADIUS * 0.3, PIECE_RADIUS * 0.25, PIECE_RADIUS * 0.4),
-        6,
-        4
-    );
+    // Neck and head, lofted along a spine that rises from the base and bends forward (+Z)
+    let sections = [
+        LoftSection::new(Vec3::new(0.0, h * 0.2, -r * 0.05), r * 0.56, r * 0.72),
+        LoftSection::new(Vec3::new(0.0, h * 0.34, -r * 0.12), r * 0.48, r * 0.68),
+        LoftSection::new(Vec3::new(0.0, h * 0.5, -r * 0.08), r * 0.42, r * 0.62),
+        LoftSection::new(Vec3::new(0.0, h * 0.63, r * 0.06), r * 0.4, r * 0.58),
+        LoftSection::new(Vec3::new(0.0, h * 0.73, r * 0.28), r * 0.37, r * 0.5),
+        LoftSection::new(Vec3::new(0.0, h * 0.75, r * 0.52), r * 0.33, r * 0.42),
+        LoftSection::new(Vec3::new(0.0, h * 0.71, r * 0.76), r * 0.29, r * 0.36),
+        LoftSection::new(Vec3::new(0.0, h * 0.66, r * 0.96), r * 0.25, r * 0.3),
+        LoftSection::new(Vec3::new(0.0, h * 0.63, r * 1.08), r * 0.16, r * 0.2),
+        LoftSection::new(Vec3::new(0.0, h * 0.62, r * 1.12), r * 0.02, r * 0.02),
+    ];
+    add_loft_to_mesh(&mut mesh_data, &sections, detail.radial_segments);
 
-    add_box_to_mesh(
-        &mut mesh_data,
-        Vec3::new(PIECE_RADIUS * 0.2, KNIGHT_HEIGHT * 0.8, PIECE_RADIUS * 0.3),
-        Vec3::new(0.05, 0.15, 0.03)
-    );
-    add_box_to_mesh(
-        &mut mesh_data,
-        Vec3::new(-PIECE_RADIUS * 0.2, KNIGHT_HEIGHT * 0.8, PIECE_RADIUS * 0.3),
-        Vec3::new(0.05, 0.15, 0.03)
-    );
+    // Mane along the back of the neck
+    let mane_steps = 5;
+    for i in 0..mane_steps {
+        let t = i as f32 / (mane_steps - 1) as f32;
+        add_elongated_sphere_to_mesh(
+            &mut mesh_data,
+            Vec3::new(0.0, h * (0.36 + 0.36 * t), -r * (0.62 - 0.5 * t)),
+            Vec3::new(r * 0.12, r * 0.2, r * 0.14),
+            detail.arc_steps * 2,
+            detail.radial_segments / 2,
+        );
+    }
+
+    // Ears and eyes
+    for side in [-1.0, 1.0] {
+        add_tapered_cylinder_to_mesh(
+            &mut mesh_data,
+            Vec3::new(side * r * 0.18, h * 0.84, r * 0.16),
+            r * 0.1,
+            r * 0.02,
+            h * 0.1,
+            (detail.radial_segments / 4).max(4),
+        );
+        add_sphere_to_mesh(
+            &mut mesh_data,
+            Vec3::new(side * r * 0.33, h * 0.75, r * 0.5),
+            r * 0.06,
+            detail.arc_steps,
+            (detail.radial_segments / 4).max(4),
+        );
+    }
 
     mesh_data.to_mesh()
 }
 
-pub fn generate_detailed_bishop_mesh() -> Mesh {
+pub fn generate_detailed_bishop_mesh(detail: MeshDetail) -> Mesh {
+    let r = PIECE_RADIUS;
+    let h = BISHOP_HEIGHT;
+    let finial_radius = r * 0.12;
+
+    let profile = staunton_base(LatheProfile::new(detail.arc_steps), r, h)
+        .point(r * 0.6, h * 0.17)
+        .point(r * 0.38, h * 0.45)
+        .point(r * 0.32, h * 0.58);
+    let profile = staunton_collar(profile, r * 0.6, r * 0.3, h * 0.6, h * 0.025);
+    let profile = staunton_collar(profile, r * 0.54, r * 0.28, h * 0.66, h * 0.02)
+        .point(r * 0.4, h * 0.74)
+        .point(r * 0.48, h * 0.8)
+        .point(r * 0.45, h * 0.86)
+        .point(r * 0.34, h * 0.91)
+        .point(r * 0.16, h * 0.95)
+        .point(r * 0.06, h - finial_radius * 1.9)
+        .arc(Vec2::new(0.0, h - finial_radius), finial_radius, -60.0, 90.0);
+
+    profile.to_mesh(detail.radial_segments)
+}
+
+pub fn generate_detailed_queen_mesh(detail: MeshDetail) -> Mesh {
+    let r = PIECE_RADIUS;
+    let h = QUEEN_HEIGHT;
+    let crown_y = h * 0.88;
+    let finial_radius = r * 0.14;
+
+    let profile = staunton_base(LatheProfile::new(detail.arc_steps), r, h)
+        .point(r * 0.62, h * 0.17)
+        .point(r * 0.4, h * 0.45)
+        .point(r * 0.32, h * 0.6);
+    let profile = staunton_collar(profile, r * 0.62, r * 0.32, h * 0.62, h * 0.025);
+    let profile = staunton_collar(profile, r * 0.54, r * 0.32, h * 0.69, h * 0.02)
+        .point(r * 0.45, h * 0.8)
+        .point(r * 0.62, crown_y)
+        .point(r * 0.6, crown_y + h * 0.015)
+        .point(r * 0.42, crown_y + h * 0.01)
+        .point(r * 0.3, crown_y + h * 0.03)
+        .point(r * 0.12, h - finial_radius * 1.8)
+        .arc(Vec2::new(0.0, h - finial_radius), finial_radius, -60.0, 90.0);
+
     let mut mesh_data = MeshData::new();
+    profile.add_to_mesh(&mut mesh_data, Vec3::ZERO, detail.radial_segments);
 
-    add_cylinder_to_mesh(
-        &mut mesh_data,
-        Vec3::ZERO,
-        PIECE_RADIUS * 0.8,
-        BISHOP_HEIGHT * 0.25,
-        8
-    );
+    // Coronet balls around the crown rim
+    let points = 8;
+    for i in 0..points {
+        let angle = i as f32 * std::f32::consts::TAU / points as f32;
+        add_sphere_to_mesh(
+            &mut mesh_data,
+            Vec3::new(angle.cos() * r * 0.58, crown_y + h * 0.025, angle.sin() * r * 0.58),
+            r * 0.08,
+            detail.arc_steps,
+            (detail.radial_segments / 4).max(4),
+        );
+    }
 
-    add_tapered_cylinder_to_mesh(
-        &mut mesh_data,
-        Vec3::new(0.0, BISHOP_HEIGHT * 0.25, 0.0),
-        PIECE_RADIUS * 0.7,
-        PIECE_RADIUS * 0.3,
-        BISHOP_HEIGHT * 0.6,
-        8
-    );
+    mesh_data.to_mesh()
+}
 
-    add_sphere_to_mesh(
-        &mut mesh_data,
-        Vec3::new(0.0, BISHOP_HEIGHT * 0.9, 0.0),
-        PIECE_RADIUS * 0.25,
-        6,
-        4
-    );
+pub fn generate_detailed_king_mesh(detail: MeshDetail) -> Mesh {
+    let r = PIECE_RADIUS;
+    let h = KING_HEIGHT;
+    let crown_top = h * 0.86;
+
+    let profile = staunton_base(LatheProfile::new(detail.arc_steps), r, h)
+        .point(r * 0.64, h * 0.17)
+        .point(r * 0.42, h * 0.45)
+        .point(r * 0.34, h * 0.6);
+    let profile = staunton_collar(profile, r * 0.64, r * 0.34, h * 0.62, h * 0.025);
+    let profile = staunton_collar(profile, r * 0.56, r * 0.34, h * 0.68, h * 0.02)
+        .point(r * 0.46, h * 0.78)
+        .point(r * 0.58, h * 0.83)
+        .point(r * 0.56, crown_top)
+        .point(r * 0.3, crown_top + h * 0.015)
+        .point(r * 0.12, crown_top + h * 0.025)
+        .point(0.0, crown_top + h * 0.025);
 
+    let mut mesh_data = MeshData::new();
+    profile.add_to_mesh(&mut mesh_data, Vec3::ZERO, detail.radial_segments);
+
+    // Cross
+    let cross_base = crown_top + h * 0.02;
+    let cross_height = h - cross_base;
     add_box_to_mesh(
         &mut mesh_data,
-        Vec3::new(0.0, BISHOP_HEIGHT * 0.95, 0.0),
-        Vec3::new(0.03, 0.1, 0.15)
+        Vec3::new(0.0, cross_base + cross_height * 0.5, 0.0),
+        Vec3::new(r * 0.14, cross_height, r * 0.1),
     );
     add_box_to_mesh(
         &mut mesh_data,
-        Vec3::new(0.0, BISHOP_HEIGHT * 0.95, 0.0),
-        Vec3::new(0.15, 0.1, 0.03)
+        Vec3::new(0.0, cross_base + cross_height * 0.62, 0.0),
+        Vec3::new(r * 0.42, r * 0.12, r * 0.1),
     );
 
     mesh_data.to_mesh()
 }
 
-pub fn generate_detailed_queen_mesh() -> Mesh {
-    let mut mesh_data = MeshData::new();
+/// A 2D silhouette (x = radius, y = height) revolved around the Y axis.
+///
+/// Points are listed from the bottom of the piece to the top. Corners sharper than
+/// `CREASE_ANGLE_DEGREES` get split normals so flat caps and discs keep hard edges.
+#[derive(Debug, Clone)]
+pub struct LatheProfile {
+    points: Vec<Vec2>,
+    arc_steps: u32,
+}
 
-    add_cylinder_to_mesh(
-        &mut mesh_data,
-        Vec3::ZERO,
-        PIECE_RADIUS * 0.9,
-        QUEEN_HEIGHT * 0.3,
-        8
-    );
+const CREASE_ANGLE_DEGREES: f32 = 50.0;
 
-    add_tapered_cylinder_to_mesh(
-        &mut mesh_data,
-        Vec3::new(0.0, QUEEN_HEIGHT * 0.3, 0.0),
-        PIECE_RADIUS * 0.8,
-        PIECE_RADIUS * 0.5,
-        QUEEN_HEIGHT * 0.4,
-        8
-    );
+impl LatheProfile {
+    pub fn new(arc_steps: u32) -> Self {
+        Self {
+            points: Vec::new(),
+            arc_steps: arc_steps.max(1),
+        }
+    }
 
-    add_cylinder_to_mesh(
-        &mut mesh_data,
-        Vec3::new(0.0, QUEEN_HEIGHT * 0.7, 0.0),
-        PIECE_RADIUS * 0.6,
-        QUEEN_HEIGHT * 0.1,
-        8
-    );
+    pub fn point(mut self, radius: f32, height: f32) -> Self {
+        self.push(Vec2::new(radius.max(0.0), height));
+        self
+    }
 
-    for i in 0..8 {
-        let angle = i as f32 * std::f32::consts::PI * 0.25;
-        let x = angle.cos() * PIECE_RADIUS * 0.55;
-        let z = angle.sin() * PIECE_RADIUS * 0.55;
-        let height = if 1 % 2 == 0 { 0.25 } else { 0.15 };
+    /// Adds a circular arc (angles in degrees, 0 = +radius, 90 = up).
+    pub fn arc(mut self, center: Vec2, radius: f32, start_degrees: f32, end_degrees: f32) -> Self {
+        let steps = self.arc_steps;
+        for i in 0..=steps {
+            let t = i as f32 / steps as f32;
+            let angle = (start_degrees + (end_degrees - start_degrees) * t).to_radians();
+            let p = center + Vec2::new(angle.cos(), angle.sin()) * radius;
+            self.push(Vec2::new(p.x.max(0.0), p.y));
+        }
+        self
+    }
 
-        add_tapered_cylinder_to_mesh(
-            &mut mesh_data,
-            Vec3::new(x, QUEEN_HEIGHT * 0.8, z),
-            0.04,
-            0.02,
-            QUEEN_HEIGHT * height,
-            4
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    fn push(&mut self, point: Vec2) {
+        // Zero-length segments would produce NaN normals
+        if self.points.last().is_some_and(|last| last.distance_squared(point) < 1e-10) {
+            return;
+        }
+        self.points.push(point);
+    }
+
+    pub fn to_mesh(&self, segments: u32) -> Mesh {
+        let mut mesh_data = MeshData::new();
+        self.add_to_mesh(&mut mesh_data, Vec3::ZERO, segments);
+        mesh_data.to_mesh()
+    }
+
+    fn add_to_mesh(&self, mesh_data: &mut MeshData, offset: Vec3, segments: u32) {
+        let points = &self.points;
+        if points.len() < 2 {
+            return;
+        }
+        let segments = segments.max(3);
+
+        // Outward normal of every profile segment
+        let segment_normals: Vec<Vec2> = points
+            .windows(2)
+            .map(|w| {
+                let d = w[1] - w[0];
+                Vec2::new(d.y, -d.x).normalize_or_zero()
+            })
+            .collect();
+
+        // Texture V follows the arc length of the silhouette
+        let mut lengths = vec![0.0];
+        for w in points.windows(2) {
+            lengths.push(lengths.last().unwrap() + w[0].distance(w[1]));
+        }
+        let total_length = lengths.last().copied().unwrap_or(1.0).max(f32::EPSILON);
+
+        // For each point: ring used to close the incoming segment and ring used to open the outgoing one
+        let mut ring_in = Vec::with_capacity(points.len());
+        let mut ring_out = Vec::with_capacity(points.len());
+
+        for (i, point) in points.iter().enumerate() {
+            let v = lengths[i] / total_length;
+            let incoming = (i > 0).then(|| segment_normals[i - 1]);
+            let outgoing = segment_normals.get(i).copied();
+
+            match (incoming, outgoing) {
+                (Some(a), Some(b)) if a.angle_to(b).abs() > CREASE_ANGLE_DEGREES.to_radians() => {
+                    ring_in.push(add_lathe_ring(mesh_data, *point, a, v, offset, segments));
+                    ring_out.push(add_lathe_ring(mesh_data, *point, b, v, offset, segments));
+                }
+                (a, b) => {
+                    let normal = (a.unwrap_or(Vec2::ZERO) + b.unwrap_or(Vec2::ZERO)).normalize_or_zero();
+                    let ring = add_lathe_ring(mesh_data, *point, normal, v, offset, segments);
+                    ring_in.push(ring);
+                    ring_out.push(ring);
+                }
+            }
+        }
+
+        for i in 0..points.len() - 1 {
+            add_strip_indices(mesh_data, ring_out[i], ring_in[i + 1], segments);
+        }
+    }
+}
+
+fn add_lathe_ring(
+    mesh_data: &mut MeshData,
+    point: Vec2,
+    normal: Vec2,
+    v: f32,
+    offset: Vec3,
+    segments: u32,
+) -> u32 {
+    let start_idx = mesh_data.verticles.len() as u32;
+
+    // One extra column so the texture seam gets its own vertices
+    for i in 0..=segments {
+        let u = i as f32 / segments as f32;
+        let angle = u * std::f32::consts::TAU;
+        let (sin_a, cos_a) = angle.sin_cos();
+
+        mesh_data.add_vertex(
+            offset + Vec3::new(point.x * cos_a, point.y, point.x * sin_a),
+            Vec3::new(normal.x * cos_a, normal.y, normal.x * sin_a),
+            Vec2::new(u, 1.0 - v),
         );
     }
 
-    mesh_data.to_mesh()
+    start_idx
 }
 
-pub fn generate_detailed_king_mesh() -> Mesh {
-    let mut mesh_data = MeshData::new();
+/// Connects two rings of `segments + 1` vertices, `lower` before `upper` along the surface.
+fn add_strip_indices(mesh_data: &mut MeshData, lower: u32, upper: u32, segments: u32) {
+    for i in 0..segments {
+        let a0 = lower + i;
+        let a1 = lower + i + 1;
+        let b0 = upper + i;
+        let b1 = upper + i + 1;
 
-    add_cylinder_to_mesh(
-        &mut mesh_data,
-        Vec3::ZERO,
-        PIECE_RADIUS,
-        KING_HEIGHT * 0.3,
-        8
-    );
+        mesh_data.indices.extend_from_slice(&[a0, b0, b1]);
+        mesh_data.indices.extend_from_slice(&[a0, b1, a1]);
+    }
+}
 
-    add_tapered_cylinder_to_mesh(
-        &mut mesh_data,
-        Vec3::new(0.0, KING_HEIGHT * 0.3, 0.0),
-        PIECE_RADIUS * 0.9,
-        PIECE_RADIUS * 0.6,
-        KING_HEIGHT * 0.5,
-        8
-    );
+/// Elliptical cross-section of a lofted surface.
+#[derive(Debug, Clone, Copy)]
+pub struct LoftSection {
+    pub center: Vec3,
+    /// Half-width along the X axis.
+    pub width: f32,
+    /// Half-depth perpendicular to both X and the spine.
+    pub depth: f32,
+}
 
-    add_cylinder_to_mesh(
-        &mut mesh_data,
-        Vec3::new(0.0, KING_HEIGHT * 0.8, 0.0),
-        PIECE_RADIUS * 0.7,
-        KING_HEIGHT * 0.1,
-        8
-    );
+impl LoftSection {
+    pub fn new(center: Vec3, width: f32, depth: f32) -> Self {
+        Self { center, width, depth }
+    }
+}
 
-    add_box_to_mesh(
-        &mut mesh_data,
-        Vec3::new(0.0, KING_HEIGHT * 0.95, 0.0),
-        Vec3::new(0.05, KING_HEIGHT * 0.2, 0.05)
-    );
+/// Skins a sequence of cross-sections whose centers form a spine in the YZ plane.
+fn add_loft_to_mesh(mesh_data: &mut MeshData, sections: &[LoftSection], segments: u32) {
+    if sections.len() < 2 {
+        return;
+    }
+    let segments = segments.max(3);
+    let start_idx = mesh_data.verticles.len() as u32;
 
-    add_box_to_mesh(
-        &mut mesh_data,
-        Vec3::new(0.0, KING_HEIGHT * 1.0, 0.0),
-        Vec3::new(0.15, 0.05, 0.05)
-    );
+    for (i, section) in sections.iter().enumerate() {
+        let prev = sections[i.saturating_sub(1)].center;
+        let next = sections[(i + 1).min(sections.len() - 1)].center;
+        let tangent = (next - prev).normalize_or(Vec3::Y);
+        let side = Vec3::X;
+        let across = side.cross(tangent).normalize_or(Vec3::Z);
+        let v = i as f32 / (sections.len() - 1) as f32;
 
-    mesh_data.to_mesh()
+        for j in 0..=segments {
+            let u = j as f32 / segments as f32;
+            let (sin_a, cos_a) = (u * std::f32::consts::TAU).sin_cos();
+            let position = section.center
+                + side * section.width * cos_a
+                + across * section.depth * sin_a;
+            let normal = (side * cos_a / section.width.max(1e-4)
+                + across * sin_a / section.depth.max(1e-4))
+                .normalize_or_zero();
+
+            mesh_data.add_vertex(position, normal, Vec2::new(u, 1.0 - v));
+        }
+    }
+
+    let ring = segments + 1;
+    for i in 0..sections.len() as u32 - 1 {
+        add_strip_indices(mesh_data, start_idx + i * ring, start_idx + (i + 1) * ring, segments);
+    }
+
+    // Account for the spine curvature and changing radii in the normals
+    mesh_data.smooth_normals(start_idx, ring);
 }
 
 struct MeshData {
@@ -278,6 +506,40 @@ impl MeshData {
         idx
     }
 
+    /// Recomputes area-weighted normals for vertices from `start_idx` on.
+    /// `seam_stride` welds the first and last column of every ring.
+    fn smooth_normals(&mut self, start_idx: u32, seam_stride: u32) {
+        let start = start_idx as usize;
+        let mut accumulated = vec![Vec3::ZERO; self.verticles.len() - start];
+
+        for tri in self.indices.chunks_exact(3) {
+            let [a, b, c] = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
+            if a < start || b < start || c < start {
+                continue;
+            }
+            let face = (self.verticles[b] - self.verticles[a])
+                .cross(self.verticles[c] - self.verticles[a]);
+            for idx in [a, b, c] {
+                accumulated[idx - start] += face;
+            }
+        }
+
+        let stride = seam_stride as usize;
+        if stride > 1 {
+            for ring in accumulated.chunks_exact_mut(stride) {
+                let welded = ring[0] + ring[stride - 1];
+                ring[0] = welded;
+                ring[stride - 1] = welded;
+            }
+        }
+
+        for (i, normal) in accumulated.into_iter().enumerate() {
+            if let Some(normal) = normal.try_normalize() {
+                self.normals[start + i] = normal;
+            }
+        }
+    }
+
     fn to_mesh(self) -> Mesh {
         let mut mesh = Mesh::new(bevy::render::render_resource::PrimitiveTopology::TriangleList, default());
         mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.verticles);
@@ -288,93 +550,9 @@ impl MeshData {
     }
 }
 
-fn add_cylinder_to_mesh(mesh_data: &mut MeshData, center: Vec3, radius: f32, height: f32, segments: u32) {
-    let start_idx = mesh_data.verticles.len() as u32;
-
-    let top_center = mesh_data.add_vertex(
-        center + Vec3::new(0.0, height / 2.0, 0.0),
-        Vec3::Y,
-        Vec2::new(0.5, 0.5)
-    );
-    let bottom_center = mesh_data.add_vertex(
-        center + Vec3::new(0.0, -height / 2.0, 0.0),
-        Vec3::NEG_Y,
-        Vec2::new(0.5, 0.5)
-    );
-
-    for i in 0..segments {
-        let angle = i as f32 * 2.0 * std::f32::consts::PI / segments as f32;
-        let cos_a = angle.cos();
-        let sin_a = angle.sin();
-        let x = cos_a * radius;
-        let z = sin_a * radius;
-
-        // top vertex
-        mesh_data.add_vertex(
-            center + Vec3::new(x, height / 2.0, z),
-            Vec3::Y,
-            Vec2::new((cos_a + 1.0) * 0.5, (sin_a + 1.0) * 0.5)
-        );
-        // bottom vertex
-        mesh_data.add_vertex(
-            center + Vec3::new(x, -height / 2.0, z),
-            Vec3::NEG_Y,
-            Vec2::new((cos_a + 1.0) * 0.5, (sin_a + 1.0) * 0.5)
-        );
-
-        // side normal
-        let side_normal = Vec3::new(cos_a, 0.0, sin_a);
-        // top
-        mesh_data.add_vertex(
-            center + Vec3::new(x, height / 2.0, z),
-            side_normal,
-            Vec2::new(i as f32 / segments as f32, 1.0)
-        );
-        // bottom
-        mesh_data.add_vertex(
-            center + Vec3::new(x, -height / 2.0, z),
-            side_normal,
-            Vec2::new(i as f32 / segments as f32, 0.0)
-        );
-
-        let next_i = (i + 1) % segments;
-        let next_side_top = start_idx + 2 + next_i * 4 + 2;
-        let next_side_bottom = start_idx + 2 + next_i * 4 + 3;
-
-        // triangle on top of area
-        mesh_data.indices.extend_from_slice(&[
-            top_center,
-            start_idx + 2 + i * 4,
-            start_idx + 2 + next_i * 4,
-        ]);
-
-        // trianle on bottom of area
-        mesh_data.indices.extend_from_slice(&[
-            bottom_center,
-            start_idx + 2 + next_i * 4 + 1,
-            start_idx + 2 + i * 4 + 1,
-        ]);
-
-        // square of side area (two triangles)
-        let curr_side_top = start_idx + 2 + i * 4 + 2;
-        let curr_side_bottom = start_idx + 2 + i * 4 + 3;
-
-        mesh_data.indices.extend_from_slice(&[
-            curr_side_top,
-            curr_side_bottom,
-            next_side_top,
-        ]);
-        mesh_data.indices.extend_from_slice(&[
-            next_side_top,
-            curr_side_bottom,
-            next_side_bottom,
-        ]);
-    }
-}
-
 pub fn generate_simple_piece_mesh(piece_type: crate::game::pieces::PieceType) -> Mesh {
     use crate::core::constants::*;
-    
+
     match piece_type {
         crate::game::pieces::PieceType::Pawn => {
             Mesh::from(Sphere::new(PIECE_RADIUS * 0.7))
@@ -397,34 +575,546 @@ pub fn generate_simple_piece_mesh(piece_type: crate::game::pieces::PieceType) ->
     }
 }
 
-// 以下すべて実装が長くなりそうなため簡略化
+/// Capped frustum centered on `center`.
 fn add_tapered_cylinder_to_mesh(
-    mesh_data: &mut MeshData, 
-    center: Vec3, 
-    bottom_radius: f32, 
-    top_radius: f32, 
-    height: f32, 
+    mesh_data: &mut MeshData,
+    center: Vec3,
+    bottom_radius: f32,
+    top_radius: f32,
+    height: f32,
     segments: u32
 ) {
-    // テーパー付きシリンダーの実装
-    // 簡略化のため、通常のシリンダーとして実装
-    add_cylinder_to_mesh(mesh_data, center, (bottom_radius + top_radius) * 0.5, height, segments);
+    LatheProfile::new(1)
+        .point(0.0, -height / 2.0)
+        .point(bottom_radius, -height / 2.0)
+        .point(top_radius, height / 2.0)
+        .point(0.0, height / 2.0)
+        .add_to_mesh(mesh_data, center, segments);
 }
 
-fn add_sphere_to_mesh(_mesh_data: &mut MeshData, _center: Vec3, radius: f32, rings: u32, sectors: u32) {
-    // 球体の実装を簡略化
-    let _sphere_mesh = Sphere::new(radius).mesh().uv(sectors, rings);
-    // ここでは簡略化のため、基本的な球体として追加
-    // 実際の実装では sphere_mesh の頂点データを mesh_data に追加する
+fn add_sphere_to_mesh(mesh_data: &mut MeshData, center: Vec3, radius: f32, rings: u32, sectors: u32) {
+    add_ellipsoid_to_mesh(mesh_data, center, Vec3::splat(radius), rings, sectors);
 }
 
 fn add_elongated_sphere_to_mesh(mesh_data: &mut MeshData, center: Vec3, scale: Vec3, rings: u32, sectors: u32) {
-    // 拡張された球体の実装
-    add_sphere_to_mesh(mesh_data, center, scale.x.max(scale.y).max(scale.z), rings, sectors);
+    add_ellipsoid_to_mesh(mesh_data, center, scale, rings, sectors);
+}
+
+fn add_ellipsoid_to_mesh(mesh_data: &mut MeshData, center: Vec3, radii: Vec3, rings: u32, sectors: u32) {
+    let rings = rings.max(2);
+    let sectors = sectors.max(3);
+    let start_idx = mesh_data.verticles.len() as u32;
+
+    for ring in 0..=rings {
+        let v = ring as f32 / rings as f32;
+        let latitude = (v - 0.5) * std::f32::consts::PI;
+        let (sin_lat, cos_lat) = latitude.sin_cos();
+
+        for sector in 0..=sectors {
+            let u = sector as f32 / sectors as f32;
+            let (sin_lon, cos_lon) = (u * std::f32::consts::TAU).sin_cos();
+            let unit = Vec3::new(cos_lat * cos_lon, sin_lat, cos_lat * sin_lon);
+
+            mesh_data.add_vertex(
+                center + unit * radii,
+                (unit / radii).normalize_or(unit),
+                Vec2::new(u, 1.0 - v),
+            );
+        }
+    }
+
+    for ring in 0..rings {
+        add_strip_indices(
+            mesh_data,
+            start_idx + ring * (sectors + 1),
+            start_idx + (ring + 1) * (sectors + 1),
+            sectors,
+        );
+    }
+}
+
+fn add_box_to_mesh(mesh_data: &mut MeshData, center: Vec3, size: Vec3) {
+    add_oriented_box_to_mesh(mesh_data, center, size, Quat::IDENTITY);
+}
+
+fn add_oriented_box_to_mesh(mesh_data: &mut MeshData, center: Vec3, size: Vec3, rotation: Quat) {
+    let half = size * 0.5;
+    // (normal, u axis, v axis) with u x v == normal so every face winds counter-clockwise
+    let faces = [
+        (Vec3::X, Vec3::NEG_Z, Vec3::Y),
+        (Vec3::NEG_X, Vec3::Z, Vec3::Y),
+        (Vec3::Y, Vec3::X, Vec3::NEG_Z),
+        (Vec3::NEG_Y, Vec3::X, Vec3::Z),
+        (Vec3::Z, Vec3::X, Vec3::Y),
+        (Vec3::NEG_Z, Vec3::NEG_X, Vec3::Y),
+    ];
+
+    for (normal, u_axis, v_axis) in faces {
+        let face_center = normal * half;
+        let u = u_axis * half;
+        let v = v_axis * half;
+        let corners = [
+            (face_center - u - v, Vec2::new(0.0, 1.0)),
+            (face_center + u - v, Vec2::new(1.0, 1.0)),
+            (face_center + u + v, Vec2::new(1.0, 0.0)),
+            (face_center - u + v, Vec2::new(0.0, 0.0)),
+        ];
+
+        let start_idx = mesh_data.verticles.len() as u32;
+        for (corner, uv) in corners {
+            mesh_data.add_vertex(center + rotation * corner, rotation * normal, uv);
+        }
+        mesh_data.indices.extend_from_slice(&[
+            start_idx, start_idx + 1, start_idx + 2,
+            start_idx, start_idx + 2, start_idx + 3,
+        ]);
+    }
+}
+
+/// Raised border around the 8x8 squares, open in the middle.
+pub fn generate_board_frame_mesh(inner_half: f32, width: f32, bottom: f32, top: f32) -> Mesh {
+    let mut mesh_data = MeshData::new();
+    let height = top - bottom;
+    let y = (top + bottom) * 0.5;
+    let middle = inner_half + width * 0.5;
+    let long_side = (inner_half + width) * 2.0;
+    let short_side = inner_half * 2.0;
+
+    add_box_to_mesh(&mut mesh_data, Vec3::new(0.0, y, -middle), Vec3::new(long_side, height, width));
+    add_box_to_mesh(&mut mesh_data, Vec3::new(0.0, y, middle), Vec3::new(long_side, height, width));
+    add_box_to_mesh(&mut mesh_data, Vec3::new(-middle, y, 0.0), Vec3::new(width, height, short_side));
+    add_box_to_mesh(&mut mesh_data, Vec3::new(middle, y, 0.0), Vec3::new(width, height, short_side));
+
+    mesh_data.to_mesh()
+}
+
+/// Polylines of a small stroke font covering the board coordinates.
+/// Glyph space: x in `0..0.6`, baseline at y = 0, ascenders at y = 1.
+fn glyph_strokes(ch: char) -> Option<&'static [&'static [(f32, f32)]]> {
+    let strokes: &'static [&'static [(f32, f32)]] = match ch {
+        'a' => &[
+            &[(0.1, 0.6), (0.45, 0.6), (0.55, 0.5), (0.55, 0.0)],
+            &[(0.55, 0.35), (0.15, 0.35), (0.05, 0.25), (0.05, 0.1), (0.15, 0.0), (0.55, 0.0)],
+        ],
+        'b' => &[&[(0.05, 1.0), (0.05, 0.0), (0.45, 0.0), (0.55, 0.1), (0.55, 0.5), (0.45, 0.6), (0.05, 0.6)]],
+        'c' => &[&[(0.55, 0.6), (0.15, 0.6), (0.05, 0.5), (0.05, 0.1), (0.15, 0.0), (0.55, 0.0)]],
+        'd' => &[&[(0.55, 1.0), (0.55, 0.0), (0.15, 0.0), (0.05, 0.1), (0.05, 0.5), (0.15, 0.6), (0.55, 0.6)]],
+        'e' => &[&[
+            (0.05, 0.3), (0.55, 0.3), (0.55, 0.5), (0.45, 0.6), (0.15, 0.6),
+            (0.05, 0.5), (0.05, 0.1), (0.15, 0.0), (0.55, 0.0),
+        ]],
+        'f' => &[&[(0.5, 1.0), (0.35, 1.0), (0.25, 0.9), (0.25, 0.0)], &[(0.05, 0.6), (0.5, 0.6)]],
+        'g' => &[
+            &[(0.55, 0.6), (0.55, -0.3), (0.45, -0.4), (0.1, -0.4)],
+            &[(0.55, 0.6), (0.15, 0.6), (0.05, 0.5), (0.05, 0.1), (0.15, 0.0), (0.55, 0.0)],
+        ],
+        'h' => &[&[(0.05, 1.0), (0.05, 0.0)], &[(0.05, 0.6), (0.45, 0.6), (0.55, 0.5), (0.55, 0.0)]],
+        '1' => &[&[(0.15, 0.8), (0.35, 1.0), (0.35, 0.0)], &[(0.15, 0.0), (0.55, 0.0)]],
+        '2' => &[&[(0.05, 0.85), (0.2, 1.0), (0.45, 1.0), (0.55, 0.9), (0.55, 0.65), (0.05, 0.0), (0.55, 0.0)]],
+        '3' => &[&[
+            (0.05, 1.0), (0.55, 1.0), (0.3, 0.6), (0.45, 0.6), (0.55, 0.5),
+            (0.55, 0.1), (0.45, 0.0), (0.05, 0.0),
+        ]],
+        '4' => &[&[(0.45, 0.0), (0.45, 1.0), (0.05, 0.3), (0.6, 0.3)]],
+        '5' => &[&[
+            (0.55, 1.0), (0.05, 1.0), (0.05, 0.55), (0.45, 0.55), (0.55, 0.45),
+            (0.55, 0.1), (0.45, 0.0), (0.05, 0.0),
+        ]],
+        '6' => &[&[
+            (0.5, 1.0), (0.2, 1.0), (0.05, 0.8), (0.05, 0.1), (0.15, 0.0),
+            (0.45, 0.0), (0.55, 0.1), (0.55, 0.45), (0.45, 0.55), (0.05, 0.55),
+        ]],
+        '7' => &[&[(0.05, 1.0), (0.55, 1.0), (0.2, 0.0)]],
+        '8' => &[&[
+            (0.15, 0.55), (0.05, 0.65), (0.05, 0.9), (0.15, 1.0), (0.45, 1.0), (0.55, 0.9),
+            (0.55, 0.65), (0.45, 0.55), (0.15, 0.55), (0.05, 0.45), (0.05, 0.1), (0.15, 0.0),
+            (0.45, 0.0), (0.55, 0.1), (0.55, 0.45), (0.45, 0.55),
+        ]],
+        _ => return None,
+    };
+    Some(strokes)
+}
+
+/// Flat glyph lying in the XZ plane, facing +Y and reading along +X with its top towards -Z.
+/// `size` is the height of a digit; the glyph is centred on the origin.
+pub fn generate_glyph_mesh(ch: char, size: f32, stroke_width: f32, depth: f32) -> Option<Mesh> {
+    let strokes = glyph_strokes(ch)?;
+    let mut mesh_data = MeshData::new();
+    let to_world = |(x, y): (f32, f32)| Vec3::new((x - 0.3) * size, depth * 0.5, -(y - 0.5) * size);
+
+    for stroke in strokes {
+        for segment in stroke.windows(2) {
+            let (start, end) = (to_world(segment[0]), to_world(segment[1]));
+            let delta = end - start;
+            let length = delta.length();
+            // Extending each segment by the stroke width closes the gaps at the joints
+            let rotation = Quat::from_rotation_y((-delta.z).atan2(delta.x));
+            add_oriented_box_to_mesh(
+                &mut mesh_data,
+                (start + end) * 0.5,
+                Vec3::new(length + stroke_width, depth, stroke_width),
+                rotation,
+            );
+        }
+    }
+
+    Some(mesh_data.to_mesh())
+}
+
+// Procedural textures
+
+/// Surface patterns that can be generated on the CPU when no texture files are shipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProceduralTexture {
+    Wood,
+    Marble,
+}
+
+impl ProceduralTexture {
+    pub const ALL: [ProceduralTexture; 2] = [ProceduralTexture::Wood, ProceduralTexture::Marble];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProceduralTexture::Wood => "wood",
+            ProceduralTexture::Marble => "marble",
+        }
+    }
+}
+
+/// Tileable maps for one pattern. Albedo is near-white so the theme colour tints it.
+pub struct TextureMaps {
+    pub albedo: Image,
+    /// Metallic/roughness layout expected by `StandardMaterial`: roughness in G, metallic in B.
+    /// Both are multipliers of the material's scalar values.
+    pub roughness: Image,
+    pub normal: Image,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TextureMapHandles {
+    pub albedo: Handle<Image>,
+    pub roughness: Handle<Image>,
+    pub normal: Handle<Image>,
 }
 
-fn add_box_to_mesh(_mesh_data: &mut MeshData, _center: Vec3, size: Vec3) {
-    // 箱の実装を簡略化
-    let _box_mesh = Mesh::from(Cuboid::new(size.x, size.y, size.z));
-    // 実際の実装では box_mesh の頂点データを mesh_data に追加する
-}
\ No newline at end of file
+#[derive(Resource, Default)]
+pub struct ProceduralTextures {
+    /// Seed the maps in `maps` were generated with.
+    pub seed: u32,
+    pub maps: std::collections::HashMap<ProceduralTexture, TextureMapHandles>,
+    /// Maps still being generated on the async compute pool.
+    pending: std::collections::HashMap<ProceduralTexture, Task<TextureMaps>>,
+}
+
+impl ProceduralTextures {
+    pub fn get(&self, kind: ProceduralTexture) -> Option<&TextureMapHandles> {
+        self.maps.get(&kind)
+    }
+
+    pub fn is_generating(&self) -> bool {
+        !self.pending.is_empty()
+    }
+}
+
+/// Starts generating the patterns the selected theme falls back to, off the main thread.
+/// A new seed throws away every map made with the old one.
+pub fn request_procedural_textures(
+    settings: Res<GameSettings>,
+    library: Res<ThemeLibrary>,
+    mut textures: ResMut<ProceduralTextures>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    if textures.seed != settings.texture_seed {
+        textures.seed = settings.texture_seed;
+        textures.maps.clear();
+        textures.pending.clear();
+    }
+
+    let Some(theme) = library.get(&settings.theme).or_else(|| library.get(DEFAULT_THEME)) else {
+        return;
+    };
+    let seed = textures.seed;
+    for kind in theme.procedural_textures() {
+        if textures.maps.contains_key(&kind) || textures.pending.contains_key(&kind) {
+            continue;
+        }
+        let task = AsyncComputeTaskPool::get()
+            .spawn(async move { load_or_generate_texture_maps(kind, PROCEDURAL_TEXTURE_SIZE, seed) });
+        textures.pending.insert(kind, task);
+    }
+}
+
+/// Adds finished maps to `Assets<Image>` and has the theme applied again so materials pick them up.
+pub fn finish_procedural_textures(
+    mut textures: ResMut<ProceduralTextures>,
+    mut images: ResMut<Assets<Image>>,
+    mut library: ResMut<ThemeLibrary>,
+) {
+    if !textures.is_generating() {
+        return;
+    }
+
+    let textures = textures.as_mut();
+    let mut finished = false;
+    textures.pending.retain(|kind, task| {
+        let Some(maps) = check_ready(task) else {
+            return true;
+        };
+        textures.maps.insert(*kind, TextureMapHandles {
+            albedo: images.add(maps.albedo),
+            roughness: images.add(maps.roughness),
+            normal: images.add(maps.normal),
+        });
+        finished = true;
+        false
+    });
+
+    if finished {
+        library.active = None;
+    }
+}
+
+/// Sampler used by every board/piece texture so UV scales above 1 tile instead of clamping.
+pub fn repeating_sampler() -> bevy::image::ImageSampler {
+    use bevy::image::{ImageAddressMode, ImageSampler, ImageSamplerDescriptor};
+
+    ImageSampler::Descriptor(ImageSamplerDescriptor {
+        address_mode_u: ImageAddressMode::Repeat,
+        address_mode_v: ImageAddressMode::Repeat,
+        ..ImageSamplerDescriptor::linear()
+    })
+}
+
+pub fn generate_texture_maps(kind: ProceduralTexture, size: u32, seed: u32) -> TextureMaps {
+    let texels = (size * size) as usize;
+    let mut albedo = Vec::with_capacity(texels);
+    let mut roughness = Vec::with_capacity(texels);
+    let mut height = Vec::with_capacity(texels);
+
+    for y in 0..size {
+        for x in 0..size {
+            let u = x as f32 / size as f32;
+            let v = y as f32 / size as f32;
+            let sample = match kind {
+                ProceduralTexture::Wood => sample_wood(u, v, seed),
+                ProceduralTexture::Marble => sample_marble(u, v, seed),
+            };
+            albedo.push(sample.albedo);
+            roughness.push(sample.roughness);
+            height.push(sample.height);
+        }
+    }
+
+    let strength = match kind {
+        ProceduralTexture::Wood => 2.0,
+        ProceduralTexture::Marble => 0.75,
+    };
+
+    TextureMaps {
+        albedo: rgba_image(size, albedo.iter().map(|c| [c.x, c.y, c.z, 1.0]), true),
+        roughness: rgba_image(size, roughness.iter().map(|&r| [1.0, r, 1.0, 1.0]), false),
+        normal: rgba_image(size, normals_from_height(&height, size, strength), false),
+    }
+}
+
+struct TexelSample {
+    albedo: Vec3,
+    roughness: f32,
+    height: f32,
+}
+
+/// Growth rings across V with fine fibres running along U.
+fn sample_wood(u: f32, v: f32, seed: u32) -> TexelSample {
+    let warp = fractal_noise(u, v, (2, 3), 4, seed);
+    let rings = (v * 10.0 + warp * 1.8).fract();
+    // Late wood: a thin dark band at the end of every ring
+    let late_wood = smoothstep(0.55, 0.85, rings) * (1.0 - smoothstep(0.9, 1.0, rings));
+    let fibres = fractal_noise(u, v, (4, 96), 3, seed.wrapping_add(17));
+    let knots = smoothstep(0.7, 0.9, fractal_noise(u, v, (3, 3), 2, seed.wrapping_add(31)));
+
+    let shade = 1.0 - 0.32 * late_wood - 0.12 * fibres - 0.15 * knots;
+    TexelSample {
+        albedo: Vec3::new(shade, shade * 0.97, shade * 0.93),
+        roughness: 0.7 + 0.2 * late_wood + 0.1 * fibres,
+        height: 1.0 - 0.6 * late_wood - 0.4 * fibres,
+    }
+}
+
+/// Soft clouding with sharp turbulent veins.
+fn sample_marble(u: f32, v: f32, seed: u32) -> TexelSample {
+    let turbulence = fractal_noise(u, v, (4, 4), 5, seed);
+    let phase = ((u + v) * 2.0 + turbulence * 3.0) * std::f32::consts::TAU;
+    let vein = (1.0 - phase.sin().abs()).powf(10.0);
+    let fine_phase = ((u - v) * 5.0 + turbulence * 4.0) * std::f32::consts::TAU;
+    let fine_vein = (1.0 - fine_phase.sin().abs()).powf(24.0) * 0.5;
+    let cloud = fractal_noise(u, v, (2, 2), 3, seed.wrapping_add(53));
+
+    let veins = (vein + fine_vein).min(1.0);
+    let shade = 1.0 - 0.45 * veins - 0.1 * cloud;
+    TexelSample {
+        albedo: Vec3::new(shade, shade, shade * 1.02).min(Vec3::ONE),
+        roughness: 0.75 + 0.25 * veins,
+        height: 1.0 - veins,
+    }
+}
+
+fn rgba_image(size: u32, texels: impl Iterator<Item = [f32; 4]>, is_srgb: bool) -> Image {
+    use bevy::render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    };
+
+    let data: Vec<u8> = texels
+        .flat_map(|texel| texel.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8))
+        .collect();
+    let format = if is_srgb { TextureFormat::Rgba8UnormSrgb } else { TextureFormat::Rgba8Unorm };
+
+    let mut image = Image::new(
+        Extent3d { width: size, height: size, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        format,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = repeating_sampler();
+    image
+}
+
+/// Tangent-space normals (+Y up) from central differences of a tileable height field.
+fn normals_from_height(height: &[f32], size: u32, strength: f32) -> impl Iterator<Item = [f32; 4]> + '_ {
+    let size = size as i64;
+    let at = move |x: i64, y: i64| height[(y.rem_euclid(size) * size + x.rem_euclid(size)) as usize];
+
+    (0..size * size).map(move |i| {
+        let (x, y) = (i % size, i / size);
+        let dx = (at(x + 1, y) - at(x - 1, y)) * strength;
+        let dy = (at(x, y + 1) - at(x, y - 1)) * strength;
+        let normal = Vec3::new(-dx, dy, 1.0).normalize();
+        [normal.x * 0.5 + 0.5, normal.y * 0.5 + 0.5, normal.z * 0.5 + 0.5, 1.0]
+    })
+}
+
+/// Value noise summed over octaves, wrapping at the texture edges. Returns roughly `0..1`.
+fn fractal_noise(u: f32, v: f32, base_period: (i32, i32), octaves: u32, seed: u32) -> f32 {
+    let mut sum = 0.0;
+    let mut amplitude = 1.0;
+    let mut total = 0.0;
+    let (mut px, mut py) = base_period;
+
+    for octave in 0..octaves {
+        sum += amplitude * tileable_value_noise(u * px as f32, v * py as f32, px, py, seed.wrapping_add(octave));
+        total += amplitude;
+        amplitude *= 0.5;
+        px *= 2;
+        py *= 2;
+    }
+    sum / total
+}
+
+fn tileable_value_noise(x: f32, y: f32, period_x: i32, period_y: i32, seed: u32) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (sx, sy) = (fx * fx * (3.0 - 2.0 * fx), fy * fy * (3.0 - 2.0 * fy));
+    let (ix, iy) = (x0 as i32, y0 as i32);
+
+    let corner = |dx: i32, dy: i32| {
+        lattice_value((ix + dx).rem_euclid(period_x), (iy + dy).rem_euclid(period_y), seed)
+    };
+    let top = corner(0, 0) + (corner(1, 0) - corner(0, 0)) * sx;
+    let bottom = corner(0, 1) + (corner(1, 1) - corner(0, 1)) * sx;
+    top + (bottom - top) * sy
+}
+
+fn lattice_value(x: i32, y: i32, seed: u32) -> f32 {
+    let mut h = (x as u32).wrapping_mul(0x27d4_eb2d)
+        ^ (y as u32).wrapping_mul(0x1656_67b1)
+        ^ seed.wrapping_mul(0x9e37_79b9);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^= h >> 16;
+    h as f32 / u32::MAX as f32
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Soft radial falloff used for blob shadows; only the alpha channel varies.
+pub fn generate_blob_shadow_texture(size: u32) -> Image {
+    let half = size as f32 / 2.0;
+    let texels = (0..size * size).map(|i| {
+        let (x, y) = ((i % size) as f32 + 0.5 - half, (i / size) as f32 + 0.5 - half);
+        let distance = (x * x + y * y).sqrt() / half;
+        let alpha = (1.0 - smoothstep(0.0, 1.0, distance)).powi(2);
+        [1.0, 1.0, 1.0, alpha]
+    });
+    rgba_image(size, texels, true)
+}
+
+fn texture_cache_path(kind: ProceduralTexture, size: u32, seed: u32, map: &str) -> std::path::PathBuf {
+    asset_paths::on_disk(asset_paths::GENERATED_TEXTURES_DIR)
+        .join(format!("{}_{:08x}_{}_{}.png", kind.name(), seed, size, map))
+}
+
+/// Reads the maps from the on-disk cache, generating and writing them on a miss.
+pub fn load_or_generate_texture_maps(kind: ProceduralTexture, size: u32, seed: u32) -> TextureMaps {
+    let paths = ["albedo", "roughness", "normal"].map(|map| texture_cache_path(kind, size, seed, map));
+
+    let cached = (|| {
+        Some(TextureMaps {
+            albedo: read_cached_image(&paths[0], true)?,
+            roughness: read_cached_image(&paths[1], false)?,
+            normal: read_cached_image(&paths[2], false)?,
+        })
+    })();
+    if let Some(maps) = cached {
+        debug!("Loaded cached {} textures", kind.name());
+        return maps;
+    }
+
+    info!("Generating {} textures ({}x{}, seed {})", kind.name(), size, size, seed);
+    let maps = generate_texture_maps(kind, size, seed);
+    for (path, image) in paths.iter().zip([&maps.albedo, &maps.roughness, &maps.normal]) {
+        if let Err(err) = write_cached_image(path, image) {
+            warn!("Could not cache {}: {}", path.display(), err);
+        }
+    }
+    maps
+}
+
+fn read_cached_image(path: &std::path::Path, is_srgb: bool) -> Option<Image> {
+    use bevy::{
+        image::{CompressedImageFormats, ImageType},
+        render::render_asset::RenderAssetUsages,
+    };
+
+    let bytes = std::fs::read(path).ok()?;
+    Image::from_buffer(
+        &bytes,
+        ImageType::Extension("png"),
+        CompressedImageFormats::NONE,
+        is_srgb,
+        repeating_sampler(),
+        RenderAssetUsages::default(),
+    )
+    .map_err(|err| warn!("Ignoring corrupt texture cache {}: {}", path.display(), err))
+    .ok()
+}
+
+fn write_cached_image(path: &std::path::Path, image: &Image) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    // PNG stores raw bytes either way; the colour space is chosen again when the cache is read
+    let mut image = image.clone();
+    image.texture_descriptor.format = bevy::render::render_resource::TextureFormat::Rgba8UnormSrgb;
+    image.try_into_dynamic()?.save(path)?;
+    Ok(())
+}
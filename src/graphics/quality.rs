@@ -0,0 +1,105 @@
+use bevy::{
+    core_pipeline::{
+        bloom::Bloom,
+        fxaa::Fxaa,
+        prepass::{DepthPrepass, NormalPrepass},
+    },
+    ecs::system::SystemParam,
+    pbr::{CascadeShadowConfigBuilder, DirectionalLightShadowMap, ScreenSpaceAmbientOcclusion},
+    prelude::*,
+};
+
+use crate::{
+    core::resources::{GameSettings, GraphicsQuality},
+    game::pieces::PieceType,
+    graphics::{ChessMeshes, MainCamera, MainLight, PieceSetState, generate_piece_mesh},
+};
+
+/// Farthest distance from the camera that still receives directional shadows.
+const SHADOW_DISTANCE: f32 = 30.0;
+
+/// What rebuilding the procedural pieces at a new quality touches.
+#[derive(SystemParam)]
+pub struct PieceMeshes<'w> {
+    meshes: ResMut<'w, Assets<Mesh>>,
+    chess_meshes: ResMut<'w, ChessMeshes>,
+    piece_sets: Res<'w, PieceSetState>,
+}
+
+/// Pushes `GameSettings::graphics_quality` into the camera, the main light and the piece meshes.
+/// Cameras and lights spawned later pick up the current preset as well.
+pub fn apply_graphics_quality(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    mut applied: Local<Option<GraphicsQuality>>,
+    cameras: Query<(Entity, Ref<MainCamera>)>,
+    mut lights: Query<(Entity, &mut DirectionalLight, Ref<MainLight>)>,
+    mut shadow_map: ResMut<DirectionalLightShadowMap>,
+    mut pieces: PieceMeshes,
+) {
+    let quality = settings.graphics_quality;
+    let previous = applied.replace(quality);
+    let changed = previous != Some(quality);
+
+    for (camera, marker) in cameras.iter() {
+        if changed || marker.is_added() {
+            configure_camera(&mut commands, camera, quality);
+        }
+    }
+
+    for (entity, mut light, marker) in lights.iter_mut() {
+        if changed || marker.is_added() {
+            light.shadows_enabled = quality.shadows_enabled();
+            commands.entity(entity).insert(
+                CascadeShadowConfigBuilder {
+                    num_cascades: quality.shadow_cascades(),
+                    first_cascade_far_bound: SHADOW_DISTANCE / (quality.shadow_cascades() as f32 + 1.0),
+                    maximum_distance: SHADOW_DISTANCE,
+                    ..default()
+                }
+                .build(),
+            );
+        }
+    }
+
+    if !changed {
+        return;
+    }
+
+    if shadow_map.size != quality.shadow_map_size() {
+        shadow_map.size = quality.shadow_map_size();
+    }
+
+    // The meshes were built with the startup quality; a loaded piece set keeps its own meshes
+    if previous.is_some() && pieces.piece_sets.active.is_none() {
+        for piece_type in PieceType::ALL {
+            let mesh = pieces.meshes.add(generate_piece_mesh(piece_type, quality));
+            pieces.chess_meshes.set_piece_mesh(piece_type, mesh);
+        }
+    }
+
+    info!("Applied {:?} graphics quality", quality);
+}
+
+fn configure_camera(commands: &mut Commands, camera: Entity, quality: GraphicsQuality) {
+    let mut camera = commands.entity(camera);
+    camera.insert(quality.msaa());
+
+    if quality.bloom_enabled() {
+        camera.insert(Bloom::NATURAL);
+    } else {
+        camera.remove::<Bloom>();
+    }
+
+    if quality.ssao_enabled() {
+        camera.insert(ScreenSpaceAmbientOcclusion::default());
+    } else {
+        camera.remove::<(ScreenSpaceAmbientOcclusion, DepthPrepass, NormalPrepass)>();
+    }
+
+    if quality.fxaa_enabled() {
+        camera.insert(Fxaa::default());
+    } else {
+        camera.remove::<Fxaa>();
+    }
+}
@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+
+use bevy::{image::ImageLoaderSettings, math::Affine2, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::{constants::*, resources::GameSettings},
+    graphics::{
+        ChessMaterials, MaterialAnimations, ProceduralTexture, ProceduralTextures, TextureMapHandles,
+        check_pulse_color, repeating_sampler,
+    },
+};
+
+/// Themes compiled into the client; files in `themes/` with the same key override them.
+const BUILTIN_THEMES: [(&str, &str); 4] = [
+    ("classic", include_str!("../../assets/themes/classic.json")),
+    ("wood", include_str!("../../assets/themes/wood.json")),
+    ("marble", include_str!("../../assets/themes/marble.json")),
+    ("metal", include_str!("../../assets/themes/metal.json")),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChessTheme {
+    pub name: String,
+    pub board: BoardTheme,
+    pub pieces: PieceTheme,
+    #[serde(default)]
+    pub highlights: HighlightPalette,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardTheme {
+    pub light_square: ThemeMaterial,
+    pub dark_square: ThemeMaterial,
+    /// Border around the squares; defaults to the dark square material.
+    #[serde(default)]
+    pub frame: Option<ThemeMaterial>,
+    /// Colour of the coordinate labels; defaults to the light square colour.
+    #[serde(default)]
+    pub coordinates: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PieceTheme {
+    pub white: ThemeMaterial,
+    pub black: ThemeMaterial,
+}
+
+/// PBR parameters of a single material. Colours are hex strings (`#RRGGBB` or `#RRGGBBAA`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeMaterial {
+    pub base_color: String,
+    #[serde(default)]
+    pub base_color_texture: Option<String>,
+    #[serde(default)]
+    pub normal_map: Option<String>,
+    #[serde(default)]
+    pub metallic_roughness_texture: Option<String>,
+    /// Generated maps used for any of the textures above that are missing on disk.
+    #[serde(default)]
+    pub procedural: Option<ProceduralTexture>,
+    #[serde(default = "default_roughness")]
+    pub roughness: f32,
+    #[serde(default)]
+    pub metallic: f32,
+    #[serde(default = "default_reflectance")]
+    pub reflectance: f32,
+    #[serde(default = "default_uv_scale")]
+    pub uv_scale: f32,
+}
+
+fn default_roughness() -> f32 {
+    0.5
+}
+
+fn default_reflectance() -> f32 {
+    0.5
+}
+
+fn default_uv_scale() -> f32 {
+    1.0
+}
+
+/// Optional overrides for the highlight colours; unset entries keep the defaults from `constants`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HighlightPalette {
+    pub selected: Option<String>,
+    pub legal_move: Option<String>,
+    pub last_move: Option<String>,
+    pub check: Option<String>,
+    pub capture: Option<String>,
+    pub threat: Option<String>,
+}
+
+impl ChessTheme {
+    /// Generated patterns any of the theme's materials fall back to.
+    pub fn procedural_textures(&self) -> impl Iterator<Item = ProceduralTexture> + '_ {
+        [
+            Some(&self.pieces.white),
+            Some(&self.pieces.black),
+            Some(&self.board.light_square),
+            Some(&self.board.dark_square),
+            self.board.frame.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|material| material.procedural)
+    }
+}
+
+impl ThemeMaterial {
+    pub fn color(&self) -> Color {
+        parse_color(&self.base_color, Color::WHITE)
+    }
+
+    fn apply(
+        &self,
+        material: &mut StandardMaterial,
+        asset_server: &AssetServer,
+        procedural: &ProceduralTextures,
+    ) {
+        let generated = self.procedural.and_then(|kind| procedural.get(kind));
+        let generated_map = |select: fn(&TextureMapHandles) -> &Handle<Image>| generated.map(|maps| select(maps).clone());
+
+        material.base_color = self.color();
+        material.base_color_texture = self
+            .base_color_texture
+            .as_deref()
+            .and_then(|path| load_theme_texture(asset_server, path, true))
+            .or_else(|| generated_map(|maps| &maps.albedo));
+        material.normal_map_texture = self
+            .normal_map
+            .as_deref()
+            .and_then(|path| load_theme_texture(asset_server, path, false))
+            .or_else(|| generated_map(|maps| &maps.normal));
+        material.metallic_roughness_texture = self
+            .metallic_roughness_texture
+            .as_deref()
+            .and_then(|path| load_theme_texture(asset_server, path, false))
+            .or_else(|| generated_map(|maps| &maps.roughness));
+        material.perceptual_roughness = self.roughness.clamp(0.089, 1.0);
+        material.metallic = self.metallic.clamp(0.0, 1.0);
+        material.reflectance = self.reflectance.clamp(0.0, 1.0);
+        material.uv_transform = Affine2::from_scale(Vec2::splat(self.uv_scale));
+    }
+}
+
+impl HighlightPalette {
+    fn entries(&self) -> [(Option<&str>, Color, f32); 6] {
+        [
+            (self.selected.as_deref(), SELECTED_COLOR, 0.3),
+            (self.legal_move.as_deref(), LEGAL_MOVE_COLOR, 0.2),
+            (self.last_move.as_deref(), LAST_MOVE_COLOR, 0.1),
+            (self.check.as_deref(), CHECK_COLOR, 0.5),
+            (self.capture.as_deref(), CAPTURE_COLOR, 0.3),
+            (self.threat.as_deref(), THREAT_COLOR, 0.2),
+        ]
+    }
+}
+
+pub fn parse_color(hex: &str, fallback: Color) -> Color {
+    match Srgba::hex(hex) {
+        Ok(color) => color.into(),
+        Err(err) => {
+            warn!("Invalid theme colour '{}': {}", hex, err);
+            fallback
+        }
+    }
+}
+
+/// Loads a repeating texture, or `None` if the file is not present so the material can fall back.
+fn load_theme_texture(asset_server: &AssetServer, path: &str, is_srgb: bool) -> Option<Handle<Image>> {
+    if !asset_paths::on_disk(path).is_file() {
+        debug!("Theme texture '{}' not found", path);
+        return None;
+    }
+
+    Some(asset_server.load_with_settings(path.to_string(), move |settings: &mut ImageLoaderSettings| {
+        settings.is_srgb = is_srgb;
+        settings.sampler = repeating_sampler();
+    }))
+}
+
+#[derive(Resource, Default)]
+pub struct ThemeLibrary {
+    pub themes: HashMap<String, ChessTheme>,
+    /// Theme currently applied to `ChessMaterials`.
+    pub active: Option<String>,
+}
+
+impl ThemeLibrary {
+    pub fn load() -> Self {
+        let mut themes = HashMap::new();
+
+        for (key, source) in BUILTIN_THEMES {
+            match serde_json::from_str::<ChessTheme>(source) {
+                Ok(theme) => {
+                    themes.insert(key.to_string(), theme);
+                }
+                Err(err) => error!("Built-in theme '{}' is malformed: {}", key, err),
+            }
+        }
+
+        let themes_dir = asset_paths::on_disk(asset_paths::THEMES_DIR);
+        if let Ok(entries) = std::fs::read_dir(themes_dir) {
+            for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+                if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                    continue;
+                }
+                let Some(key) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                    continue;
+                };
+
+                let parsed = std::fs::read_to_string(&path)
+                    .map_err(|err| err.to_string())
+                    .and_then(|source| serde_json::from_str::<ChessTheme>(&source).map_err(|err| err.to_string()));
+                match parsed {
+                    Ok(theme) => {
+                        themes.insert(key.to_string(), theme);
+                    }
+                    Err(err) => warn!("Skipping theme {}: {}", path.display(), err),
+                }
+            }
+        }
+
+        Self { themes, active: None }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&ChessTheme> {
+        self.themes.get(key)
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.themes.keys().map(String::as_str).collect();
+        names.sort();
+        names
+    }
+}
+
+impl ChessMaterials {
+    /// Rewrites the existing material assets so every entity using them picks up the theme.
+    pub fn apply_theme(
+        &self,
+        theme: &ChessTheme,
+        materials: &mut Assets<StandardMaterial>,
+        asset_server: &AssetServer,
+        procedural: &ProceduralTextures,
+    ) {
+        let targets = [
+            (&self.white_piece, &theme.pieces.white),
+            (&self.black_piece, &theme.pieces.black),
+            (&self.light_square, &theme.board.light_square),
+            (&self.dark_square, &theme.board.dark_square),
+            (&self.board_frame, theme.board.frame.as_ref().unwrap_or(&theme.board.dark_square)),
+        ];
+        for (handle, desc) in targets {
+            if let Some(material) = materials.get_mut(handle) {
+                desc.apply(material, asset_server, procedural);
+            }
+        }
+
+        if let Some(material) = materials.get_mut(&self.coordinate_label) {
+            material.base_color = match &theme.board.coordinates {
+                Some(hex) => parse_color(hex, COORDINATE_LABEL_COLOR),
+                None => theme.board.light_square.color(),
+            };
+        }
+
+        let highlights = [
+            &self.selected,
+            &self.legal_move,
+            &self.last_move,
+            &self.check,
+            &self.capture,
+            &self.threat,
+        ];
+        for (handle, (hex, default_color, emissive)) in highlights.into_iter().zip(theme.highlights.entries()) {
+            let color = hex.map_or(default_color, |hex| parse_color(hex, default_color));
+            if let Some(material) = materials.get_mut(handle) {
+                material.base_color = color;
+                material.emissive = scale_color_linear(color, emissive);
+            }
+        }
+    }
+}
+
+pub fn load_theme_library(mut commands: Commands) {
+    let library = ThemeLibrary::load();
+    info!("Loaded themes: {:?}", library.names());
+    commands.insert_resource(library);
+}
+
+pub fn apply_selected_theme(
+    settings: Res<GameSettings>,
+    mut library: ResMut<ThemeLibrary>,
+    chess_materials: Res<ChessMaterials>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    procedural: Res<ProceduralTextures>,
+    mut material_animations: ResMut<MaterialAnimations>,
+) {
+    if !settings.is_changed() && library.active.is_some() {
+        return;
+    }
+
+    let key = if library.get(&settings.theme).is_some() {
+        settings.theme.clone()
+    } else {
+        warn!("Unknown theme '{}', falling back to '{}'", settings.theme, DEFAULT_THEME);
+        DEFAULT_THEME.to_string()
+    };
+    if library.active.as_deref() == Some(key.as_str()) {
+        return;
+    }
+
+    if let Some(theme) = library.get(&key) {
+        info!("Applying theme '{}'", theme.name);
+        chess_materials.apply_theme(theme, &mut materials, &asset_server, &procedural);
+
+        // Resting colours changed under any running animations
+        material_animations.rebase();
+        if let Some(check) = materials.get(&chess_materials.check) {
+            material_animations.recolor(&chess_materials.check.clone().into(), check_pulse_color(check));
+        }
+    }
+    library.active = Some(key);
+}
@@ -0,0 +1,252 @@
+//! Threat overlay: square control tints with per-side attack counts, hanging pieces
+//! and pin lines. Recomputed from the rules layer whenever the board changes; the
+//! markers are spawned once per square and shown or hidden, pin lines are pooled.
+
+use bevy::{pbr::NotShadowCaster, prelude::*};
+
+use crate::{
+    core::{
+        constants::*,
+        events::{GameAction, GameActionEvent},
+        resources::GameSettings,
+    },
+    game::{
+        board::{BoardPosition, ChessBoard},
+        pieces::{ChessPiece, PieceColor},
+        rules::{hanging_pieces, pinned_pieces, AttackMap, Pin},
+    },
+    graphics::{BoardEntity, ChessAssets, ChessMaterials, ChessMeshes},
+};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreatMarker {
+    Control(BoardPosition),
+    /// The `index`-th attack pip of `color` on a square.
+    Pip { pos: BoardPosition, color: PieceColor, index: u8 },
+    Hanging(BoardPosition),
+    /// Slot in the pin line pool.
+    Pin(usize),
+}
+
+impl ThreatMarker {
+    /// Every per-square marker: control tint, hanging ring and attack pips.
+    fn squares() -> impl Iterator<Item = Self> {
+        (0..8)
+            .flat_map(|rank| (0..8).map(move |file| BoardPosition { file, rank }))
+            .flat_map(|pos| {
+                let pips = [PieceColor::White, PieceColor::Black]
+                    .into_iter()
+                    .flat_map(move |color| (0..MAX_THREAT_PIPS).map(move |index| Self::Pip { pos, color, index }));
+                [Self::Control(pos), Self::Hanging(pos)].into_iter().chain(pips)
+            })
+    }
+
+    fn mesh(&self, assets: &ThreatOverlayAssets, meshes: &ChessMeshes) -> Handle<Mesh> {
+        match self {
+            Self::Control(_) => meshes.highlight_square.clone(),
+            Self::Pip { .. } => assets.pip.clone(),
+            Self::Hanging(_) => meshes.highlight_circle.clone(),
+            Self::Pin(_) => assets.line.clone(),
+        }
+    }
+
+    fn name(&self) -> String {
+        match self {
+            Self::Control(pos) => format!("Control {}", pos.to_algebraic()),
+            Self::Pip { pos, .. } => format!("Attack pip {}", pos.to_algebraic()),
+            Self::Hanging(pos) => format!("Hanging {}", pos.to_algebraic()),
+            Self::Pin(index) => format!("Pin line {}", index),
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ThreatOverlayAssets {
+    pub pip: Handle<Mesh>,
+    pub line: Handle<Mesh>,
+    pub white_control: Handle<StandardMaterial>,
+    pub black_control: Handle<StandardMaterial>,
+    pub contested: Handle<StandardMaterial>,
+    pub white_pip: Handle<StandardMaterial>,
+    pub black_pip: Handle<StandardMaterial>,
+    pub pin: Handle<StandardMaterial>,
+}
+
+pub fn setup_threat_overlay(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut assets: ResMut<ThreatOverlayAssets>,
+) {
+    let mut overlay_material = |color: Color| {
+        materials.add(StandardMaterial {
+            base_color: color,
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        })
+    };
+
+    *assets = ThreatOverlayAssets {
+        pip: meshes.add(Cylinder::new(THREAT_PIP_RADIUS, 0.01)),
+        // Unit length along Z, stretched per pin
+        line: meshes.add(Cuboid::new(PIN_LINE_WIDTH, 0.01, 1.0)),
+        white_control: overlay_material(WHITE_CONTROL_COLOR),
+        black_control: overlay_material(BLACK_CONTROL_COLOR),
+        contested: overlay_material(CONTESTED_COLOR),
+        white_pip: overlay_material(WHITE_PIECE_COLOR),
+        black_pip: overlay_material(BLACK_PIECE_COLOR),
+        pin: overlay_material(PIN_COLOR),
+    };
+}
+
+pub fn handle_threat_overlay_actions(
+    mut events: EventReader<GameActionEvent>,
+    mut settings: ResMut<GameSettings>,
+) {
+    for event in events.read() {
+        if let GameAction::ToggleThreatOverlay = event.action {
+            settings.show_threats = !settings.show_threats;
+        }
+    }
+}
+
+/// What the overlay shows for the current board.
+struct ThreatLayout {
+    attack_map: AttackMap,
+    hanging: Vec<BoardPosition>,
+    pins: Vec<Pin>,
+}
+
+impl ThreatLayout {
+    /// Material and transform of `marker`, or `None` while it has nothing to show.
+    fn place(
+        &self,
+        marker: ThreatMarker,
+        assets: &ThreatOverlayAssets,
+        materials: &ChessMaterials,
+    ) -> Option<(Handle<StandardMaterial>, Transform)> {
+        match marker {
+            ThreatMarker::Control(pos) => {
+                let white = self.attack_map.count(pos, PieceColor::White);
+                let black = self.attack_map.count(pos, PieceColor::Black);
+                let control = match white.cmp(&black) {
+                    std::cmp::Ordering::Greater => &assets.white_control,
+                    std::cmp::Ordering::Less => &assets.black_control,
+                    std::cmp::Ordering::Equal if white == 0 => return None,
+                    std::cmp::Ordering::Equal => &assets.contested,
+                };
+                Some((control.clone(), Transform::from_translation(square_center(pos))))
+            }
+            ThreatMarker::Pip { pos, color, index } => {
+                // One pip per attacker, White's along the edge nearest White (-Z); capped
+                let shown = self.attack_map.count(pos, color).min(MAX_THREAT_PIPS);
+                if index >= shown {
+                    return None;
+                }
+                let (edge, material) = match color {
+                    PieceColor::White => (-1.0, &assets.white_pip),
+                    PieceColor::Black => (1.0, &assets.black_pip),
+                };
+                let x = (index as f32 - (shown - 1) as f32 / 2.0) * THREAT_PIP_SPACING;
+                let offset = Vec3::new(x, 0.01, edge * SQUARE_SIZE * 0.35);
+                Some((material.clone(), Transform::from_translation(square_center(pos) + offset)))
+            }
+            ThreatMarker::Hanging(pos) => self.hanging.contains(&pos).then(|| {
+                (
+                    materials.threat.clone(),
+                    Transform::from_translation(pos.to_world_position().with_y(THREAT_OVERLAY_HEIGHT + 0.02)),
+                )
+            }),
+            ThreatMarker::Pin(index) => {
+                let pin = self.pins.get(index)?;
+                let from = pin.pinner.to_world_position().with_y(THREAT_OVERLAY_HEIGHT + 0.03);
+                let to = pin.king.to_world_position().with_y(THREAT_OVERLAY_HEIGHT + 0.03);
+                let length = from.distance(to);
+                let transform = Transform::from_translation((from + to) / 2.0)
+                    .with_rotation(Quat::from_rotation_arc(Vec3::Z, (to - from) / length))
+                    .with_scale(Vec3::new(1.0, 1.0, length));
+                Some((assets.pin.clone(), transform))
+            }
+        }
+    }
+}
+
+fn square_center(pos: BoardPosition) -> Vec3 {
+    pos.to_world_position().with_y(THREAT_OVERLAY_HEIGHT)
+}
+
+pub fn update_threat_overlay(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    board: Res<ChessBoard>,
+    pieces: Query<&ChessPiece>,
+    mut markers: Query<(&ThreatMarker, &mut MeshMaterial3d<StandardMaterial>, &mut Transform, &mut Visibility)>,
+    assets: Res<ThreatOverlayAssets>,
+    chess_assets: ChessAssets,
+) {
+    if !settings.is_changed() && !board.is_changed() {
+        return;
+    }
+
+    if !settings.show_threats {
+        for (_, _, _, mut visibility) in markers.iter_mut() {
+            visibility.set_if_neq(Visibility::Hidden);
+        }
+        return;
+    }
+
+    let attack_map = AttackMap::compute(&board, &pieces);
+    let layout = ThreatLayout {
+        hanging: hanging_pieces(&board, &pieces, &attack_map),
+        pins: [PieceColor::White, PieceColor::Black]
+            .into_iter()
+            .flat_map(|color| pinned_pieces(color, &board, &pieces))
+            .collect(),
+        attack_map,
+    };
+
+    // Square markers go away with the rest of the board when a game ends
+    let mut missing: Vec<ThreatMarker> = if markers.is_empty() {
+        ThreatMarker::squares().collect()
+    } else {
+        Vec::new()
+    };
+
+    let mut pin_slots = 0;
+    for (marker, mut material, mut transform, mut visibility) in markers.iter_mut() {
+        if let ThreatMarker::Pin(index) = marker {
+            pin_slots = pin_slots.max(index + 1);
+        }
+
+        match layout.place(*marker, &assets, &chess_assets.materials) {
+            Some((handle, placed)) => {
+                if material.0 != handle {
+                    material.0 = handle;
+                }
+                transform.set_if_neq(placed);
+                visibility.set_if_neq(Visibility::Inherited);
+            }
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+        }
+    }
+    missing.extend((pin_slots..layout.pins.len()).map(ThreatMarker::Pin));
+
+    for marker in missing {
+        let (material, transform, visibility) = match layout.place(marker, &assets, &chess_assets.materials) {
+            Some((material, transform)) => (material, transform, Visibility::Inherited),
+            None => (Handle::default(), Transform::default(), Visibility::Hidden),
+        };
+        commands.spawn((
+            Mesh3d(marker.mesh(&assets, &chess_assets.meshes)),
+            MeshMaterial3d(material),
+            transform,
+            visibility,
+            NotShadowCaster,
+            marker,
+            BoardEntity,
+            Name::new(marker.name()),
+        ));
+    }
+}
@@ -0,0 +1,558 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use crate::{
+    core::{
+        constants::*,
+        events::{AnimationEvent, AnimationType, EaseType, HighlightType},
+        resources::GameSettings,
+    },
+    game::pieces::{send_to_graveyard, ChessPiece, MovePath, PieceType},
+    graphics::{
+        capture_burst, promotion_burst, BoardSquare, ChessMeshes, MaterialAnimations, ParticleBurstEvent,
+        ParticleType,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TweenId(u64);
+
+/// What a tween changes. `from: None` starts from the value the entity has when the tween begins.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TweenAction {
+    Translation { from: Option<Vec3>, to: Vec3 },
+    /// Translation along a piece route; the path does its own easing, so the tween's is ignored.
+    Path { from: Option<Vec3>, to: Vec3, path: MovePath },
+    Rotation { from: Option<Quat>, to: Quat },
+    Scale { from: Option<Vec3>, to: Vec3 },
+    /// Moves a camera while keeping it aimed at an interpolated point.
+    Camera { position: Vec3, look_at: Vec3 },
+    /// Alpha of the UI node's background, text and image colours.
+    Fade { from: Option<f32>, to: f32 },
+    /// Swaps the piece type and mesh at the start of the tween.
+    Promote(PieceType),
+    /// Hands a captured piece over to the graveyard.
+    ToGraveyard,
+    /// Particle burst at a fixed point.
+    Burst { particle_type: ParticleType, position: Vec3, count: u32 },
+    Despawn,
+    /// Placeholder used for delays.
+    Wait,
+}
+
+#[derive(Debug, Clone)]
+pub struct Tween {
+    pub entity: Entity,
+    pub action: TweenAction,
+    /// Seconds at `animation_speed` 1.0.
+    pub duration: f32,
+    pub ease: EaseType,
+}
+
+impl Tween {
+    pub fn new(entity: Entity, action: TweenAction, duration: f32) -> Self {
+        Self {
+            entity,
+            action,
+            duration,
+            ease: EaseType::default(),
+        }
+    }
+
+    pub fn delay(duration: f32) -> Self {
+        Self::new(Entity::PLACEHOLDER, TweenAction::Wait, duration)
+    }
+
+    /// Not tied to an entity, so cancelling a piece's tweens keeps the burst.
+    pub fn burst(particle_type: ParticleType, position: Vec3, count: u32) -> Self {
+        Self::new(Entity::PLACEHOLDER, TweenAction::Burst { particle_type, position, count }, 0.0)
+    }
+
+    pub fn with_ease(mut self, ease: EaseType) -> Self {
+        self.ease = ease;
+        self
+    }
+}
+
+/// Tweens arranged in time: a sequence runs its children one after another,
+/// a parallel group runs them together and lasts as long as the longest child.
+#[derive(Debug, Clone)]
+pub enum TweenNode {
+    Tween(Tween),
+    Sequence(Vec<TweenNode>),
+    Parallel(Vec<TweenNode>),
+}
+
+impl From<Tween> for TweenNode {
+    fn from(tween: Tween) -> Self {
+        TweenNode::Tween(tween)
+    }
+}
+
+impl TweenNode {
+    pub fn sequence(nodes: impl IntoIterator<Item = impl Into<TweenNode>>) -> Self {
+        TweenNode::Sequence(nodes.into_iter().map(Into::into).collect())
+    }
+
+    pub fn parallel(nodes: impl IntoIterator<Item = impl Into<TweenNode>>) -> Self {
+        TweenNode::Parallel(nodes.into_iter().map(Into::into).collect())
+    }
+
+    pub fn duration(&self) -> f32 {
+        match self {
+            TweenNode::Tween(tween) => tween.duration.max(0.0),
+            TweenNode::Sequence(nodes) => nodes.iter().map(TweenNode::duration).sum(),
+            TweenNode::Parallel(nodes) => nodes.iter().map(TweenNode::duration).fold(0.0, f32::max),
+        }
+    }
+
+    fn flatten(self, start: f32, tracks: &mut Vec<Track>) {
+        match self {
+            TweenNode::Tween(tween) => tracks.push(Track {
+                start,
+                tween,
+                state: TrackState::Pending,
+            }),
+            TweenNode::Sequence(nodes) => {
+                let mut offset = start;
+                for node in nodes {
+                    let duration = node.duration();
+                    node.flatten(offset, tracks);
+                    offset += duration;
+                }
+            }
+            TweenNode::Parallel(nodes) => {
+                for node in nodes {
+                    node.flatten(start, tracks);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum StartValue {
+    Vec3(Vec3),
+    Quat(Quat),
+    Camera { position: Vec3, look_at: Vec3 },
+    Alpha(f32),
+    None,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TrackState {
+    Pending,
+    Running(StartValue),
+    Done,
+}
+
+struct Track {
+    start: f32,
+    tween: Tween,
+    state: TrackState,
+}
+
+struct Timeline {
+    id: TweenId,
+    tracks: Vec<Track>,
+    elapsed: f32,
+    cancelled: bool,
+    /// `Tweening` has been inserted on the targets.
+    tagged: bool,
+}
+
+impl Timeline {
+    fn targets(&self, entity: Entity) -> bool {
+        self.tracks.iter().any(|track| track.tween.entity == entity)
+    }
+}
+
+/// Sent once per timeline when its last tween ends or it is cancelled.
+#[derive(Event, Debug, Clone)]
+pub struct TweenCompleted {
+    pub id: TweenId,
+    pub cancelled: bool,
+}
+
+/// On entities driven by a running timeline; systems that snap transforms skip them.
+#[derive(Component)]
+pub struct Tweening;
+
+#[derive(Resource, Default)]
+pub struct Tweens {
+    timelines: Vec<Timeline>,
+    next_id: u64,
+}
+
+impl Tweens {
+    pub fn start(&mut self, node: impl Into<TweenNode>) -> TweenId {
+        let id = TweenId(self.next_id);
+        self.next_id += 1;
+
+        let mut tracks = Vec::new();
+        node.into().flatten(0.0, &mut tracks);
+        self.timelines.push(Timeline {
+            id,
+            tracks,
+            elapsed: 0.0,
+            cancelled: false,
+            tagged: false,
+        });
+        id
+    }
+
+    /// Stops a timeline where it is; values already written are kept.
+    pub fn cancel(&mut self, id: TweenId) {
+        for timeline in self.timelines.iter_mut().filter(|timeline| timeline.id == id) {
+            timeline.cancelled = true;
+        }
+    }
+
+    /// Cancels every timeline that touches `entity`.
+    pub fn cancel_entity(&mut self, entity: Entity) {
+        for timeline in self.timelines.iter_mut().filter(|timeline| timeline.targets(entity)) {
+            timeline.cancelled = true;
+        }
+    }
+
+    pub fn is_running(&self, id: TweenId) -> bool {
+        self.timelines.iter().any(|timeline| timeline.id == id && !timeline.cancelled)
+    }
+
+    pub fn is_animating(&self, entity: Entity) -> bool {
+        self.timelines.iter().any(|timeline| !timeline.cancelled && timeline.targets(entity))
+    }
+}
+
+/// Point on the board plane the camera is looking at.
+fn camera_look_at(transform: &Transform) -> Vec3 {
+    let forward = transform.forward();
+    if forward.y < -f32::EPSILON {
+        let distance = -transform.translation.y / forward.y;
+        transform.translation + forward * distance
+    } else {
+        transform.translation + forward * CAMERA_DEFAULT_DISTANCE
+    }
+}
+
+/// Colours a `Fade` changes the alpha of.
+#[derive(SystemParam)]
+pub struct UiColors<'w, 's> {
+    backgrounds: Query<'w, 's, &'static mut BackgroundColor>,
+    texts: Query<'w, 's, &'static mut TextColor>,
+    images: Query<'w, 's, &'static mut ImageNode>,
+}
+
+impl UiColors<'_, '_> {
+    fn alpha(&self, entity: Entity) -> f32 {
+        if let Ok(background) = self.backgrounds.get(entity) {
+            background.0.alpha()
+        } else if let Ok(text) = self.texts.get(entity) {
+            text.0.alpha()
+        } else if let Ok(image) = self.images.get(entity) {
+            image.color.alpha()
+        } else {
+            1.0
+        }
+    }
+
+    fn set_alpha(&mut self, entity: Entity, alpha: f32) {
+        if let Ok(mut background) = self.backgrounds.get_mut(entity) {
+            background.0.set_alpha(alpha);
+        }
+        if let Ok(mut text) = self.texts.get_mut(entity) {
+            text.0.set_alpha(alpha);
+        }
+        if let Ok(mut image) = self.images.get_mut(entity) {
+            image.color.set_alpha(alpha);
+        }
+    }
+}
+
+/// Pieces a `Promote` tween swaps, with the meshes to swap them to.
+#[derive(SystemParam)]
+pub struct PromotablePieces<'w, 's> {
+    pieces: Query<'w, 's, (&'static mut ChessPiece, &'static mut Mesh3d)>,
+    chess_meshes: Res<'w, ChessMeshes>,
+}
+
+#[derive(SystemParam)]
+pub struct TweenEvents<'w> {
+    completed: EventWriter<'w, TweenCompleted>,
+    bursts: EventWriter<'w, ParticleBurstEvent>,
+}
+
+pub fn update_tweens(
+    mut commands: Commands,
+    mut tweens: ResMut<Tweens>,
+    mut transforms: Query<&mut Transform>,
+    mut ui_colors: UiColors,
+    mut pieces: PromotablePieces,
+    (settings, time): (Res<GameSettings>, Res<Time>),
+    mut events: TweenEvents,
+) {
+    if tweens.timelines.is_empty() {
+        return;
+    }
+
+    let delta = time.delta_secs() * settings.animation_speed.max(MIN_ANIMATION_SPEED);
+
+    let mut finished = Vec::new();
+
+    for timeline in tweens.timelines.iter_mut() {
+        if timeline.cancelled {
+            finished.push((timeline.id, true));
+            continue;
+        }
+        if !timeline.tagged {
+            for track in &timeline.tracks {
+                if track.tween.entity != Entity::PLACEHOLDER {
+                    commands.entity(track.tween.entity).try_insert(Tweening);
+                }
+            }
+            timeline.tagged = true;
+        }
+        timeline.elapsed += delta;
+
+        for track in timeline.tracks.iter_mut() {
+            if matches!(track.state, TrackState::Done) || timeline.elapsed < track.start {
+                continue;
+            }
+            let entity = track.tween.entity;
+
+            let start = match track.state {
+                TrackState::Running(start) => start,
+                _ => {
+                    let start = match &track.tween.action {
+                        TweenAction::Translation { from, .. } | TweenAction::Path { from, .. } => transforms
+                            .get(entity)
+                            .map(|transform| StartValue::Vec3(from.unwrap_or(transform.translation))),
+                        TweenAction::Rotation { from, .. } => transforms
+                            .get(entity)
+                            .map(|transform| StartValue::Quat(from.unwrap_or(transform.rotation))),
+                        TweenAction::Scale { from, .. } => transforms
+                            .get(entity)
+                            .map(|transform| StartValue::Vec3(from.unwrap_or(transform.scale))),
+                        TweenAction::Camera { .. } => transforms.get(entity).map(|transform| StartValue::Camera {
+                            position: transform.translation,
+                            look_at: camera_look_at(transform),
+                        }),
+                        TweenAction::Fade { from, .. } => Ok(StartValue::Alpha(
+                            from.unwrap_or_else(|| ui_colors.alpha(entity)),
+                        )),
+                        TweenAction::Promote(piece_type) => {
+                            if let Ok((mut piece, mut mesh)) = pieces.pieces.get_mut(entity) {
+                                piece.piece_type = *piece_type;
+                                mesh.0 = pieces.chess_meshes.get_piece_mesh(*piece_type);
+                            }
+                            Ok(StartValue::None)
+                        }
+                        TweenAction::ToGraveyard => {
+                            if let Ok((piece, _)) = pieces.pieces.get(entity) {
+                                send_to_graveyard(&mut commands, entity, Some(piece));
+                            }
+                            Ok(StartValue::None)
+                        }
+                        TweenAction::Burst { particle_type, position, count } => {
+                            events.bursts.write(ParticleBurstEvent {
+                                particle_type: *particle_type,
+                                position: *position,
+                                count: *count,
+                            });
+                            Ok(StartValue::None)
+                        }
+                        TweenAction::Despawn => {
+                            commands.entity(entity).try_despawn();
+                            Ok(StartValue::None)
+                        }
+                        TweenAction::Wait => Ok(StartValue::None),
+                    };
+                    match start {
+                        Ok(start) => start,
+                        // Entity is gone; nothing left to animate
+                        Err(_) => {
+                            track.state = TrackState::Done;
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            let t = if track.tween.duration > 0.0 {
+                ((timeline.elapsed - track.start) / track.tween.duration).clamp(0.0, 1.0)
+            } else {
+                1.0
+            };
+            let eased = if t >= 1.0 { 1.0 } else { track.tween.ease.apply(t) };
+
+            match (&track.tween.action, start) {
+                (TweenAction::Translation { to, .. }, StartValue::Vec3(from)) => {
+                    if let Ok(mut transform) = transforms.get_mut(entity) {
+                        transform.translation = from.lerp(*to, eased);
+                    }
+                }
+                (TweenAction::Path { to, path, .. }, StartValue::Vec3(from)) => {
+                    if let Ok(mut transform) = transforms.get_mut(entity) {
+                        transform.translation = path.sample(from, *to, t);
+                    }
+                }
+                (TweenAction::Rotation { to, .. }, StartValue::Quat(from)) => {
+                    if let Ok(mut transform) = transforms.get_mut(entity) {
+                        transform.rotation = from.slerp(*to, eased);
+                    }
+                }
+                (TweenAction::Scale { to, .. }, StartValue::Vec3(from)) => {
+                    if let Ok(mut transform) = transforms.get_mut(entity) {
+                        transform.scale = from.lerp(*to, eased);
+                    }
+                }
+                (TweenAction::Camera { position, look_at }, StartValue::Camera { position: from_position, look_at: from_look_at }) => {
+                    if let Ok(mut transform) = transforms.get_mut(entity) {
+                        transform.translation = from_position.lerp(*position, eased);
+                        transform.look_at(from_look_at.lerp(*look_at, eased), Vec3::Y);
+                    }
+                }
+                (TweenAction::Fade { to, .. }, StartValue::Alpha(from)) => {
+                    ui_colors.set_alpha(entity, from + (to - from) * eased);
+                }
+                _ => {}
+            }
+
+            track.state = if t >= 1.0 { TrackState::Done } else { TrackState::Running(start) };
+        }
+
+        if timeline.tracks.iter().all(|track| matches!(track.state, TrackState::Done)) {
+            finished.push((timeline.id, false));
+        }
+    }
+
+    for (id, cancelled) in finished {
+        let Some(index) = tweens.timelines.iter().position(|timeline| timeline.id == id) else {
+            continue;
+        };
+        let timeline = tweens.timelines.remove(index);
+
+        for track in &timeline.tracks {
+            let entity = track.tween.entity;
+            if entity != Entity::PLACEHOLDER && !tweens.timelines.iter().any(|other| other.targets(entity)) {
+                commands.entity(entity).try_remove::<Tweening>();
+            }
+        }
+        events.completed.write(TweenCompleted { id, cancelled });
+    }
+}
+
+/// Shrinks the piece away, swaps it for `new_piece_type` and pops the new piece up with a burst.
+pub fn promotion_tweens(entity: Entity, position: Vec3, new_piece_type: PieceType, duration: f32) -> TweenNode {
+    let half = duration * 0.5;
+    TweenNode::sequence([
+        Tween::new(entity, TweenAction::Scale { from: Some(Vec3::ONE), to: Vec3::ZERO }, half)
+            .with_ease(EaseType::EaseIn)
+            .into(),
+        Tween::new(entity, TweenAction::Promote(new_piece_type), 0.0).into(),
+        promotion_burst(position, new_piece_type),
+        Tween::new(entity, TweenAction::Scale { from: Some(Vec3::ZERO), to: Vec3::ONE }, half)
+            .with_ease(EaseType::Elastic)
+            .into(),
+    ])
+}
+
+fn highlight_color(highlight_type: &HighlightType) -> Color {
+    match highlight_type {
+        HighlightType::Selected => SELECTED_COLOR,
+        HighlightType::LegalMove => LEGAL_MOVE_COLOR,
+        HighlightType::LastMove => LAST_MOVE_COLOR,
+        HighlightType::Check => CHECK_COLOR,
+        HighlightType::Capture => CAPTURE_COLOR,
+        HighlightType::Threat => THREAT_COLOR,
+    }
+}
+
+/// Turns `AnimationEvent`s into tweens. Square highlights go through `MaterialAnimations`.
+pub fn handle_animation_events(
+    mut events: EventReader<AnimationEvent>,
+    mut tweens: ResMut<Tweens>,
+    mut material_animations: ResMut<MaterialAnimations>,
+    squares: Query<(Entity, &BoardSquare)>,
+) {
+    for event in events.read() {
+        let entity = event.entity;
+
+        match &event.animation_type {
+            AnimationType::PieceMove { from, to, duration, ease_type } => {
+                tweens.cancel_entity(entity);
+                tweens.start(
+                    Tween::new(entity, TweenAction::Translation { from: Some(*from), to: *to }, *duration)
+                        .with_ease(*ease_type),
+                );
+            }
+            AnimationType::PieceCapture { capture_position, duration } => {
+                tweens.cancel_entity(entity);
+                tweens.start(TweenNode::sequence([
+                    TweenNode::parallel([
+                        capture_burst(*capture_position),
+                        Tween::new(
+                            entity,
+                            TweenAction::Translation {
+                                from: Some(*capture_position),
+                                to: *capture_position - Vec3::Y * 2.0,
+                            },
+                            *duration,
+                        )
+                        .with_ease(EaseType::EaseIn)
+                        .into(),
+                        Tween::new(entity, TweenAction::Scale { from: None, to: Vec3::ZERO }, *duration)
+                            .with_ease(EaseType::EaseIn)
+                            .into(),
+                    ]),
+                    Tween::new(entity, TweenAction::Despawn, 0.0).into(),
+                ]));
+            }
+            AnimationType::PiecePromotion { position, new_piece_type, duration } => {
+                tweens.start(TweenNode::sequence([
+                    Tween::new(entity, TweenAction::Translation { from: None, to: *position }, 0.0).into(),
+                    promotion_tweens(entity, *position, *new_piece_type, *duration),
+                ]));
+            }
+            AnimationType::BoardHighlight { positions, highlight_type, duration } => {
+                let color = highlight_color(highlight_type);
+                for (square, _) in squares.iter().filter(|(_, square)| positions.contains(&square.position)) {
+                    match duration {
+                        Some(duration) => {
+                            material_animations.flash(square, color, 1, *duration);
+                        }
+                        None => {
+                            material_animations.add(
+                                square,
+                                crate::graphics::MaterialChannel::Emissive,
+                                crate::graphics::MaterialCurve::Pulse { frequency: PULSE_FREQUENCY },
+                                color,
+                            );
+                        }
+                    }
+                }
+                if let Some(duration) = duration {
+                    // Lets listeners wait on highlights like any other animation
+                    tweens.start(Tween::delay(*duration));
+                }
+            }
+            AnimationType::CameraMove { target_position, target_look_at, duration, ease_type } => {
+                tweens.cancel_entity(entity);
+                tweens.start(
+                    Tween::new(
+                        entity,
+                        TweenAction::Camera { position: *target_position, look_at: *target_look_at },
+                        *duration,
+                    )
+                    .with_ease(*ease_type),
+                );
+            }
+            AnimationType::UIFadeIn { duration } => {
+                tweens.cancel_entity(entity);
+                tweens.start(Tween::new(entity, TweenAction::Fade { from: Some(0.0), to: 1.0 }, *duration));
+            }
+            AnimationType::UIFadeOut { duration } => {
+                tweens.cancel_entity(entity);
+                tweens.start(Tween::new(entity, TweenAction::Fade { from: None, to: 0.0 }, *duration));
+            }
+        }
+    }
+}
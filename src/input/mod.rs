@@ -1,10 +1,44 @@
 use bevy::prelude::*;
 
+use crate::core::{
+    events::{GameAction, GameActionEvent},
+    resources::InputSettings,
+    CoreSet,
+};
+use crate::ui::Focused;
+
 pub struct InputPlugin;
 
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
         // TODO: 入力システムの実装
+        app.add_systems(Update, handle_toggle_keys.in_set(CoreSet::Input));
+
         info!("Input plugin loaded (placeholder)")
     }
-}
\ No newline at end of file
+}
+
+/// Board overlay toggles bound in `InputSettings`. Ignored while typing into a text field.
+fn handle_toggle_keys(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    input_settings: Res<InputSettings>,
+    focused_inputs: Query<(), With<Focused>>,
+    mut actions: EventWriter<GameActionEvent>,
+) {
+    if !focused_inputs.is_empty() {
+        return;
+    }
+
+    let bindings = [
+        ("toggle_coordinates", GameAction::ToggleCoordinates),
+        ("toggle_threats", GameAction::ToggleThreatOverlay),
+    ];
+
+    for key in keyboard_input.get_just_pressed() {
+        for (name, action) in &bindings {
+            if input_settings.is_key_bound_to_action(*key, name) {
+                actions.write(GameActionEvent { action: action.clone() });
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! Keeps the `ChessClock` in line with the times the server reports.
+//!
+//! Reported times are half a round trip old when they arrive, so the side to
+//! move is charged for that on top. A flag fall on our clock ends the game on
+//! our side and asks the server for the real state.
+
+use bevy::prelude::*;
+
+use crate::{
+    core::{constants::CLOCK_DRIFT_TOLERANCE_SECONDS, events::*, resources::NetworkState},
+    game::clock::ChessClock,
+};
+
+use super::reconciliation::OnlineGame;
+
+/// Leaving the online screens stops the clock, so a local game starts untimed.
+pub fn reset_chess_clock(mut clock: ResMut<ChessClock>) {
+    *clock = ChessClock::default();
+}
+
+pub fn sync_chess_clock(
+    mut responses: EventReader<NetworkResponseEvent>,
+    mut clock: ResMut<ChessClock>,
+    mut online: ResMut<OnlineGame>,
+    network_state: Res<NetworkState>,
+    mut requests: EventWriter<SendClientRequestEvent>,
+) {
+    let latency = network_state.ping as f32 / 2000.0;
+
+    for event in responses.read() {
+        match &event.response {
+            // The time control comes with the opponent, in `GameJoined`
+            ServerResponse::GameCreated { .. } => *clock = ChessClock::default(),
+            ServerResponse::GameJoined { game_state: snapshot, .. } | ServerResponse::GameStateUpdate { game_state: snapshot } => {
+                if clock.time_control != snapshot.time_control {
+                    *clock = ChessClock::new(snapshot.time_control.clone());
+                }
+                clock.restore(snapshot.move_count, snapshot.current_player, snapshot.game_result.is_some());
+                if let (Some(white), Some(black)) = (snapshot.white_time_remaining, snapshot.black_time_remaining) {
+                    clock.sync(white, black, latency);
+                }
+            }
+            ServerResponse::MoveUpdate { move_number, time_remaining, .. } => {
+                // Our own moves are already counted; an echo carries the times after them
+                if *move_number >= clock.moves_played {
+                    clock.advance_to(*move_number);
+                }
+                if let Some((white, black)) = time_remaining.filter(|_| *move_number == clock.moves_played) {
+                    let drift = clock.sync(white, black, latency);
+                    if drift > CLOCK_DRIFT_TOLERANCE_SECONDS {
+                        debug!("Clock drifted {:.2}s from the server", drift);
+                    }
+                }
+            }
+            ServerResponse::GameOver { .. } => clock.stop(),
+            _ => {}
+        }
+    }
+
+    if online.is_active()
+        && !online.is_finished()
+        && let Some(result) = clock.result.take()
+    {
+        info!("Flag fell, asking the server to confirm");
+        online.result = Some(result);
+        requests.write(SendClientRequestEvent {
+            request: ClientRequest::RequestGameState,
+        });
+    }
+}
@@ -0,0 +1,373 @@
+//! Server connection running on a background tokio runtime over whichever transport
+//! the address scheme selects. Bevy systems and the connection task only talk through
+//! two bounded channels, so a slow side applies backpressure instead of buffering
+//! without limit.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use tokio::{runtime::Runtime, sync::mpsc, task::JoinHandle};
+
+use crate::{
+    core::{
+        constants::*,
+        events::*,
+        resources::{ConnectionStatus, GameSettings, NetworkState},
+        states::GameState,
+    },
+    network::{
+        protocol::{self, WireMessage},
+        session::{handle_connection_lost, track_session_response},
+        tcp::TcpTransport,
+        transport::{Endpoint, FrameReader, FrameWriter, Transport},
+        websocket::WebSocketTransport,
+    },
+};
+
+pub const ERROR_CONNECT_FAILED: u32 = 1;
+pub const ERROR_CONNECTION_LOST: u32 = 2;
+pub const ERROR_PROTOCOL: u32 = 3;
+pub const ERROR_SEND_QUEUE_FULL: u32 = 4;
+pub const ERROR_NOT_CONNECTED: u32 = 5;
+
+/// What the connection task reports back to Bevy.
+#[derive(Debug)]
+pub enum InboundEvent {
+    /// Socket is open; the `Connect` handshake has been queued.
+    Connected,
+    Message(WireMessage),
+    Error(NetworkError),
+    /// Always the last event of a connection.
+    Disconnected { reason: String },
+}
+
+/// Runtime the network tasks live on, off Bevy's threads.
+#[derive(Resource)]
+pub struct NetworkRuntime(Runtime);
+
+impl Default for NetworkRuntime {
+    fn default() -> Self {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("network")
+            .enable_all()
+            .build()
+            .expect("failed to start the network runtime");
+        Self(runtime)
+    }
+}
+
+impl NetworkRuntime {
+    pub fn handle(&self) -> &tokio::runtime::Handle {
+        self.0.handle()
+    }
+}
+
+struct Connection {
+    /// `None` once the client asked to close; the task flushes and hangs up.
+    outbound: Option<mpsc::Sender<WireMessage>>,
+    inbound: mpsc::Receiver<InboundEvent>,
+    task: JoinHandle<()>,
+}
+
+#[derive(Resource, Default)]
+pub struct NetworkClient {
+    connection: Option<Connection>,
+}
+
+impl NetworkClient {
+    /// Opens a connection to `address`, replacing any current one.
+    /// `handshake` is the first frame written once the socket is open.
+    pub fn connect(&mut self, runtime: &NetworkRuntime, address: String, handshake: NetworkMessage) {
+        self.abort();
+
+        let (outbound_tx, outbound_rx) = mpsc::channel(NETWORK_CHANNEL_CAPACITY);
+        let (inbound_tx, inbound_rx) = mpsc::channel(NETWORK_CHANNEL_CAPACITY);
+        let task = runtime.handle().spawn(run_connection(address, handshake, outbound_rx, inbound_tx));
+
+        self.connection = Some(Connection {
+            outbound: Some(outbound_tx),
+            inbound: inbound_rx,
+            task,
+        });
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    /// Queues a message without blocking the frame.
+    pub fn send(&self, message: impl Into<WireMessage>) -> Result<(), NetworkError> {
+        let Some(outbound) = self.connection.as_ref().and_then(|connection| connection.outbound.as_ref()) else {
+            return Err(network_error(ERROR_NOT_CONNECTED, "Not connected to a server".to_string(), true));
+        };
+
+        outbound.try_send(message.into()).map_err(|error| match error {
+            mpsc::error::TrySendError::Full(_) => {
+                network_error(ERROR_SEND_QUEUE_FULL, "Outgoing message queue is full".to_string(), true)
+            }
+            mpsc::error::TrySendError::Closed(_) => {
+                network_error(ERROR_CONNECTION_LOST, "Connection is closed".to_string(), true)
+            }
+        })
+    }
+
+    /// Hangs up after the queued messages are written.
+    pub fn close(&mut self) {
+        if let Some(connection) = &mut self.connection {
+            connection.outbound = None;
+        }
+    }
+
+    /// Drops the connection immediately.
+    pub fn abort(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            connection.task.abort();
+        }
+    }
+
+    fn try_recv(&mut self) -> Option<InboundEvent> {
+        let event = self.connection.as_mut()?.inbound.try_recv().ok()?;
+        if let InboundEvent::Disconnected { .. } = event {
+            self.connection = None;
+        }
+        Some(event)
+    }
+}
+
+pub(crate) fn network_error(code: u32, message: String, is_recoverable: bool) -> NetworkError {
+    NetworkError {
+        code,
+        message,
+        is_recoverable,
+    }
+}
+
+async fn run_connection(
+    address: String,
+    handshake: NetworkMessage,
+    mut outbound: mpsc::Receiver<WireMessage>,
+    inbound: mpsc::Sender<InboundEvent>,
+) {
+    let reason = match Endpoint::parse(&address) {
+        Endpoint::Tcp(address) => run_transport::<TcpTransport>(&address, handshake, &mut outbound, &inbound).await,
+        Endpoint::WebSocket(url) => run_transport::<WebSocketTransport>(&url, handshake, &mut outbound, &inbound).await,
+    };
+    let _ = inbound.send(InboundEvent::Disconnected { reason }).await;
+}
+
+async fn run_transport<T: Transport>(
+    address: &str,
+    handshake: NetworkMessage,
+    outbound: &mut mpsc::Receiver<WireMessage>,
+    inbound: &mpsc::Sender<InboundEvent>,
+) -> String {
+    let timeout = Duration::from_secs_f32(CONNECTION_TIMEOUT_SECONDS);
+    let transport = match tokio::time::timeout(timeout, T::connect(address)).await {
+        Ok(Ok(transport)) => transport,
+        Ok(Err(error)) => {
+            let reason = format!("Could not connect to {}: {}", address, error);
+            let _ = inbound.send(InboundEvent::Error(network_error(ERROR_CONNECT_FAILED, reason.clone(), true))).await;
+            return reason;
+        }
+        Err(_) => {
+            let reason = format!("Timed out connecting to {}", address);
+            let _ = inbound.send(InboundEvent::Error(network_error(ERROR_CONNECT_FAILED, reason.clone(), true))).await;
+            return reason;
+        }
+    };
+
+    if inbound.send(InboundEvent::Connected).await.is_err() {
+        return "Client shut down".to_string();
+    }
+
+    let (reader, mut writer) = transport.split();
+    let reason = tokio::select! {
+        reason = read_loop(reader, inbound) => reason,
+        reason = write_loop(&mut writer, handshake, outbound) => reason,
+    };
+    let _ = writer.close().await;
+    reason
+}
+
+async fn read_loop(mut reader: impl FrameReader, inbound: &mpsc::Sender<InboundEvent>) -> String {
+    loop {
+        let payload = match reader.read_frame().await {
+            Ok(Some(payload)) => payload,
+            Ok(None) => return "Server closed the connection".to_string(),
+            Err(error) => return format!("Connection lost: {}", error),
+        };
+
+        // A bad frame is reported but doesn't end the session
+        let event = match std::str::from_utf8(&payload).map_err(|error| error.to_string()).and_then(|text| {
+            protocol::decode(text).map_err(|error| error.to_string())
+        }) {
+            Ok(frame) => InboundEvent::Message(frame.message),
+            Err(error) => InboundEvent::Error(network_error(ERROR_PROTOCOL, error, true)),
+        };
+
+        if inbound.send(event).await.is_err() {
+            return "Client shut down".to_string();
+        }
+    }
+}
+
+async fn write_loop(
+    writer: &mut impl FrameWriter,
+    handshake: NetworkMessage,
+    outbound: &mut mpsc::Receiver<WireMessage>,
+) -> String {
+    let mut next = Some(WireMessage::from(handshake));
+
+    while let Some(message) = next {
+        let text = match protocol::encode(message) {
+            Ok(text) => text,
+            Err(error) => return format!("Failed to encode message: {}", error),
+        };
+        if let Err(error) = writer.write_frame(text.as_bytes()).await {
+            return format!("Connection lost: {}", error);
+        }
+        next = outbound.recv().await;
+    }
+
+    "Disconnected by client".to_string()
+}
+
+/// Connects to `GameSettings::server_address` when the connecting screen opens.
+pub fn start_connection(
+    mut client: ResMut<NetworkClient>,
+    runtime: Res<NetworkRuntime>,
+    settings: Res<GameSettings>,
+    mut network_state: ResMut<NetworkState>,
+    time: Res<Time<Real>>,
+) {
+    let address = if settings.server_address.is_empty() {
+        DEFAULT_SERVER_ADDRESS.to_string()
+    } else {
+        settings.server_address.clone()
+    };
+
+    info!("Connecting to {}", address);
+    network_state.start_connection(address.clone(), time.elapsed_secs());
+    client.connect(&runtime, address, NetworkMessage::connect(settings.player_name.clone()));
+}
+
+pub fn close_connection(mut client: ResMut<NetworkClient>, mut network_state: ResMut<NetworkState>) {
+    if client.is_active() {
+        client.close();
+        network_state.disconnect();
+    }
+}
+
+pub fn send_outbound_messages(
+    mut client: ResMut<NetworkClient>,
+    mut network_state: ResMut<NetworkState>,
+    mut messages: EventReader<SendNetworkMessageEvent>,
+    mut requests: EventReader<SendClientRequestEvent>,
+    mut network_events: EventWriter<NetworkEvent>,
+) {
+    for event in messages.read() {
+        if let Err(error) = client.send(event.message.clone()) {
+            network_events.write(NetworkEvent::error(error));
+        }
+    }
+
+    for event in requests.read() {
+        match client.send(event.request.clone()) {
+            Ok(()) => {
+                network_events.write(NetworkEvent::message_sent(event.request.clone()));
+                if let ClientRequest::Disconnect { .. } = event.request {
+                    client.close();
+                    network_state.disconnect();
+                }
+            }
+            Err(error) => {
+                network_events.write(NetworkEvent::error(error));
+            }
+        }
+    }
+}
+
+pub fn receive_inbound_messages(
+    mut client: ResMut<NetworkClient>,
+    mut network_state: ResMut<NetworkState>,
+    mut responses: EventWriter<NetworkResponseEvent>,
+    mut network_events: EventWriter<NetworkEvent>,
+    game_state: Res<State<GameState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    time: Res<Time<Real>>,
+) {
+    let now = time.elapsed_secs();
+
+    for _ in 0..MAX_INBOUND_MESSAGES_PER_FRAME {
+        let Some(event) = client.try_recv() else {
+            break;
+        };
+
+        match event {
+            InboundEvent::Connected => {
+                debug!("Socket open to {}, waiting for handshake", network_state.server_address);
+            }
+            InboundEvent::Message(WireMessage::Response(response)) => {
+                let response = *response;
+                network_state.last_message_time = now;
+
+                if let ServerResponse::Connected { player_id, session_id, protocol_version, .. } = &response {
+                    let version = match protocol::negotiate(protocol_version) {
+                        Ok(version) => version,
+                        Err(error) => {
+                            network_events.write(NetworkEvent::error(network_error(ERROR_PROTOCOL, error.to_string(), false)));
+                            client.close();
+                            network_state.connection_error();
+                            continue;
+                        }
+                    };
+
+                    let resumed = network_state.session_id.as_ref() == Some(session_id);
+                    network_state.connection_established(player_id.clone(), session_id.clone(), now);
+                    network_state.protocol_version = Some(version.to_string());
+                    network_events.write(NetworkEvent::connected());
+
+                    // Back into the game we dropped out of; the server answers with a snapshot
+                    if let Some(game_id) = network_state.game_id.clone() {
+                        info!("Rejoining game {} (session resumed: {})", game_id, resumed);
+                        if let Err(error) = client.send(ClientRequest::JoinGame { game_id, password: None }) {
+                            network_events.write(NetworkEvent::error(error));
+                        }
+                    }
+                    if *game_state.get() == GameState::Connecting {
+                        next_game_state.set(GameState::Lobby);
+                    }
+                }
+                track_session_response(&mut network_state, &response, now);
+
+                network_events.write(NetworkEvent::message_received(response.clone()));
+                responses.write(NetworkResponseEvent { response });
+            }
+            InboundEvent::Message(WireMessage::Session(message)) => {
+                network_state.last_message_time = now;
+                if let NetworkMessage::Pong { .. } = message {
+                    network_state.pong_received(now);
+                }
+                network_events.write(NetworkEvent::session_message(message));
+            }
+            InboundEvent::Message(WireMessage::Request(request)) => {
+                warn!("Ignoring client request sent by the server: {:?}", request);
+            }
+            InboundEvent::Error(error) => {
+                warn!("Network error {}: {}", error.code, error.message);
+                network_events.write(NetworkEvent::error(error));
+            }
+            InboundEvent::Disconnected { reason } => {
+                info!("Disconnected: {}", reason);
+                // Closes we asked for have already reset the state
+                if network_state.connection_status != ConnectionStatus::Disconnected
+                    && network_state.connection_status != ConnectionStatus::Error
+                {
+                    handle_connection_lost(&mut network_state, now, game_state.get(), &mut next_game_state);
+                }
+                network_events.write(NetworkEvent::disconnected(&reason));
+            }
+        }
+    }
+}
@@ -0,0 +1,736 @@
+//! In-memory chess server for development and integration tests. It speaks the
+//! real protocol over length-delimited TCP but only checks whose turn it is, not
+//! whether a move is legal. Clocks run on the same `ChessClock` as the client and
+//! are checked whenever a player in the game sends a request.
+//!
+//! Needs the `mock_server` feature:
+//! `cargo run --features mock_server --bin mock_server -- 127.0.0.1:8080`
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use tokio::{
+    io::{AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    runtime::Runtime,
+    sync::mpsc,
+};
+
+use crate::{
+    core::{constants::*, events::*},
+    game::{
+        clock::{timeout_result, ChessClock},
+        fen::{to_uci_move, FenPosition},
+    },
+    network::{
+        framing,
+        protocol::{self, ERROR_BAD_REQUEST, ERROR_CONFLICT, ERROR_FORBIDDEN, ERROR_NOT_FOUND, ERROR_TOO_MANY_REQUESTS},
+        WireMessage,
+    },
+    BoardPosition, PieceColor,
+};
+
+pub struct MockServer {
+    listener: TcpListener,
+    state: Arc<Mutex<ServerState>>,
+}
+
+/// A server running on its own runtime; dropping it stops the server.
+pub struct MockServerHandle {
+    address: SocketAddr,
+    _runtime: Runtime,
+}
+
+impl MockServerHandle {
+    pub fn address(&self) -> String {
+        self.address.to_string()
+    }
+}
+
+impl MockServer {
+    pub async fn bind(address: &str) -> io::Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(address).await?,
+            state: Arc::default(),
+        })
+    }
+
+    /// Binds `address` (port 0 picks a free one) and serves in the background.
+    pub fn spawn(address: &str) -> io::Result<MockServerHandle> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("mock-server")
+            .enable_all()
+            .build()?;
+        let server = runtime.block_on(Self::bind(address))?;
+        let address = server.local_addr()?;
+        runtime.spawn(server.run());
+
+        Ok(MockServerHandle {
+            address,
+            _runtime: runtime,
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    pub async fn run(self) -> io::Result<()> {
+        loop {
+            let (stream, _) = self.listener.accept().await?;
+            stream.set_nodelay(true)?;
+            tokio::spawn(serve_connection(stream, self.state.clone()));
+        }
+    }
+}
+
+async fn serve_connection(stream: TcpStream, state: Arc<Mutex<ServerState>>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::with_capacity(NETWORK_BUFFER_SIZE, reader);
+    let (outbox, mut outgoing) = mpsc::unbounded_channel::<WireMessage>();
+
+    let writer_task = tokio::spawn(async move {
+        while let Some(message) = outgoing.recv().await {
+            let Ok(text) = protocol::encode(message) else {
+                continue;
+            };
+            if framing::write_frame(&mut writer, text.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+        let _ = writer.shutdown().await;
+    });
+
+    let mut player_id: Option<String> = None;
+    while let Ok(Some(payload)) = framing::read_frame(&mut reader).await {
+        let frame = std::str::from_utf8(&payload)
+            .map_err(|error| error.to_string())
+            .and_then(|text| protocol::decode(text).map_err(|error| error.to_string()));
+        let message = match frame {
+            Ok(frame) => frame.message,
+            Err(error) => {
+                let _ = outbox.send(error_response(ERROR_BAD_REQUEST, &error));
+                continue;
+            }
+        };
+
+        let mut state = state.lock().unwrap();
+        match (message, &player_id) {
+            (WireMessage::Session(NetworkMessage::Connect { player_name, protocol_version, session_id, .. }), None) => {
+                match state.connect(player_name, &protocol_version, session_id, outbox.clone()) {
+                    Ok(id) => player_id = Some(id),
+                    Err(error) => {
+                        let _ = outbox.send(error_response(ERROR_BAD_REQUEST, &error));
+                        break;
+                    }
+                }
+            }
+            (WireMessage::Request(ClientRequest::Disconnect { .. }), Some(_)) => break,
+            (WireMessage::Request(request), Some(id)) => {
+                let id = id.clone();
+                state.handle_request(&id, request);
+            }
+            (_, None) => {
+                let _ = outbox.send(error_response(ERROR_FORBIDDEN, "Connect first"));
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(id) = player_id {
+        state.lock().unwrap().disconnect(&id, &outbox);
+    }
+    drop(outbox);
+    let _ = writer_task.await;
+}
+
+fn error_response(code: u32, message: &str) -> WireMessage {
+    ServerResponse::Error {
+        message: message.to_string(),
+        error_code: Some(code),
+    }
+    .into()
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs())
+}
+
+struct Player {
+    name: String,
+    session_id: String,
+    /// `None` while disconnected; the session can still be resumed.
+    outbox: Option<mpsc::UnboundedSender<WireMessage>>,
+    game_id: Option<String>,
+    games_played: u32,
+    wins: u32,
+}
+
+struct Game {
+    id: String,
+    white: String,
+    black: Option<String>,
+    is_private: bool,
+    /// Asked of the player taking the black seat; seated players rejoin without it.
+    password: Option<String>,
+    time_control: Option<TimeControl>,
+    created_at: u64,
+    position: FenPosition,
+    /// Position before each move, for undo.
+    previous_positions: Vec<FenPosition>,
+    moves: Vec<String>,
+    last_move: Option<(BoardPosition, BoardPosition)>,
+    draw_offer: Option<String>,
+    undo_offer: Option<(String, u32)>,
+    result: Option<GameResult>,
+    clock: ChessClock,
+    clock_updated_at: Instant,
+}
+
+impl Game {
+    fn color_of(&self, player_id: &str) -> Option<PieceColor> {
+        if self.white == player_id {
+            Some(PieceColor::White)
+        } else if self.black.as_deref() == Some(player_id) {
+            Some(PieceColor::Black)
+        } else {
+            None
+        }
+    }
+
+    fn opponent_of(&self, player_id: &str) -> Option<String> {
+        match self.color_of(player_id)? {
+            PieceColor::White => self.black.clone(),
+            PieceColor::Black => Some(self.white.clone()),
+        }
+    }
+
+    /// Brings the clock up to now; the result if the side to move has run out of time.
+    fn run_clock(&mut self) -> Option<GameResult> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.clock_updated_at).as_secs_f32();
+        self.clock_updated_at = now;
+        let flagged = self.clock.tick(elapsed)?;
+        Some(timeout_result(flagged, &self.position))
+    }
+
+    fn time_remaining(&self) -> Option<(f32, f32)> {
+        self.clock
+            .is_enabled()
+            .then_some((self.clock.white_remaining, self.clock.black_remaining))
+    }
+
+    fn players(&self) -> Vec<String> {
+        std::iter::once(self.white.clone()).chain(self.black.clone()).collect()
+    }
+
+    fn status(&self) -> GameStatus {
+        match (&self.black, &self.result) {
+            (_, Some(_)) => GameStatus::Finished,
+            (None, None) => GameStatus::Waiting,
+            (Some(_), None) => GameStatus::Active,
+        }
+    }
+}
+
+#[derive(Default)]
+struct ServerState {
+    next_id: u64,
+    players: HashMap<String, Player>,
+    sessions: HashMap<String, String>,
+    games: BTreeMap<String, Game>,
+}
+
+impl ServerState {
+    fn new_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}-{}", prefix, self.next_id)
+    }
+
+    fn send(&self, player_id: &str, message: impl Into<WireMessage>) {
+        if let Some(outbox) = self.players.get(player_id).and_then(|player| player.outbox.as_ref()) {
+            let _ = outbox.send(message.into());
+        }
+    }
+
+    fn send_error(&self, player_id: &str, code: u32, message: &str) {
+        self.send(player_id, error_response(code, message));
+    }
+
+    fn connect(
+        &mut self,
+        player_name: String,
+        protocol_version: &str,
+        session_id: Option<String>,
+        outbox: mpsc::UnboundedSender<WireMessage>,
+    ) -> Result<String, String> {
+        let version = protocol::negotiate(protocol_version).map_err(|error| error.to_string())?;
+
+        let resumed = session_id.and_then(|session_id| self.sessions.get(&session_id).cloned());
+        let player_id = match resumed {
+            Some(player_id) => player_id,
+            None => {
+                let player_id = self.new_id("player");
+                let session_id = self.new_id("session");
+                self.sessions.insert(session_id.clone(), player_id.clone());
+                self.players.insert(
+                    player_id.clone(),
+                    Player {
+                        name: player_name.clone(),
+                        session_id,
+                        outbox: None,
+                        game_id: None,
+                        games_played: 0,
+                        wins: 0,
+                    },
+                );
+                player_id
+            }
+        };
+
+        let player = self.players.get_mut(&player_id).expect("player registered above");
+        player.name = player_name;
+        player.outbox = Some(outbox);
+        let session_id = player.session_id.clone();
+
+        let current_players = self.players.values().filter(|player| player.outbox.is_some()).count() as u32;
+        self.send(
+            &player_id,
+            ServerResponse::Connected {
+                player_id: player_id.clone(),
+                session_id,
+                server_info: ServerInfo {
+                    server_name: "Mock server".to_string(),
+                    version: CLIENT_VERSION.to_string(),
+                    max_players: 64,
+                    current_players,
+                    features: vec!["chat".to_string(), "undo".to_string(), "draw".to_string()],
+                },
+                protocol_version: version.to_string(),
+            },
+        );
+        self.send(&player_id, ServerResponse::Authenticated { player_info: self.player_info(&player_id) });
+        Ok(player_id)
+    }
+
+    /// Drops the connection behind `outbox`, unless the session has already moved to a newer one.
+    fn disconnect(&mut self, player_id: &str, outbox: &mpsc::UnboundedSender<WireMessage>) {
+        let Some(player) = self.players.get_mut(player_id) else {
+            return;
+        };
+        if !player.outbox.as_ref().is_some_and(|current| current.same_channel(outbox)) {
+            return;
+        }
+        player.outbox = None;
+
+        let opponent = player
+            .game_id
+            .as_ref()
+            .and_then(|game_id| self.games.get(game_id))
+            .and_then(|game| game.opponent_of(player_id));
+        if let Some(opponent) = opponent {
+            self.send(
+                &opponent,
+                NetworkMessage::PlayerLeft {
+                    player_id: player_id.to_string(),
+                    reason: "disconnected".to_string(),
+                },
+            );
+        }
+    }
+
+    fn player_info(&self, player_id: &str) -> PlayerInfo {
+        let player = &self.players[player_id];
+        let is_online = player.outbox.is_some();
+        PlayerInfo {
+            id: player_id.to_string(),
+            name: player.name.clone(),
+            rating: 1200,
+            games_played: player.games_played,
+            win_rate: if player.games_played == 0 {
+                0.0
+            } else {
+                player.wins as f32 / player.games_played as f32
+            },
+            is_online,
+            status: match (is_online, &player.game_id) {
+                (false, _) => PlayerStatus::Offline,
+                (true, Some(_)) => PlayerStatus::InGame,
+                (true, None) => PlayerStatus::Online,
+            },
+        }
+    }
+
+    fn game_info(&self, game: &Game) -> GameInfo {
+        GameInfo {
+            id: game.id.clone(),
+            white_player: Some(self.player_info(&game.white)),
+            black_player: game.black.as_deref().map(|black| self.player_info(black)),
+            status: game.status(),
+            time_control: game.time_control.clone(),
+            move_count: game.moves.len() as u32,
+            created_at: game.created_at,
+        }
+    }
+
+    fn snapshot(&self, game: &Game) -> GameStateSnapshot {
+        GameStateSnapshot {
+            board_fen: vec![game.position.to_string()],
+            move_history: game.moves.clone(),
+            white_player: Some(self.player_info(&game.white)),
+            black_player: game.black.as_deref().map(|black| self.player_info(black)),
+            current_player: game.position.active_color,
+            move_count: game.moves.len() as u32,
+            is_check: false,
+            game_result: game.result.clone(),
+            time_control: game.time_control.clone(),
+            white_time_remaining: game.time_remaining().map(|(white, _)| white),
+            black_time_remaining: game.time_remaining().map(|(_, black)| black),
+            last_move: game.last_move,
+        }
+    }
+
+    /// The unfinished game `player_id` is seated in.
+    fn active_game_id(&self, player_id: &str) -> Option<String> {
+        let game_id = self.players.get(player_id)?.game_id.clone()?;
+        let game = self.games.get(&game_id)?;
+        (game.result.is_none()).then_some(game_id)
+    }
+
+    fn handle_request(&mut self, player_id: &str, request: ClientRequest) {
+        if let Err((code, message)) = self.try_handle_request(player_id, request) {
+            self.send_error(player_id, code, &message);
+        }
+    }
+
+    fn try_handle_request(&mut self, player_id: &str, request: ClientRequest) -> Result<(), (u32, String)> {
+        match request {
+            ClientRequest::Ping => self.send(player_id, ServerResponse::Pong),
+            ClientRequest::GetGameList => {
+                let games = self
+                    .games
+                    .values()
+                    .filter(|game| !game.is_private && game.result.is_none())
+                    .map(|game| self.game_info(game))
+                    .collect();
+                self.send(player_id, ServerResponse::GameList { games });
+            }
+            ClientRequest::GetPlayerList => {
+                let players = self
+                    .players
+                    .iter()
+                    .filter(|(_, player)| player.outbox.is_some())
+                    .map(|(id, _)| self.player_info(id))
+                    .collect();
+                self.send(player_id, ServerResponse::PlayerList { players });
+            }
+            ClientRequest::CreateGame { time_control, is_private, password } => {
+                self.create_game(player_id, time_control, is_private, password)?
+            }
+            ClientRequest::JoinGame { game_id, password } => self.join_game(player_id, &game_id, password)?,
+            ClientRequest::SendChatMessage { message, message_type } => self.chat(player_id, message, message_type)?,
+            ClientRequest::Disconnect { .. } => {}
+            request => {
+                let game_id = self
+                    .active_game_id(player_id)
+                    .ok_or((ERROR_CONFLICT, "Not in a game".to_string()))?;
+                self.handle_game_request(player_id, &game_id, request)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn create_game(
+        &mut self,
+        player_id: &str,
+        time_control: Option<TimeControl>,
+        is_private: bool,
+        password: Option<String>,
+    ) -> Result<(), (u32, String)> {
+        if self.active_game_id(player_id).is_some() {
+            return Err((ERROR_CONFLICT, "Already in a game".to_string()));
+        }
+
+        let game_id = self.new_id("game");
+        self.games.insert(
+            game_id.clone(),
+            Game {
+                id: game_id.clone(),
+                white: player_id.to_string(),
+                black: None,
+                is_private,
+                password,
+                clock: ChessClock::new(time_control.clone()),
+                clock_updated_at: Instant::now(),
+                time_control,
+                created_at: unix_time(),
+                position: FenPosition::starting(),
+                previous_positions: Vec::new(),
+                moves: Vec::new(),
+                last_move: None,
+                draw_offer: None,
+                undo_offer: None,
+                result: None,
+            },
+        );
+        if let Some(player) = self.players.get_mut(player_id) {
+            player.game_id = Some(game_id.clone());
+        }
+        self.send(
+            player_id,
+            ServerResponse::GameCreated {
+                game_id,
+                player_color: PieceColor::White,
+            },
+        );
+        Ok(())
+    }
+
+    fn join_game(&mut self, player_id: &str, game_id: &str, password: Option<String>) -> Result<(), (u32, String)> {
+        let in_other_game = self.active_game_id(player_id).is_some_and(|active| active != game_id);
+        let game = self
+            .games
+            .get_mut(game_id)
+            .ok_or((ERROR_NOT_FOUND, format!("No game {}", game_id)))?;
+
+        // Seated players rejoin; a free black seat takes a new one, given the password
+        let newly_seated = match game.color_of(player_id) {
+            Some(_) => false,
+            None if in_other_game => return Err((ERROR_CONFLICT, "Already in a game".to_string())),
+            None if game.black.is_none() && game.result.is_none() => {
+                if game.password.is_some() && game.password != password {
+                    return Err((ERROR_FORBIDDEN, "Wrong password".to_string()));
+                }
+                game.black = Some(player_id.to_string());
+                true
+            }
+            None => return Err((ERROR_CONFLICT, "Game is full".to_string())),
+        };
+        if let Some(player) = self.players.get_mut(player_id) {
+            player.game_id = Some(game_id.to_string());
+        }
+
+        let game = &self.games[game_id];
+        let recipients = if newly_seated { game.players() } else { vec![player_id.to_string()] };
+        for recipient in recipients {
+            let opponent_info = game.opponent_of(&recipient).map(|opponent| self.player_info(&opponent));
+            self.send(
+                &recipient,
+                ServerResponse::GameJoined {
+                    game_id: game_id.to_string(),
+                    player_color: game.color_of(&recipient).expect("recipient is seated"),
+                    opponent_info,
+                    game_state: self.snapshot(game),
+                },
+            );
+        }
+        if newly_seated {
+            let name = self.players[player_id].name.clone();
+            self.system_message(&game.players(), &format!("{} joined the game", name));
+        }
+        Ok(())
+    }
+
+    fn handle_game_request(&mut self, player_id: &str, game_id: &str, request: ClientRequest) -> Result<(), (u32, String)> {
+        if let Some(result) = self.games.get_mut(game_id).and_then(Game::run_clock) {
+            self.finish_game(game_id, result);
+            if request != ClientRequest::RequestGameState {
+                return Err((ERROR_CONFLICT, "Time is up".to_string()));
+            }
+        }
+
+        let game = self.games.get_mut(game_id).expect("active game exists");
+        let color = game.color_of(player_id).expect("player is seated");
+        let opponent = game.opponent_of(player_id);
+
+        match request {
+            ClientRequest::RequestGameState => {
+                let snapshot = self.snapshot(&self.games[game_id]);
+                self.send(player_id, ServerResponse::GameStateUpdate { game_state: snapshot });
+                return Ok(());
+            }
+            ClientRequest::Resign => {
+                let result = match color {
+                    PieceColor::White => GameResult::BlackWins(GameEndReason::Resignation),
+                    PieceColor::Black => GameResult::WhiteWins(GameEndReason::Resignation),
+                };
+                self.finish_game(game_id, result);
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        let opponent = opponent.ok_or((ERROR_CONFLICT, "Waiting for an opponent".to_string()))?;
+        match request {
+            ClientRequest::MakeMove { from, to, promotion } => {
+                if game.position.active_color != color {
+                    return Err((ERROR_CONFLICT, "Not your turn".to_string()));
+                }
+                let before = game.position.clone();
+                let chess_move = game
+                    .position
+                    .apply_move(from, to, promotion)
+                    .ok_or((ERROR_BAD_REQUEST, format!("Illegal move {}", to_uci_move(from, to, promotion))))?;
+
+                game.previous_positions.push(before);
+                game.moves.push(to_uci_move(from, to, chess_move.promotion));
+                game.last_move = Some((from, to));
+                game.undo_offer = None;
+                game.clock.advance_to(game.moves.len() as u32);
+
+                let update = ServerResponse::MoveUpdate {
+                    from,
+                    to,
+                    promotion: chess_move.promotion,
+                    move_number: game.moves.len() as u32,
+                    time_remaining: game.time_remaining(),
+                };
+                for recipient in game.players() {
+                    self.send(&recipient, update.clone());
+                }
+            }
+            ClientRequest::OfferDraw => {
+                if game.draw_offer.is_some() {
+                    return Err((ERROR_TOO_MANY_REQUESTS, "A draw offer is already pending".to_string()));
+                }
+                game.draw_offer = Some(player_id.to_string());
+                self.send(&opponent, ServerResponse::DrawOffered { from_player: player_id.to_string() });
+            }
+            ClientRequest::AcceptDraw | ClientRequest::DeclineDraw => {
+                if game.draw_offer.as_deref() != Some(opponent.as_str()) {
+                    return Err((ERROR_NOT_FOUND, "No draw offer to answer".to_string()));
+                }
+                game.draw_offer = None;
+                let accepted = matches!(request, ClientRequest::AcceptDraw);
+                self.send(&opponent, ServerResponse::DrawResponse { accepted });
+                if accepted {
+                    self.finish_game(game_id, GameResult::Draw(DrawReason::Agreement));
+                }
+            }
+            ClientRequest::RequestUndo => {
+                if game.undo_offer.is_some() {
+                    return Err((ERROR_TOO_MANY_REQUESTS, "An undo request is already pending".to_string()));
+                }
+                // Back to the requester's turn: their last move, plus the reply if there was one
+                let moves_count = if game.position.active_color == color { 2 } else { 1 };
+                if game.moves.len() < moves_count as usize {
+                    return Err((ERROR_NOT_FOUND, "Nothing to undo".to_string()));
+                }
+                game.undo_offer = Some((player_id.to_string(), moves_count));
+                self.send(
+                    &opponent,
+                    ServerResponse::UndoOffered {
+                        from_player: player_id.to_string(),
+                        moves_count,
+                    },
+                );
+            }
+            ClientRequest::AcceptUndo | ClientRequest::DeclineUndo => {
+                let moves_count = match &game.undo_offer {
+                    Some((requester, moves_count)) if *requester == opponent => *moves_count as usize,
+                    _ => return Err((ERROR_NOT_FOUND, "No undo request to answer".to_string())),
+                };
+                game.undo_offer = None;
+                let accepted = matches!(request, ClientRequest::AcceptUndo);
+                if accepted {
+                    let keep = game.moves.len() - moves_count;
+                    game.position = game.previous_positions[keep].clone();
+                    game.previous_positions.truncate(keep);
+                    game.moves.truncate(keep);
+                    game.clock.advance_to(keep as u32);
+                    game.last_move = game
+                        .moves
+                        .last()
+                        .and_then(|text| crate::game::fen::parse_uci_move(text))
+                        .map(|(from, to, _)| (from, to));
+                }
+
+                self.send(&opponent, ServerResponse::UndoResponse { accepted });
+                if accepted {
+                    let game = &self.games[game_id];
+                    let snapshot = self.snapshot(game);
+                    for recipient in game.players() {
+                        self.send(&recipient, ServerResponse::GameStateUpdate { game_state: snapshot.clone() });
+                    }
+                }
+            }
+            request => return Err((ERROR_BAD_REQUEST, format!("Unsupported request {:?}", request))),
+        }
+        Ok(())
+    }
+
+    fn finish_game(&mut self, game_id: &str, result: GameResult) {
+        let Some(game) = self.games.get_mut(game_id) else {
+            return;
+        };
+        game.result = Some(result.clone());
+        game.clock.stop();
+        game.draw_offer = None;
+        game.undo_offer = None;
+
+        let winner = match &result {
+            GameResult::WhiteWins(_) => Some(game.white.clone()),
+            GameResult::BlackWins(_) => game.black.clone(),
+            GameResult::Draw(_) => None,
+        };
+        let players = game.players();
+        for player_id in &players {
+            if let Some(player) = self.players.get_mut(player_id) {
+                player.game_id = None;
+                player.games_played += 1;
+                if winner.as_ref() == Some(player_id) {
+                    player.wins += 1;
+                }
+            }
+            self.send(player_id, ServerResponse::GameOver { result: result.clone() });
+        }
+    }
+
+    fn chat(&mut self, player_id: &str, message: String, message_type: ChatMessageType) -> Result<(), (u32, String)> {
+        let recipients: Vec<String> = match message_type {
+            ChatMessageType::Global => self
+                .players
+                .iter()
+                .filter(|(_, player)| player.outbox.is_some())
+                .map(|(id, _)| id.clone())
+                .collect(),
+            // No recipient field in the request, so private messages go to the opponent
+            ChatMessageType::Game | ChatMessageType::Private => {
+                let game_id = self
+                    .active_game_id(player_id)
+                    .ok_or((ERROR_CONFLICT, "Not in a game".to_string()))?;
+                self.games[&game_id].players()
+            }
+            ChatMessageType::System => return Err((ERROR_FORBIDDEN, "Players can't send system messages".to_string())),
+        };
+
+        let chat = ServerResponse::ChatMessage {
+            from_player: player_id.to_string(),
+            message,
+            message_type,
+            timestamp: unix_time(),
+        };
+        for recipient in recipients {
+            self.send(&recipient, chat.clone());
+        }
+        Ok(())
+    }
+
+    fn system_message(&self, recipients: &[String], message: &str) {
+        let chat = ServerResponse::ChatMessage {
+            from_player: "server".to_string(),
+            message: message.to_string(),
+            message_type: ChatMessageType::System,
+            timestamp: unix_time(),
+        };
+        for recipient in recipients {
+            self.send(recipient, chat.clone());
+        }
+    }
+}
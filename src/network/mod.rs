@@ -1,10 +1,56 @@
+pub mod protocol;
+pub mod framing;
+pub mod transport;
+pub mod tcp;
+pub mod websocket;
+pub mod connection;
+pub mod session;
+pub mod reconciliation;
+pub mod negotiation;
+pub mod clock_sync;
+#[cfg(feature = "mock_server")]
+pub mod mock_server;
+
 use bevy::prelude::*;
+use crate::core::{GameState, CoreSet};
+
+pub use protocol::*;
+pub use framing::*;
+pub use transport::*;
+pub use tcp::*;
+pub use websocket::*;
+pub use connection::*;
+pub use session::*;
+pub use reconciliation::*;
+pub use negotiation::*;
+pub use clock_sync::*;
 
 pub struct NetworkPlugin;
 
 impl Plugin for NetworkPlugin {
     fn build(&self, app: &mut App) {
-        // TODO: ネットワークシステムの実装
-        info!("Network plugin loaded (placeholder)");
+        app
+            .init_state::<crate::core::states::NetworkState>()
+            .init_resource::<NetworkRuntime>()
+            .init_resource::<NetworkClient>()
+            .init_resource::<OnlineGame>()
+            .init_resource::<Negotiation>()
+            .add_systems(OnEnter(GameState::Connecting), start_connection)
+            .add_systems(OnEnter(GameState::MainMenu), (close_connection, end_online_game, reset_chess_clock, reset_negotiation))
+            .add_systems(Update, (
+                receive_inbound_messages,
+                track_online_game,
+                track_negotiation_responses,
+                sync_chess_clock,
+                (reconcile_server_state, send_local_moves, handle_negotiation_actions)
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+                monitor_connection,
+                reconnect_to_server,
+                send_outbound_messages,
+                sync_network_state,
+            ).chain().in_set(CoreSet::Network));
+
+        info!("Network plugin loaded");
     }
-}
\ No newline at end of file
+}
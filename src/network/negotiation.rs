@@ -0,0 +1,183 @@
+//! Draw offers, undo requests and resignation for online games.
+//!
+//! `GameAction`s become requests to the server, with our own offers rate
+//! limited. Offers from the opponent wait in `Negotiation::incoming` until the
+//! confirm dialog answers them. Taking moves back and applying the result is
+//! left to `reconciliation`, which owns the board.
+
+use bevy::prelude::*;
+
+use crate::core::{
+    constants::OFFER_COOLDOWN_SECONDS,
+    events::*,
+    resources::UIState,
+};
+
+use super::reconciliation::OnlineGame;
+
+/// An opponent's offer waiting for our answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IncomingOffer {
+    Draw { from_player: String },
+    Undo { from_player: String, moves_count: u32 },
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct Negotiation {
+    pub incoming: Option<IncomingOffer>,
+    /// Our offer is out and the opponent hasn't answered yet.
+    pub awaiting_draw_response: bool,
+    pub awaiting_undo_response: bool,
+    draw_offered_at: Option<f32>,
+    undo_requested_at: Option<f32>,
+}
+
+impl Negotiation {
+    /// Seconds until another draw offer may be sent.
+    pub fn draw_cooldown(&self, now: f32) -> f32 {
+        cooldown_left(self.draw_offered_at, now)
+    }
+
+    /// Seconds until another undo request may be sent.
+    pub fn undo_cooldown(&self, now: f32) -> f32 {
+        cooldown_left(self.undo_requested_at, now)
+    }
+}
+
+fn cooldown_left(sent_at: Option<f32>, now: f32) -> f32 {
+    sent_at.map_or(0.0, |sent_at| (sent_at + OFFER_COOLDOWN_SECONDS - now).max(0.0))
+}
+
+pub fn reset_negotiation(mut negotiation: ResMut<Negotiation>) {
+    *negotiation = Negotiation::default();
+}
+
+pub fn handle_negotiation_actions(
+    mut actions: EventReader<GameActionEvent>,
+    mut negotiation: ResMut<Negotiation>,
+    mut online: ResMut<OnlineGame>,
+    mut ui_state: ResMut<UIState>,
+    mut requests: EventWriter<SendClientRequestEvent>,
+    time: Res<Time<Real>>,
+) {
+    let now = time.elapsed_secs();
+
+    for event in actions.read() {
+        let is_negotiation = matches!(
+            event.action,
+            GameAction::OfferDraw
+                | GameAction::AcceptDraw
+                | GameAction::DeclineDraw
+                | GameAction::RequestUndo
+                | GameAction::AccpetUndo
+                | GameAction::DeclineUndo
+                | GameAction::Resign
+        );
+        if !is_negotiation {
+            continue;
+        }
+        if !online.is_active() || online.is_finished() {
+            ui_state.set_status("No online game in progress".to_string());
+            continue;
+        }
+
+        let request = match (&event.action, negotiation.incoming.clone()) {
+            (GameAction::OfferDraw, _) => {
+                let cooldown = negotiation.draw_cooldown(now);
+                if cooldown > 0.0 {
+                    ui_state.set_status(format!("You can offer another draw in {:.0}s", cooldown.ceil()));
+                    continue;
+                }
+                negotiation.draw_offered_at = Some(now);
+                negotiation.awaiting_draw_response = true;
+                ui_state.set_status("Draw offered".to_string());
+                ClientRequest::OfferDraw
+            }
+            (GameAction::RequestUndo, _) => {
+                let cooldown = negotiation.undo_cooldown(now);
+                if cooldown > 0.0 {
+                    ui_state.set_status(format!("You can ask for another undo in {:.0}s", cooldown.ceil()));
+                    continue;
+                }
+                let Some(moves_count) = online.undo_moves_for_request() else {
+                    ui_state.set_status("Nothing to undo".to_string());
+                    continue;
+                };
+                negotiation.undo_requested_at = Some(now);
+                negotiation.awaiting_undo_response = true;
+                online.undo_moves = Some(moves_count);
+                ui_state.set_status("Undo requested".to_string());
+                ClientRequest::RequestUndo
+            }
+            (GameAction::AcceptDraw, Some(IncomingOffer::Draw { .. })) => ClientRequest::AcceptDraw,
+            (GameAction::DeclineDraw, Some(IncomingOffer::Draw { .. })) => ClientRequest::DeclineDraw,
+            (GameAction::AccpetUndo, Some(IncomingOffer::Undo { moves_count, .. })) => {
+                online.accept_undo(moves_count);
+                ClientRequest::AcceptUndo
+            }
+            (GameAction::DeclineUndo, Some(IncomingOffer::Undo { .. })) => ClientRequest::DeclineUndo,
+            (GameAction::Resign, _) => ClientRequest::Resign,
+            (action, _) => {
+                debug!("No matching offer to answer with {:?}", action);
+                continue;
+            }
+        };
+
+        if matches!(
+            request,
+            ClientRequest::AcceptDraw | ClientRequest::DeclineDraw | ClientRequest::AcceptUndo | ClientRequest::DeclineUndo
+        ) {
+            negotiation.incoming = None;
+        }
+        requests.write(SendClientRequestEvent { request });
+    }
+}
+
+pub fn track_negotiation_responses(
+    mut responses: EventReader<NetworkResponseEvent>,
+    mut negotiation: ResMut<Negotiation>,
+    online: Res<OnlineGame>,
+    mut ui_state: ResMut<UIState>,
+) {
+    for event in responses.read() {
+        match &event.response {
+            ServerResponse::GameCreated { .. } | ServerResponse::GameJoined { .. } => {
+                *negotiation = Negotiation::default();
+            }
+            ServerResponse::DrawOffered { from_player } if online.is_active() => {
+                negotiation.incoming = Some(IncomingOffer::Draw {
+                    from_player: from_player.clone(),
+                });
+            }
+            ServerResponse::UndoOffered { from_player, moves_count } if online.is_active() => {
+                negotiation.incoming = Some(IncomingOffer::Undo {
+                    from_player: from_player.clone(),
+                    moves_count: *moves_count,
+                });
+            }
+            ServerResponse::DrawResponse { accepted } => {
+                negotiation.awaiting_draw_response = false;
+                let answer = if *accepted { "accepted" } else { "declined" };
+                ui_state.set_status(format!("Draw offer {}", answer));
+            }
+            ServerResponse::UndoResponse { accepted } => {
+                negotiation.awaiting_undo_response = false;
+                let answer = if *accepted { "accepted" } else { "declined" };
+                ui_state.set_status(format!("Undo request {}", answer));
+            }
+            // The server drops a pending undo once someone moves
+            ServerResponse::MoveUpdate { .. } => {
+                if matches!(negotiation.incoming, Some(IncomingOffer::Undo { .. })) {
+                    negotiation.incoming = None;
+                }
+            }
+            ServerResponse::GameOver { result } => {
+                negotiation.incoming = None;
+                negotiation.awaiting_draw_response = false;
+                negotiation.awaiting_undo_response = false;
+                ui_state.set_status(result.describe());
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Versioned JSON wire format shared with the server.
+//! Every frame carries the protocol version next to a tagged message:
+//! `{"version":"1.0","kind":"request","body":{"type":"make_move",...}}`.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::core::{
+    constants::PROTOCOL_VERSION,
+    events::{ClientRequest, NetworkMessage, ServerResponse},
+};
+
+/// `error_code`s of `ServerResponse::Error`, named after their HTTP counterparts.
+pub const ERROR_BAD_REQUEST: u32 = 400;
+pub const ERROR_FORBIDDEN: u32 = 403;
+pub const ERROR_NOT_FOUND: u32 = 404;
+pub const ERROR_CONFLICT: u32 = 409;
+pub const ERROR_TOO_MANY_REQUESTS: u32 = 429;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "body", rename_all = "snake_case")]
+pub enum WireMessage {
+    /// Session traffic: handshake, pongs, errors.
+    Session(NetworkMessage),
+    Request(ClientRequest),
+    /// Boxed: snapshots make responses several times larger than anything else.
+    Response(Box<ServerResponse>),
+}
+
+impl From<NetworkMessage> for WireMessage {
+    fn from(message: NetworkMessage) -> Self {
+        Self::Session(message)
+    }
+}
+
+impl From<ClientRequest> for WireMessage {
+    fn from(request: ClientRequest) -> Self {
+        Self::Request(request)
+    }
+}
+
+impl From<ServerResponse> for WireMessage {
+    fn from(response: ServerResponse) -> Self {
+        Self::Response(Box::new(response))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Frame {
+    pub version: String,
+    #[serde(flatten)]
+    pub message: WireMessage,
+}
+
+impl Frame {
+    pub fn new(message: impl Into<WireMessage>) -> Self {
+        Self {
+            version: PROTOCOL_VERSION.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ProtocolError {
+    #[error("malformed message: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid protocol version `{0}`")]
+    InvalidVersion(String),
+    #[error("protocol version {theirs} is incompatible with {ours}")]
+    IncompatibleVersion { ours: String, theirs: String },
+}
+
+/// `major.minor`; peers with the same major version understand each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ProtocolVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl ProtocolVersion {
+    pub fn parse(version: &str) -> Result<Self, ProtocolError> {
+        let invalid = || ProtocolError::InvalidVersion(version.to_string());
+        let (major, minor) = version.split_once('.').unwrap_or((version, "0"));
+
+        Ok(Self {
+            major: major.parse().map_err(|_| invalid())?,
+            minor: minor.parse().map_err(|_| invalid())?,
+        })
+    }
+
+    pub fn current() -> Self {
+        Self::parse(PROTOCOL_VERSION).expect("PROTOCOL_VERSION is valid")
+    }
+}
+
+impl std::fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// Version both sides can speak, given the one the peer announced: the lower of the
+/// two when the major versions match.
+pub fn negotiate(theirs: &str) -> Result<ProtocolVersion, ProtocolError> {
+    let ours = ProtocolVersion::current();
+    let theirs = ProtocolVersion::parse(theirs)?;
+
+    if ours.major != theirs.major {
+        return Err(ProtocolError::IncompatibleVersion {
+            ours: ours.to_string(),
+            theirs: theirs.to_string(),
+        });
+    }
+    Ok(ours.min(theirs))
+}
+
+pub fn encode(message: impl Into<WireMessage>) -> Result<String, ProtocolError> {
+    Ok(serde_json::to_string(&Frame::new(message))?)
+}
+
+/// Parses a frame, rejecting versions from another major release.
+pub fn decode(text: &str) -> Result<Frame, ProtocolError> {
+    let frame: Frame = serde_json::from_str(text)?;
+    negotiate(&frame.version)?;
+    Ok(frame)
+}
@@ -0,0 +1,532 @@
+//! Keeps online games in line with the server, which owns the real game.
+//!
+//! Our own moves are shown straight away and kept as pending until the server
+//! echoes them in a `MoveUpdate`; a rejected move (an `Error` with a move's
+//! error code) while moves are pending takes them back. Opponent moves are
+//! played when their `move_number` is the next one we expect. A gap, or a move
+//! that doesn't fit our board, asks the server for a snapshot, and snapshots
+//! replace whatever we had. An agreed undo takes the moves back from our own
+//! history before the server's snapshot arrives.
+
+use std::collections::VecDeque;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    core::{
+        constants::RESYNC_TIMEOUT_SECONDS,
+        events::*,
+        resources::GameSettings,
+    },
+    game::{
+        board::ChessBoard,
+        fen::{parse_uci_move, FenPosition},
+        pieces::{move_piece, spawn_piece, ChessPiece},
+        state::{CheckStatus, ChessMove, GameStateResource, GameStatus, MoveHistory},
+    },
+    graphics::{ChessMaterials, ChessMeshes, Tweens},
+    network::protocol::{ERROR_BAD_REQUEST, ERROR_CONFLICT},
+    BoardPosition, PieceColor, PieceType,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingMove {
+    pub from: BoardPosition,
+    pub to: BoardPosition,
+    pub promotion: Option<PieceType>,
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct OnlineGame {
+    pub player_color: Option<PieceColor>,
+    /// Position after the last move the server confirmed.
+    pub confirmed_position: FenPosition,
+    /// Half-moves the server has confirmed, counted like `MoveUpdate::move_number`.
+    pub confirmed_moves: u32,
+    /// Our moves already on the board that the server hasn't echoed yet.
+    pub pending_moves: VecDeque<PendingMove>,
+    /// When the outstanding `RequestGameState` was sent.
+    pub resync_requested_at: Option<f32>,
+    /// Half-moves to take back once the undo we asked for, or accepted, goes through.
+    pub undo_moves: Option<u32>,
+    pub result: Option<GameResult>,
+    /// Responses waiting for the board; they can arrive before the game screen opens.
+    inbox: VecDeque<ServerResponse>,
+}
+
+impl OnlineGame {
+    pub fn is_active(&self) -> bool {
+        self.player_color.is_some()
+    }
+
+    pub fn is_awaiting_snapshot(&self) -> bool {
+        self.resync_requested_at.is_some()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// Half-moves an undo asked for now would take back: our last move, and the reply if there was one.
+    pub fn undo_moves_for_request(&self) -> Option<u32> {
+        let position = self.predicted_position();
+        let moves_count = if self.player_color == Some(position.active_color) { 2 } else { 1 };
+        (self.confirmed_moves + self.pending_moves.len() as u32 >= moves_count).then_some(moves_count)
+    }
+
+    /// We agreed to the opponent's undo; the server only answers with a snapshot.
+    pub fn accept_undo(&mut self, moves_count: u32) {
+        self.undo_moves = Some(moves_count);
+        self.inbox.push_back(ServerResponse::UndoResponse { accepted: true });
+    }
+
+    /// Confirmed position with our pending moves played on top.
+    pub fn predicted_position(&self) -> FenPosition {
+        let mut position = self.confirmed_position.clone();
+        for pending in &self.pending_moves {
+            position.apply_move(pending.from, pending.to, pending.promotion);
+        }
+        position
+    }
+
+    fn start(&mut self, player_color: PieceColor) {
+        *self = Self {
+            player_color: Some(player_color),
+            ..default()
+        };
+    }
+}
+
+/// The 3D board and what it takes to change it.
+#[derive(SystemParam)]
+pub struct BoardEntities<'w, 's> {
+    commands: Commands<'w, 's>,
+    board: ResMut<'w, ChessBoard>,
+    pieces: Query<'w, 's, (Entity, &'static mut ChessPiece, &'static mut Transform)>,
+    meshes: Res<'w, ChessMeshes>,
+    materials: Res<'w, ChessMaterials>,
+    settings: Res<'w, GameSettings>,
+    tweens: ResMut<'w, Tweens>,
+    time: Res<'w, Time>,
+}
+
+impl BoardEntities<'_, '_> {
+    pub fn placement(&self) -> String {
+        FenPosition::placement_of(self.board.get_all_pieces().into_iter().filter_map(|(pos, entity)| {
+            self.pieces
+                .get(entity)
+                .ok()
+                .map(|(_, piece, _)| (pos, piece.piece_type, piece.color))
+        }))
+    }
+
+    pub fn play(&mut self, chess_move: &ChessMove, turn: u32) -> Result<(), String> {
+        move_piece(
+            &mut self.commands,
+            &mut self.pieces,
+            &mut self.board,
+            &mut self.tweens,
+            &self.settings,
+            chess_move.from,
+            chess_move.to,
+            chess_move.promotion,
+            turn,
+        )?;
+        Ok(())
+    }
+
+    /// Replaces every piece with the ones in `position`, unless the board already matches it.
+    /// Returns whether it did; the new pieces only exist once commands have been applied.
+    pub fn restore(&mut self, position: &FenPosition) -> bool {
+        if self.placement() == position.placement() {
+            return false;
+        }
+
+        for (entity, _, _) in self.pieces.iter() {
+            self.commands.entity(entity).despawn();
+        }
+        self.board.clear();
+
+        for (pos, piece_type, color) in position.pieces() {
+            let entity = spawn_piece(&mut self.commands, piece_type, color, pos, &self.meshes, &self.materials);
+            self.board.set_piece_at(pos, Some(entity));
+        }
+        true
+    }
+}
+
+/// Leaving the online screens ends the game on our side.
+pub fn end_online_game(mut online: ResMut<OnlineGame>) {
+    *online = OnlineGame::default();
+}
+
+/// Queues the responses that concern the board until the game screen can take them.
+pub fn track_online_game(mut responses: EventReader<NetworkResponseEvent>, mut online: ResMut<OnlineGame>) {
+    for event in responses.read() {
+        match &event.response {
+            // Rejoining after a reconnect lands here too; the snapshot replaces our board
+            ServerResponse::GameCreated { player_color, .. } | ServerResponse::GameJoined { player_color, .. } => {
+                online.start(*player_color);
+                online.inbox.push_back(event.response.clone());
+            }
+            ServerResponse::MoveUpdate { .. }
+            | ServerResponse::GameStateUpdate { .. }
+            | ServerResponse::UndoResponse { .. }
+            | ServerResponse::GameOver { .. }
+            | ServerResponse::Error { .. }
+                if online.is_active() =>
+            {
+                online.inbox.push_back(event.response.clone());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether an error answers a `MakeMove`: an illegal move (400), or one out of turn (409).
+/// Draw offers and undo requests fail with 404 or 429 and chat with 403; those leave our moves alone.
+fn rejects_move(error_code: Option<u32>) -> bool {
+    matches!(error_code, Some(ERROR_BAD_REQUEST | ERROR_CONFLICT))
+}
+
+pub fn reconcile_server_state(
+    mut online: ResMut<OnlineGame>,
+    mut history: ResMut<MoveHistory>,
+    mut game_state: ResMut<GameStateResource>,
+    mut board: BoardEntities,
+    mut requests: EventWriter<SendClientRequestEvent>,
+    time: Res<Time<Real>>,
+) {
+    let now = time.elapsed_secs();
+    let mut resync = online
+        .resync_requested_at
+        .is_some_and(|requested_at| now - requested_at > RESYNC_TIMEOUT_SECONDS);
+
+    while let Some(response) = online.inbox.pop_front() {
+        let rebuilt = match response {
+            ServerResponse::GameCreated { .. } => {
+                let position = FenPosition::starting();
+                reset_history(&mut history, &position);
+                update_game_state(&mut game_state, &position, 0);
+                online.confirmed_position = position.clone();
+                board.restore(&position)
+            }
+            ServerResponse::GameJoined { game_state: snapshot, .. } | ServerResponse::GameStateUpdate { game_state: snapshot } => {
+                match apply_snapshot(&snapshot, &mut online, &mut history, &mut board) {
+                    Ok(rebuilt) => {
+                        update_game_state(&mut game_state, &online.confirmed_position, snapshot.move_count);
+                        apply_snapshot_status(&mut game_state, &snapshot);
+                        rebuilt
+                    }
+                    Err(error) => {
+                        warn!("Ignoring game snapshot: {}", error);
+                        false
+                    }
+                }
+            }
+            ServerResponse::MoveUpdate { from, to, promotion, move_number, .. } => {
+                let pending = PendingMove { from, to, promotion };
+                match apply_move_update(pending, move_number, &mut online, &mut history, &mut board, now) {
+                    Ok(()) => {
+                        let predicted = online.predicted_position();
+                        let move_count = online.confirmed_moves + online.pending_moves.len() as u32;
+                        update_game_state(&mut game_state, &predicted, move_count);
+                    }
+                    Err(reason) => {
+                        warn!("Out of sync with the server: {}", reason);
+                        resync = true;
+                    }
+                }
+                false
+            }
+            ServerResponse::Error { message, error_code }
+                if rejects_move(error_code) && !online.pending_moves.is_empty() =>
+            {
+                warn!(
+                    "Server rejected our move ({}), taking back {} move(s)",
+                    message,
+                    online.pending_moves.len()
+                );
+                let keep = history.moves.len().saturating_sub(online.pending_moves.len());
+                history.moves.truncate(keep);
+                history.positions.truncate(keep + 1);
+                online.pending_moves.clear();
+
+                let position = online.confirmed_position.clone();
+                update_game_state(&mut game_state, &position, online.confirmed_moves);
+                board.restore(&position)
+            }
+            ServerResponse::UndoResponse { accepted } => match online.undo_moves.take() {
+                Some(moves_count) if accepted => match take_back(moves_count, &mut online, &mut history, &mut board) {
+                    Ok(rebuilt) => {
+                        info!("Took back {} move(s)", moves_count);
+                        let position = online.confirmed_position.clone();
+                        update_game_state(&mut game_state, &position, online.confirmed_moves);
+                        game_state.game_status = GameStatus::InProgress;
+                        game_state.check_status = CheckStatus::None;
+                        rebuilt
+                    }
+                    Err(reason) => {
+                        warn!("Could not take back moves: {}", reason);
+                        resync = true;
+                        false
+                    }
+                },
+                _ => false,
+            },
+            ServerResponse::GameOver { result } => {
+                (game_state.game_status, game_state.check_status) = result_status(&result, game_state.current_player);
+                online.pending_moves.clear();
+                online.result = Some(result);
+                false
+            }
+            _ => false,
+        };
+
+        // The rest waits a frame, until the respawned pieces can be queried
+        if rebuilt {
+            break;
+        }
+    }
+
+    if resync {
+        info!("Requesting a game snapshot from the server");
+        online.resync_requested_at = Some(now);
+        requests.write(SendClientRequestEvent {
+            request: ClientRequest::RequestGameState,
+        });
+    }
+}
+
+/// Plays our own moves straight away and sends them to the server.
+pub fn send_local_moves(
+    mut move_events: EventReader<MovePieceEvent>,
+    mut online: ResMut<OnlineGame>,
+    mut history: ResMut<MoveHistory>,
+    mut game_state: ResMut<GameStateResource>,
+    mut board: BoardEntities,
+    mut requests: EventWriter<SendClientRequestEvent>,
+) {
+    for event in move_events.read().filter(|event| event.is_player_move) {
+        if !online.is_active() || online.is_finished() {
+            continue;
+        }
+        if online.is_awaiting_snapshot() {
+            debug!("Waiting for the server's snapshot, move ignored");
+            continue;
+        }
+
+        let mut position = online.predicted_position();
+        if online.player_color != Some(position.active_color) {
+            debug!("Not our turn, move ignored");
+            continue;
+        }
+        let Some(mut chess_move) = position.apply_move(event.from, event.to, event.promotion) else {
+            debug!("No piece of ours on {}", event.from.to_algebraic());
+            continue;
+        };
+
+        let move_count = online.confirmed_moves + online.pending_moves.len() as u32 + 1;
+        if let Err(error) = board.play(&chess_move, move_count) {
+            warn!("Could not play {}: {}", chess_move.notation, error);
+            continue;
+        }
+        chess_move.timestamp = board.time.elapsed_secs_f64();
+        history.moves.push(chess_move);
+        history.positions.push(position.to_string());
+        update_game_state(&mut game_state, &position, move_count);
+
+        online.pending_moves.push_back(PendingMove {
+            from: event.from,
+            to: event.to,
+            promotion: event.promotion,
+        });
+        requests.write(SendClientRequestEvent {
+            request: ClientRequest::MakeMove {
+                from: event.from,
+                to: event.to,
+                promotion: event.promotion,
+            },
+        });
+    }
+}
+
+/// Applies one `MoveUpdate`; an error means we no longer know the server's board.
+fn apply_move_update(
+    update: PendingMove,
+    move_number: u32,
+    online: &mut OnlineGame,
+    history: &mut MoveHistory,
+    board: &mut BoardEntities,
+    now: f32,
+) -> Result<(), String> {
+    // Whatever is in flight is part of the snapshot we asked for
+    if online.resync_requested_at.is_some_and(|requested_at| now - requested_at <= RESYNC_TIMEOUT_SECONDS) {
+        return Ok(());
+    }
+
+    let expected = online.confirmed_moves + 1;
+    if move_number < expected {
+        // A repeat; it has to match what we recorded for that move
+        let history_in_step = history.moves.len() == (online.confirmed_moves as usize + online.pending_moves.len());
+        let recorded = history.moves.get(move_number.saturating_sub(1) as usize);
+        return match recorded {
+            Some(recorded) if history_in_step && (recorded.from != update.from || recorded.to != update.to) => {
+                Err(format!("move {} differs from our history", move_number))
+            }
+            _ => Ok(()),
+        };
+    }
+    if move_number > expected {
+        return Err(format!("expected move {}, got move {}", expected, move_number));
+    }
+
+    if online.pending_moves.front() == Some(&update) {
+        online.pending_moves.pop_front();
+        online.confirmed_position.apply_move(update.from, update.to, update.promotion);
+        online.confirmed_moves = move_number;
+        return Ok(());
+    }
+    if !online.pending_moves.is_empty() {
+        return Err(format!("server played move {} differently from us", move_number));
+    }
+
+    if board.placement() != online.confirmed_position.placement() {
+        return Err("board differs from the confirmed position".to_string());
+    }
+    let mut chess_move = online
+        .confirmed_position
+        .apply_move(update.from, update.to, update.promotion)
+        .ok_or_else(|| format!("no piece to move on {}", update.from.to_algebraic()))?;
+    board.play(&chess_move, move_number)?;
+
+    chess_move.timestamp = board.time.elapsed_secs_f64();
+    history.moves.push(chess_move);
+    history.positions.push(online.confirmed_position.to_string());
+    online.confirmed_moves = move_number;
+    Ok(())
+}
+
+/// Takes the server's snapshot as the confirmed position and puts it on the board.
+/// Returns whether the pieces had to be respawned.
+fn apply_snapshot(
+    snapshot: &GameStateSnapshot,
+    online: &mut OnlineGame,
+    history: &mut MoveHistory,
+    board: &mut BoardEntities,
+) -> Result<bool, String> {
+    let replayed = replay_moves(&snapshot.move_history);
+    let position = match snapshot.board_fen.last() {
+        Some(fen) => FenPosition::parse(fen).map_err(|error| error.to_string())?,
+        None => replayed
+            .as_ref()
+            .map(|replayed| replayed.positions.last().cloned().unwrap_or_default())
+            .ok_or("snapshot has neither a position nor a readable move list")?,
+    };
+
+    // The move list fills our history when it leads to the same board
+    match replayed {
+        Some(replayed) if replayed.positions.last().map(FenPosition::placement) == Some(position.placement()) => {
+            history.moves = replayed.moves;
+            history.positions = replayed.positions.iter().map(ToString::to_string).collect();
+        }
+        _ => reset_history(history, &position),
+    }
+
+    let rebuilt = board.restore(&position);
+    online.confirmed_position = position;
+    online.confirmed_moves = snapshot.move_count;
+    online.pending_moves.clear();
+    online.resync_requested_at = None;
+    online.result = snapshot.game_result.clone();
+    Ok(rebuilt)
+}
+
+/// Rolls the confirmed position back `moves_count` half-moves using our history.
+/// Returns whether the pieces had to be respawned.
+fn take_back(
+    moves_count: u32,
+    online: &mut OnlineGame,
+    history: &mut MoveHistory,
+    board: &mut BoardEntities,
+) -> Result<bool, String> {
+    // A move of ours crossed the agreement; the server's snapshot sorts it out
+    if !online.pending_moves.is_empty() {
+        return Err("moves still waiting for the server".to_string());
+    }
+    let keep = online
+        .confirmed_moves
+        .checked_sub(moves_count)
+        .ok_or_else(|| format!("only {} move(s) played", online.confirmed_moves))?;
+    if history.moves.len() != online.confirmed_moves as usize {
+        return Err("history doesn't match the confirmed moves".to_string());
+    }
+
+    history.moves.truncate(keep as usize);
+    history.positions.truncate(keep as usize + 1);
+    let fen = history.positions.last().ok_or("no position to go back to")?;
+    let position = FenPosition::parse(fen).map_err(|error| error.to_string())?;
+
+    let rebuilt = board.restore(&position);
+    online.confirmed_position = position;
+    online.confirmed_moves = keep;
+    Ok(rebuilt)
+}
+
+struct ReplayedMoves {
+    moves: Vec<ChessMove>,
+    /// Starting position first, one more than `moves`.
+    positions: Vec<FenPosition>,
+}
+
+fn replay_moves(move_list: &[String]) -> Option<ReplayedMoves> {
+    let mut position = FenPosition::starting();
+    let mut replayed = ReplayedMoves {
+        moves: Vec::with_capacity(move_list.len()),
+        positions: vec![position.clone()],
+    };
+    for text in move_list {
+        let (from, to, promotion) = parse_uci_move(text)?;
+        replayed.moves.push(position.apply_move(from, to, promotion)?);
+        replayed.positions.push(position.clone());
+    }
+    Some(replayed)
+}
+
+fn reset_history(history: &mut MoveHistory, position: &FenPosition) {
+    history.moves.clear();
+    history.positions = vec![position.to_string()];
+}
+
+fn update_game_state(game_state: &mut GameStateResource, position: &FenPosition, move_count: u32) {
+    game_state.current_player = position.active_color;
+    game_state.move_count = move_count;
+    game_state.castling_rights = position.castling_rights;
+    game_state.en_passant_target = position.en_passant_target;
+    game_state.halfmove_clock = position.halfmove_clock;
+    game_state.fullmove_number = position.fullmove_number;
+}
+
+/// Check and result come from the server; moves alone don't tell us either.
+fn apply_snapshot_status(game_state: &mut GameStateResource, snapshot: &GameStateSnapshot) {
+    let side = snapshot.current_player;
+    game_state.current_player = side;
+    (game_state.game_status, game_state.check_status) = match &snapshot.game_result {
+        None if snapshot.is_check => (GameStatus::Check, CheckStatus::Check(side)),
+        None => (GameStatus::InProgress, CheckStatus::None),
+        Some(result) => result_status(result, side),
+    };
+}
+
+/// `side` is the player to move when the game ended.
+fn result_status(result: &GameResult, side: PieceColor) -> (GameStatus, CheckStatus) {
+    match result {
+        GameResult::WhiteWins(reason) | GameResult::BlackWins(reason) => match reason {
+            GameEndReason::Checkmate => (GameStatus::Checkmate, CheckStatus::Checkmate(side)),
+            GameEndReason::Timeout => (GameStatus::Timeout, CheckStatus::None),
+            GameEndReason::Resignation | GameEndReason::Disconnection => (GameStatus::Resigned, CheckStatus::None),
+        },
+        GameResult::Draw(DrawReason::Stalemate) => (GameStatus::Stalemate, CheckStatus::None),
+        GameResult::Draw(_) => (GameStatus::Draw, CheckStatus::None),
+    }
+}
@@ -0,0 +1,147 @@
+//! Connection state machine on top of `NetworkClient`: handshake timeout, heartbeat
+//! with RTT, dead-connection detection and backoff reconnects that resume the session.
+//!
+//! ```text
+//! Disconnected -> Connecting -> Connected -> Authenticated <-> InGame
+//!                     |             \______________|___________/
+//!                     v                            v (lost)
+//!                   Error  <--- out of attempts -- Reconnecting
+//! ```
+
+use bevy::prelude::*;
+
+use crate::{
+    core::{
+        constants::*,
+        events::*,
+        resources::{ConnectionStatus, GameSettings, NetworkState},
+        states::GameState,
+    },
+    network::connection::{network_error, NetworkClient, NetworkRuntime, ERROR_CONNECT_FAILED},
+};
+
+/// Status changes driven by server responses.
+pub(crate) fn track_session_response(network_state: &mut NetworkState, response: &ServerResponse, time: f32) {
+    match response {
+        ServerResponse::Authenticated { .. } if network_state.connection_status == ConnectionStatus::Connected => {
+            network_state.authenticated();
+        }
+        ServerResponse::GameCreated { game_id, .. } | ServerResponse::GameJoined { game_id, .. } => {
+            network_state.join_game(game_id.clone());
+        }
+        ServerResponse::GameOver { .. } => network_state.leave_game(),
+        ServerResponse::Pong => network_state.pong_received(time),
+        _ => {}
+    }
+}
+
+/// Schedules a reconnect, or gives up and leaves the online screens.
+pub(crate) fn handle_connection_lost(
+    network_state: &mut NetworkState,
+    time: f32,
+    game_state: &GameState,
+    next_game_state: &mut NextState<GameState>,
+) {
+    if network_state.connection_lost(time) {
+        info!(
+            "Connection lost, reconnecting in {:.1}s",
+            network_state.next_reconnect_time - time
+        );
+    } else if game_state.requires_network() {
+        next_game_state.set(GameState::MainMenu);
+    }
+}
+
+pub fn monitor_connection(
+    mut client: ResMut<NetworkClient>,
+    mut network_state: ResMut<NetworkState>,
+    mut network_events: EventWriter<NetworkEvent>,
+    game_state: Res<State<GameState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    time: Res<Time<Real>>,
+) {
+    let now = time.elapsed_secs();
+
+    let lost = if network_state.is_handshaking()
+        && now - network_state.connection_start_time > CONNECTION_TIMEOUT_SECONDS
+    {
+        Some("Timed out waiting for the server")
+    } else if network_state.is_connection_dead(now) {
+        Some("Server stopped responding")
+    } else {
+        None
+    };
+
+    if let Some(reason) = lost {
+        warn!("{}", reason);
+        client.abort();
+        network_events.write(NetworkEvent::error(network_error(ERROR_CONNECT_FAILED, reason.to_string(), true)));
+        handle_connection_lost(&mut network_state, now, game_state.get(), &mut next_game_state);
+        network_events.write(NetworkEvent::disconnected(reason));
+        return;
+    }
+
+    if network_state.ping_due(now) {
+        match client.send(ClientRequest::Ping) {
+            Ok(()) => network_state.ping_sent(now),
+            Err(error) => {
+                network_events.write(NetworkEvent::error(error));
+            }
+        }
+    }
+}
+
+pub fn reconnect_to_server(
+    mut client: ResMut<NetworkClient>,
+    runtime: Res<NetworkRuntime>,
+    settings: Res<GameSettings>,
+    mut network_state: ResMut<NetworkState>,
+    mut network_events: EventWriter<NetworkEvent>,
+    time: Res<Time<Real>>,
+) {
+    let now = time.elapsed_secs();
+    if !network_state.should_reconnect(now) {
+        return;
+    }
+    let Some(session_id) = network_state.session_id.clone() else {
+        return;
+    };
+
+    network_state.begin_reconnect(now);
+    info!(
+        "Reconnecting to {} (attempt {}/{})",
+        network_state.server_address, network_state.reconnect_attempts, network_state.max_reconnect_attempts
+    );
+    network_events.write(NetworkEvent::reconnect_attempt(network_state.reconnect_attempts));
+
+    client.connect(
+        &runtime,
+        network_state.server_address.clone(),
+        NetworkMessage::resume(settings.player_name.clone(), session_id),
+    );
+}
+
+/// Mirrors `ConnectionStatus` into the `states::NetworkState` state.
+pub fn sync_network_state(
+    network_state: Res<NetworkState>,
+    current: Res<State<crate::core::states::NetworkState>>,
+    mut next: ResMut<NextState<crate::core::states::NetworkState>>,
+) {
+    use crate::core::states::NetworkState as Phase;
+
+    if !network_state.is_changed() {
+        return;
+    }
+
+    let phase = match network_state.connection_status {
+        ConnectionStatus::Disconnected => Phase::Disconnected,
+        ConnectionStatus::Connecting | ConnectionStatus::Reconnecting => Phase::Connecting,
+        ConnectionStatus::Connected => Phase::Connected,
+        ConnectionStatus::Authenticated => Phase::Authenticated,
+        ConnectionStatus::InGame => Phase::InGame,
+        ConnectionStatus::Error => Phase::Error,
+    };
+    if *current.get() != phase {
+        next.set(phase);
+    }
+}
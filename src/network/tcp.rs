@@ -0,0 +1,57 @@
+//! Raw TCP with length-delimited frames.
+
+use std::io;
+
+use tokio::{
+    io::{AsyncWriteExt, BufReader},
+    net::{
+        tcp::{OwnedReadHalf, OwnedWriteHalf},
+        TcpStream,
+    },
+};
+
+use crate::{
+    core::constants::NETWORK_BUFFER_SIZE,
+    network::{
+        framing,
+        transport::{FrameReader, FrameWriter, Transport},
+    },
+};
+
+pub struct TcpTransport(TcpStream);
+
+pub struct TcpReader(BufReader<OwnedReadHalf>);
+
+pub struct TcpWriter(OwnedWriteHalf);
+
+impl Transport for TcpTransport {
+    type Reader = TcpReader;
+    type Writer = TcpWriter;
+
+    async fn connect(address: &str) -> io::Result<Self> {
+        let stream = TcpStream::connect(address).await?;
+        stream.set_nodelay(true)?;
+        Ok(Self(stream))
+    }
+
+    fn split(self) -> (TcpReader, TcpWriter) {
+        let (reader, writer) = self.0.into_split();
+        (TcpReader(BufReader::with_capacity(NETWORK_BUFFER_SIZE, reader)), TcpWriter(writer))
+    }
+}
+
+impl FrameReader for TcpReader {
+    async fn read_frame(&mut self) -> io::Result<Option<Vec<u8>>> {
+        framing::read_frame(&mut self.0).await
+    }
+}
+
+impl FrameWriter for TcpWriter {
+    async fn write_frame(&mut self, payload: &[u8]) -> io::Result<()> {
+        framing::write_frame(&mut self.0, payload).await
+    }
+
+    async fn close(&mut self) -> io::Result<()> {
+        self.0.shutdown().await
+    }
+}
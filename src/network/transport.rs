@@ -0,0 +1,48 @@
+//! Transports move whole frames between the client and the server. The connection
+//! task is generic over them, so TCP and WebSocket carry identical messages.
+
+use std::{future::Future, io};
+
+pub trait Transport: Sized + Send + 'static {
+    type Reader: FrameReader;
+    type Writer: FrameWriter;
+
+    fn connect(address: &str) -> impl Future<Output = io::Result<Self>> + Send;
+
+    fn split(self) -> (Self::Reader, Self::Writer);
+}
+
+pub trait FrameReader: Send + 'static {
+    /// Next frame's payload, `Ok(None)` once the peer has closed the connection.
+    fn read_frame(&mut self) -> impl Future<Output = io::Result<Option<Vec<u8>>>> + Send;
+}
+
+pub trait FrameWriter: Send + 'static {
+    fn write_frame(&mut self, payload: &[u8]) -> impl Future<Output = io::Result<()>> + Send;
+
+    /// Politely ends the connection.
+    fn close(&mut self) -> impl Future<Output = io::Result<()>> + Send;
+}
+
+/// Server address with the transport picked from its scheme:
+/// `ws://` and `wss://` use WebSocket, `tcp://` or no scheme uses raw TCP.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Endpoint {
+    Tcp(String),
+    WebSocket(String),
+}
+
+impl Endpoint {
+    pub fn parse(address: &str) -> Self {
+        let address = address.trim();
+        let lowercase = address.to_ascii_lowercase();
+
+        if lowercase.starts_with("ws://") || lowercase.starts_with("wss://") {
+            Self::WebSocket(address.to_string())
+        } else if lowercase.starts_with("tcp://") {
+            Self::Tcp(address["tcp://".len()..].to_string())
+        } else {
+            Self::Tcp(address.to_string())
+        }
+    }
+}
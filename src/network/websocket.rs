@@ -0,0 +1,95 @@
+//! WebSocket (`ws://`, `wss://`). Each frame is one text message; the WebSocket
+//! layer already delimits messages, so no length prefix is added.
+
+use std::io;
+
+use futures_util::{
+    stream::{SplitSink, SplitStream},
+    SinkExt, StreamExt,
+};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    connect_async_with_config,
+    tungstenite::{protocol::WebSocketConfig, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+
+use crate::{
+    core::constants::MAX_FRAME_SIZE,
+    network::transport::{FrameReader, FrameWriter, Transport},
+};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+pub struct WebSocketTransport(Socket);
+
+pub struct WebSocketReader(SplitStream<Socket>);
+
+pub struct WebSocketWriter(SplitSink<Socket, Message>);
+
+fn frame_too_large(length: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("frame of {} bytes exceeds the {} byte limit", length, MAX_FRAME_SIZE),
+    )
+}
+
+impl Transport for WebSocketTransport {
+    type Reader = WebSocketReader;
+    type Writer = WebSocketWriter;
+
+    async fn connect(address: &str) -> io::Result<Self> {
+        // tungstenite would otherwise buffer messages up to 64 MiB before we get to check them
+        let config = WebSocketConfig::default()
+            .max_message_size(Some(MAX_FRAME_SIZE))
+            .max_frame_size(Some(MAX_FRAME_SIZE));
+        // Moves are tiny; don't let Nagle hold them back, as with plain TCP
+        let (socket, _response) = connect_async_with_config(address, Some(config), true)
+            .await
+            .map_err(io::Error::other)?;
+        Ok(Self(socket))
+    }
+
+    fn split(self) -> (WebSocketReader, WebSocketWriter) {
+        let (writer, reader) = self.0.split();
+        (WebSocketReader(reader), WebSocketWriter(writer))
+    }
+}
+
+impl FrameReader for WebSocketReader {
+    async fn read_frame(&mut self) -> io::Result<Option<Vec<u8>>> {
+        loop {
+            let payload = match self.0.next().await {
+                None | Some(Ok(Message::Close(_))) => return Ok(None),
+                Some(Ok(Message::Text(text))) => text.as_bytes().to_vec(),
+                Some(Ok(Message::Binary(data))) => data.to_vec(),
+                // Pings are answered by tungstenite itself
+                Some(Ok(_)) => continue,
+                Some(Err(error)) => return Err(io::Error::other(error)),
+            };
+
+            if payload.len() > MAX_FRAME_SIZE {
+                return Err(frame_too_large(payload.len()));
+            }
+            return Ok(Some(payload));
+        }
+    }
+}
+
+impl FrameWriter for WebSocketWriter {
+    async fn write_frame(&mut self, payload: &[u8]) -> io::Result<()> {
+        if payload.len() > MAX_FRAME_SIZE {
+            return Err(frame_too_large(payload.len()));
+        }
+
+        let message = match std::str::from_utf8(payload) {
+            Ok(text) => Message::text(text),
+            Err(_) => Message::binary(payload.to_vec()),
+        };
+        self.0.send(message).await.map_err(io::Error::other)
+    }
+
+    async fn close(&mut self) -> io::Result<()> {
+        self.0.close().await.map_err(io::Error::other)
+    }
+}
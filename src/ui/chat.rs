@@ -0,0 +1,477 @@
+//! Chat panel shown in the lobby and during online games: one tab per
+//! `ChatMessageType`, local system notices for joins, leaves, offers and results,
+//! per-player muting and a simple profanity filter.
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+
+use crate::{
+    core::{
+        constants::*,
+        events::*,
+        resources::{GameSettings, NetworkState},
+    },
+    ui::widgets::*,
+};
+
+const TABS: [ChatMessageType; 4] = [
+    ChatMessageType::Game,
+    ChatMessageType::Global,
+    ChatMessageType::System,
+    ChatMessageType::Private,
+];
+
+/// Whole words masked by the filter, also with common suffixes ("-s", "-ing", ...).
+const PROFANITY: [&str; 10] = [
+    "arse", "ass", "asshole", "bastard", "bitch", "crap", "damn", "dick", "fuck", "shit",
+];
+const PROFANITY_SUFFIXES: [&str; 7] = ["", "s", "es", "ed", "er", "ers", "ing"];
+
+/// Sender id the server uses for its own announcements.
+const SERVER_SENDER: &str = "server";
+
+#[derive(Debug, Clone)]
+pub struct ChatEntry {
+    pub message_type: ChatMessageType,
+    /// `None` for notices generated by the client itself.
+    pub sender_id: Option<String>,
+    pub text: String,
+    /// Unix time in seconds.
+    pub timestamp: u64,
+}
+
+#[derive(Resource, Debug)]
+pub struct Chat {
+    pub entries: VecDeque<ChatEntry>,
+    pub active_tab: ChatMessageType,
+    unread: [u32; TABS.len()],
+    /// Player id -> display name, learned from player lists and game joins.
+    pub names: HashMap<String, String>,
+    pub muted: HashSet<String>,
+    pub filter_profanity: bool,
+}
+
+impl Default for Chat {
+    fn default() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            active_tab: ChatMessageType::Game,
+            unread: [0; TABS.len()],
+            names: HashMap::new(),
+            muted: HashSet::new(),
+            filter_profanity: true,
+        }
+    }
+}
+
+impl Chat {
+    pub fn push(&mut self, entry: ChatEntry) {
+        let muted = entry.sender_id.as_ref().is_some_and(|sender| self.muted.contains(sender));
+        if entry.message_type != self.active_tab && !muted {
+            self.unread[tab_index(&entry.message_type)] += 1;
+        }
+
+        self.entries.push_back(entry);
+        while self.entries.len() > MAX_CHAT_HISTORY {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn system(&mut self, text: impl Into<String>) {
+        self.push(ChatEntry {
+            message_type: ChatMessageType::System,
+            sender_id: None,
+            text: text.into(),
+            timestamp: unix_time(),
+        });
+    }
+
+    pub fn select_tab(&mut self, tab: ChatMessageType) {
+        self.unread[tab_index(&tab)] = 0;
+        self.active_tab = tab;
+    }
+
+    pub fn unread(&self, tab: &ChatMessageType) -> u32 {
+        self.unread[tab_index(tab)]
+    }
+
+    pub fn display_name(&self, player_id: &str) -> String {
+        match self.names.get(player_id) {
+            Some(name) => name.clone(),
+            None if player_id == SERVER_SENDER => "Server".to_string(),
+            None => player_id.to_string(),
+        }
+    }
+
+    pub fn toggle_mute(&mut self, player_id: &str) {
+        if !self.muted.remove(player_id) {
+            self.muted.insert(player_id.to_string());
+        }
+    }
+
+    /// Forgets the session's messages; mutes and the filter setting are kept.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.unread = [0; TABS.len()];
+        self.names.clear();
+    }
+
+    fn learn_name(&mut self, player: &PlayerInfo) {
+        self.names.insert(player.id.clone(), player.name.clone());
+    }
+}
+
+fn tab_index(message_type: &ChatMessageType) -> usize {
+    TABS.iter().position(|tab| tab == message_type).unwrap_or(0)
+}
+
+fn tab_name(message_type: &ChatMessageType) -> &'static str {
+    match message_type {
+        ChatMessageType::Game => "Game",
+        ChatMessageType::Global => "Global",
+        ChatMessageType::System => "System",
+        ChatMessageType::Private => "Private",
+    }
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs())
+}
+
+/// `HH:MM` in UTC.
+pub fn format_timestamp(timestamp: u64) -> String {
+    format!("{:02}:{:02}", timestamp / 3600 % 24, timestamp / 60 % 60)
+}
+
+fn is_profane(word: &str) -> bool {
+    let word = word.to_lowercase();
+    PROFANITY.iter().any(|bad| {
+        word.strip_prefix(bad)
+            .is_some_and(|suffix| PROFANITY_SUFFIXES.contains(&suffix))
+    })
+}
+
+/// Replaces the letters of profane words with `*`, leaving everything else untouched.
+pub fn filter_profanity(text: &str) -> String {
+    let mut filtered = String::with_capacity(text.len());
+    let mut word = String::new();
+
+    let flush = |word: &mut String, filtered: &mut String| {
+        if is_profane(word) {
+            filtered.extend(word.chars().map(|_| '*'));
+        } else {
+            filtered.push_str(word);
+        }
+        word.clear();
+    };
+
+    for character in text.chars() {
+        if character.is_alphanumeric() {
+            word.push(character);
+        } else {
+            flush(&mut word, &mut filtered);
+            filtered.push(character);
+        }
+    }
+    flush(&mut word, &mut filtered);
+    filtered
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub enum ChatButton {
+    Tab(ChatMessageType),
+    ToggleMute(String),
+    ToggleFilter,
+}
+
+#[derive(Component)]
+pub struct ChatRoot;
+
+#[derive(Component)]
+pub struct ChatLog;
+
+#[derive(Component)]
+pub struct ChatInput;
+
+pub fn setup_chat_panel(mut commands: Commands, mut chat: ResMut<Chat>) {
+    // Draw the log once the panel exists
+    chat.set_changed();
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(UI_MARGIN_LARGE),
+                bottom: Val::Px(UI_MARGIN_LARGE),
+                width: Val::Px(CHAT_PANEL_WIDTH),
+                height: Val::Px(CHAT_PANEL_HEIGHT),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(UI_MARGIN_MEDIUM)),
+                row_gap: Val::Px(UI_MARGIN_SMALL),
+                ..default()
+            },
+            BackgroundColor(UI_PANEL_COLOR),
+            BorderRadius::all(Val::Px(UI_PANEL_BORDER_RADIUS)),
+            GlobalZIndex(1),
+            ChatRoot,
+        ))
+        .with_children(|panel| {
+            panel
+                .spawn(Node {
+                    column_gap: Val::Px(UI_MARGIN_SMALL),
+                    ..default()
+                })
+                .with_children(|tabs| {
+                    for tab in TABS {
+                        spawn_small_button(tabs, tab_name(&tab), ChatButton::Tab(tab));
+                    }
+                    tabs.spawn(Node {
+                        flex_grow: 1.0,
+                        ..default()
+                    });
+                    spawn_small_button(tabs, "Filter", ChatButton::ToggleFilter);
+                });
+
+            panel.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::FlexEnd,
+                    flex_grow: 1.0,
+                    row_gap: Val::Px(UI_MARGIN_SMALL / 2.0),
+                    overflow: Overflow::clip(),
+                    ..default()
+                },
+                ChatLog,
+            ));
+
+            spawn_text_input(
+                panel,
+                TextInput {
+                    placeholder: "Click to chat...".to_string(),
+                    max_length: MAX_CHAT_MESSAGE_LENGTH,
+                    ..default()
+                },
+                Val::Percent(100.0),
+                ChatInput,
+            );
+        });
+}
+
+pub fn cleanup_chat_panel(mut commands: Commands, roots: Query<Entity, With<ChatRoot>>) {
+    for entity in roots.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+pub fn reset_chat(mut chat: ResMut<Chat>) {
+    chat.clear();
+}
+
+pub fn collect_chat_messages(
+    mut responses: EventReader<NetworkResponseEvent>,
+    mut network_events: EventReader<NetworkEvent>,
+    mut chat: ResMut<Chat>,
+    network_state: Res<NetworkState>,
+    settings: Res<GameSettings>,
+) {
+    if let Some(player_id) = &network_state.player_id
+        && chat.names.get(player_id) != Some(&settings.player_name)
+    {
+        chat.names.insert(player_id.clone(), settings.player_name.clone());
+    }
+
+    for event in responses.read() {
+        match &event.response {
+            ServerResponse::ChatMessage {
+                from_player,
+                message,
+                message_type,
+                timestamp,
+            } => chat.push(ChatEntry {
+                message_type: message_type.clone(),
+                sender_id: Some(from_player.clone()),
+                text: message.clone(),
+                timestamp: *timestamp,
+            }),
+            ServerResponse::PlayerList { players } => {
+                for player in players {
+                    chat.learn_name(player);
+                }
+            }
+            ServerResponse::GameCreated { game_id, .. } => {
+                chat.system(format!("Created game {}, waiting for an opponent", game_id));
+            }
+            ServerResponse::GameJoined {
+                game_id,
+                player_color,
+                opponent_info,
+                game_state,
+            } => {
+                for player in [&game_state.white_player, &game_state.black_player, opponent_info]
+                    .into_iter()
+                    .flatten()
+                {
+                    chat.learn_name(player);
+                }
+                match opponent_info {
+                    Some(opponent) => chat.system(format!("Playing {:?} against {}", player_color, opponent.name)),
+                    None => chat.system(format!("Joined game {} as {:?}", game_id, player_color)),
+                }
+            }
+            ServerResponse::DrawOffered { from_player } => {
+                let name = chat.display_name(from_player);
+                chat.system(format!("{} offers a draw", name));
+            }
+            ServerResponse::DrawResponse { accepted } => {
+                chat.system(if *accepted { "Draw offer accepted" } else { "Draw offer declined" });
+            }
+            ServerResponse::UndoOffered { from_player, moves_count } => {
+                let name = chat.display_name(from_player);
+                chat.system(format!("{} asks to take back {} move(s)", name, moves_count));
+            }
+            ServerResponse::GameOver { result } => chat.system(format!("Game over: {}", result.describe())),
+            _ => {}
+        }
+    }
+
+    for event in network_events.read() {
+        if let NetworkEventType::SessionMessage {
+            message: NetworkMessage::PlayerLeft { player_id, reason },
+        } = &event.event_type
+        {
+            let name = chat.display_name(player_id);
+            chat.system(format!("{} left ({})", name, reason));
+        }
+    }
+}
+
+pub fn handle_chat_buttons(buttons: Query<(&Interaction, &ChatButton), Changed<Interaction>>, mut chat: ResMut<Chat>) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            ChatButton::Tab(tab) => chat.select_tab(tab.clone()),
+            ChatButton::ToggleMute(player_id) => chat.toggle_mute(player_id),
+            ChatButton::ToggleFilter => chat.filter_profanity = !chat.filter_profanity,
+        }
+    }
+}
+
+pub fn send_chat_message(
+    mut submitted: EventReader<TextSubmitted>,
+    mut inputs: Query<&mut TextInput, With<ChatInput>>,
+    mut chat: ResMut<Chat>,
+    mut requests: EventWriter<SendClientRequestEvent>,
+) {
+    for event in submitted.read() {
+        let Ok(mut input) = inputs.get_mut(event.entity) else {
+            continue;
+        };
+        let message = event.value.trim();
+        if message.is_empty() {
+            continue;
+        }
+
+        // The server echoes our own messages back, so nothing is added locally
+        if chat.active_tab == ChatMessageType::System {
+            chat.system("System messages can't be sent; switch to another tab");
+            continue;
+        }
+        requests.write(SendClientRequestEvent {
+            request: ClientRequest::SendChatMessage {
+                message: message.to_string(),
+                message_type: chat.active_tab.clone(),
+            },
+        });
+        input.value.clear();
+    }
+}
+
+pub fn update_chat_panel(
+    mut commands: Commands,
+    chat: Res<Chat>,
+    network_state: Res<NetworkState>,
+    logs: Query<Entity, With<ChatLog>>,
+    buttons: Query<(&ChatButton, &Children)>,
+    mut labels: Query<(&mut Text, &mut TextColor)>,
+) {
+    if !chat.is_changed() {
+        return;
+    }
+
+    for (button, children) in buttons.iter() {
+        let (label, color) = match button {
+            ChatButton::Tab(tab) => {
+                let unread = chat.unread(tab);
+                let label = match unread {
+                    0 => tab_name(tab).to_string(),
+                    _ => format!("{} ({})", tab_name(tab), unread),
+                };
+                (label, if *tab == chat.active_tab { UI_ACCENT_COLOR } else { UI_TEXT_COLOR })
+            }
+            ChatButton::ToggleFilter if chat.filter_profanity => ("Filter: on".to_string(), UI_TEXT_COLOR),
+            ChatButton::ToggleFilter => ("Filter: off".to_string(), UI_TEXT_DISABLED_COLOR),
+            ChatButton::ToggleMute(_) => continue,
+        };
+        for child in children.iter() {
+            if let Ok((mut text, mut text_color)) = labels.get_mut(child) {
+                text.0 = label.clone();
+                text_color.0 = color;
+            }
+        }
+    }
+
+    let Ok(log) = logs.single() else {
+        return;
+    };
+    let visible: Vec<_> = chat
+        .entries
+        .iter()
+        .filter(|entry| entry.message_type == chat.active_tab)
+        .collect();
+    let skip = visible.len().saturating_sub(CHAT_VISIBLE_MESSAGES);
+
+    commands.entity(log).despawn_related::<Children>().with_children(|log| {
+        for entry in visible.into_iter().skip(skip) {
+            let time = format_timestamp(entry.timestamp);
+            let sender = entry
+                .sender_id
+                .as_ref()
+                .filter(|sender| entry.message_type != ChatMessageType::System || sender.as_str() != SERVER_SENDER);
+            let muted = sender.is_some_and(|sender| chat.muted.contains(sender));
+
+            let (line, color) = match sender {
+                _ if muted => (
+                    format!("[{}] {}: (muted)", time, chat.display_name(sender.unwrap())),
+                    UI_TEXT_DISABLED_COLOR,
+                ),
+                Some(sender) => {
+                    let text = if chat.filter_profanity { filter_profanity(&entry.text) } else { entry.text.clone() };
+                    (format!("[{}] {}: {}", time, chat.display_name(sender), text), UI_TEXT_COLOR)
+                }
+                None => (format!("[{}] * {}", time, entry.text), UI_WARNING_COLOR),
+            };
+
+            log.spawn(Node {
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(UI_MARGIN_SMALL),
+                ..default()
+            })
+            .with_children(|row| {
+                spawn_label(row, line, UI_FONT_SIZE_SMALL, color);
+
+                let Some(sender) = sender.filter(|sender| Some(*sender) != network_state.player_id.as_ref()) else {
+                    return;
+                };
+                let action = if muted { "Unmute" } else { "Mute" };
+                spawn_small_button(row, action, ChatButton::ToggleMute(sender.clone()));
+            });
+        }
+    });
+}
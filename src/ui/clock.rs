@@ -0,0 +1,95 @@
+//! Both players' clocks in the top-right corner, with low-time and flag colours.
+
+use bevy::prelude::*;
+
+use crate::{core::constants::*, game::clock::ChessClock, PieceColor};
+
+#[derive(Component)]
+pub struct ClockPanel;
+
+#[derive(Component)]
+pub struct ClockText(pub PieceColor);
+
+/// `m:ss`, or `s.t` in the last ten seconds.
+pub fn format_clock(seconds: f32) -> String {
+    let seconds = seconds.max(0.0);
+    if seconds < 10.0 {
+        format!("{:.1}", seconds)
+    } else {
+        let whole = seconds.ceil() as u32;
+        format!("{}:{:02}", whole / 60, whole % 60)
+    }
+}
+
+pub fn setup_clock_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(UI_MARGIN_LARGE),
+                right: Val::Px(UI_MARGIN_LARGE),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(UI_MARGIN_MEDIUM)),
+                row_gap: Val::Px(UI_MARGIN_SMALL),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(UI_PANEL_COLOR),
+            BorderRadius::all(Val::Px(UI_PANEL_BORDER_RADIUS)),
+            ClockPanel,
+        ))
+        .with_children(|panel| {
+            // Black on top, as seen from White's side of the board
+            for color in [PieceColor::Black, PieceColor::White] {
+                panel.spawn((
+                    Text::new(""),
+                    TextFont {
+                        font_size: UI_FONT_SIZE_LARGE,
+                        ..default()
+                    },
+                    TextColor(UI_TEXT_COLOR),
+                    ClockText(color),
+                ));
+            }
+        });
+}
+
+pub fn cleanup_clock_panel(mut commands: Commands, panels: Query<Entity, With<ClockPanel>>) {
+    for entity in panels.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+pub fn update_clock_panel(
+    clock: Res<ChessClock>,
+    mut panels: Query<&mut Node, With<ClockPanel>>,
+    mut texts: Query<(&ClockText, &mut Text, &mut TextColor)>,
+) {
+    if !clock.is_changed() {
+        return;
+    }
+
+    let display = if clock.is_enabled() { Display::Flex } else { Display::None };
+    for mut node in panels.iter_mut() {
+        if node.display != display {
+            node.display = display;
+        }
+    }
+
+    for (ClockText(color), mut text, mut text_color) in texts.iter_mut() {
+        let name = match color {
+            PieceColor::White => "White",
+            PieceColor::Black => "Black",
+        };
+        text.0 = format!("{}  {}", name, format_clock(clock.remaining(*color)));
+        text_color.0 = if clock.flagged == Some(*color) {
+            UI_ERROR_COLOR
+        } else if clock.is_low_on_time(*color) {
+            UI_WARNING_COLOR
+        } else if clock.running == Some(*color) {
+            UI_ACCENT_COLOR
+        } else {
+            UI_TEXT_COLOR
+        };
+    }
+}
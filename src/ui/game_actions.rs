@@ -0,0 +1,262 @@
+//! In-game action bar (draw, undo, resign) and the confirm dialog that answers
+//! the opponent's offers or double-checks a resignation.
+
+use bevy::prelude::*;
+
+use crate::{
+    core::{
+        constants::*,
+        events::{GameAction, GameActionEvent},
+        resources::UIState,
+    },
+    network::{IncomingOffer, Negotiation},
+    ui::{chat::Chat, widgets::*},
+};
+
+/// Local prompts that aren't offers from the opponent.
+#[derive(Resource, Debug, Default)]
+pub struct ConfirmPrompt {
+    pub resign: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Confirmation {
+    Offer(IncomingOffer),
+    Resign,
+}
+
+#[derive(Component)]
+pub struct GameActionBar;
+
+#[derive(Component)]
+pub struct GameActionStatusText;
+
+#[derive(Component, Debug, Clone)]
+pub struct ActionButton(pub GameAction);
+
+#[derive(Component, Debug)]
+pub struct ConfirmDialog(pub Confirmation);
+
+#[derive(Component, Debug, Clone)]
+pub enum DialogButton {
+    Send(GameAction),
+    Cancel,
+}
+
+pub fn setup_game_actions(mut commands: Commands, mut ui_state: ResMut<UIState>) {
+    ui_state.show_screen(crate::core::states::UIState::InGameUI);
+    ui_state.set_status(String::new());
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(UI_MARGIN_LARGE),
+                bottom: Val::Px(UI_MARGIN_LARGE),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(UI_MARGIN_MEDIUM)),
+                row_gap: Val::Px(UI_MARGIN_SMALL),
+                ..default()
+            },
+            BackgroundColor(UI_PANEL_COLOR),
+            BorderRadius::all(Val::Px(UI_PANEL_BORDER_RADIUS)),
+            GameActionBar,
+        ))
+        .with_children(|bar| {
+            bar.spawn(Node {
+                column_gap: Val::Px(UI_MARGIN_MEDIUM),
+                ..default()
+            })
+            .with_children(|buttons| {
+                spawn_button(buttons, "Offer draw", ActionButton(GameAction::OfferDraw));
+                spawn_button(buttons, "Undo", ActionButton(GameAction::RequestUndo));
+                spawn_button(buttons, "Resign", ActionButton(GameAction::Resign));
+            });
+            bar.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: UI_FONT_SIZE_SMALL,
+                    ..default()
+                },
+                TextColor(UI_TEXT_COLOR),
+                GameActionStatusText,
+            ));
+        });
+}
+
+type GameActionRoot = Or<(With<GameActionBar>, With<ConfirmDialog>)>;
+
+pub fn cleanup_game_actions(
+    mut commands: Commands,
+    roots: Query<Entity, GameActionRoot>,
+    mut prompt: ResMut<ConfirmPrompt>,
+    mut ui_state: ResMut<UIState>,
+) {
+    for entity in roots.iter() {
+        commands.entity(entity).despawn();
+    }
+    prompt.resign = false;
+    ui_state.hide_screen(crate::core::states::UIState::InGameUI);
+    ui_state.hide_screen(crate::core::states::UIState::ConfirmDialog);
+    ui_state.modal_open = false;
+}
+
+pub fn handle_action_buttons(
+    buttons: Query<(&Interaction, &ActionButton), Changed<Interaction>>,
+    mut prompt: ResMut<ConfirmPrompt>,
+    mut actions: EventWriter<GameActionEvent>,
+) {
+    for (interaction, ActionButton(action)) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match action {
+            // Resigning goes through the confirm dialog first
+            GameAction::Resign => prompt.resign = true,
+            action => {
+                actions.write(GameActionEvent { action: action.clone() });
+            }
+        }
+    }
+}
+
+pub fn handle_dialog_buttons(
+    buttons: Query<(&Interaction, &DialogButton), Changed<Interaction>>,
+    mut prompt: ResMut<ConfirmPrompt>,
+    mut actions: EventWriter<GameActionEvent>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if let DialogButton::Send(action) = button {
+            actions.write(GameActionEvent { action: action.clone() });
+        }
+        // Offers close once the answer goes out; the resign prompt closes either way
+        prompt.resign = false;
+    }
+}
+
+/// Shows the dialog for the opponent's offer, or our resign prompt, and closes it once answered.
+pub fn update_confirm_dialog(
+    mut commands: Commands,
+    negotiation: Res<Negotiation>,
+    prompt: Res<ConfirmPrompt>,
+    chat: Res<Chat>,
+    dialogs: Query<(Entity, &ConfirmDialog)>,
+    mut ui_state: ResMut<UIState>,
+) {
+    if !negotiation.is_changed() && !prompt.is_changed() {
+        return;
+    }
+
+    let wanted = match (&negotiation.incoming, prompt.resign) {
+        (Some(offer), _) => Some(Confirmation::Offer(offer.clone())),
+        (None, true) => Some(Confirmation::Resign),
+        (None, false) => None,
+    };
+    if dialogs.iter().next().map(|(_, dialog)| &dialog.0) == wanted.as_ref() {
+        return;
+    }
+
+    for (entity, _) in dialogs.iter() {
+        commands.entity(entity).despawn();
+    }
+    let Some(confirmation) = wanted else {
+        ui_state.hide_screen(crate::core::states::UIState::ConfirmDialog);
+        ui_state.modal_open = false;
+        return;
+    };
+    ui_state.show_screen(crate::core::states::UIState::ConfirmDialog);
+    ui_state.modal_open = true;
+
+    let (question, accept, decline) = match &confirmation {
+        Confirmation::Offer(IncomingOffer::Draw { from_player }) => (
+            format!("{} offers a draw", chat.display_name(from_player)),
+            ("Accept", DialogButton::Send(GameAction::AcceptDraw)),
+            ("Decline", DialogButton::Send(GameAction::DeclineDraw)),
+        ),
+        Confirmation::Offer(IncomingOffer::Undo { from_player, moves_count }) => (
+            format!("{} asks to take back {} move(s)", chat.display_name(from_player), moves_count),
+            ("Accept", DialogButton::Send(GameAction::AccpetUndo)),
+            ("Decline", DialogButton::Send(GameAction::DeclineUndo)),
+        ),
+        Confirmation::Resign => (
+            "Resign this game?".to_string(),
+            ("Resign", DialogButton::Send(GameAction::Resign)),
+            ("Cancel", DialogButton::Cancel),
+        ),
+    };
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.4)),
+            GlobalZIndex(2),
+            ConfirmDialog(confirmation),
+        ))
+        .with_children(|overlay| {
+            overlay
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(UI_MARGIN_LARGE * 2.0)),
+                        row_gap: Val::Px(UI_MARGIN_LARGE),
+                        ..default()
+                    },
+                    BackgroundColor(UI_PANEL_COLOR),
+                    BorderRadius::all(Val::Px(UI_PANEL_BORDER_RADIUS)),
+                ))
+                .with_children(|dialog| {
+                    spawn_label(dialog, question, UI_FONT_SIZE_LARGE, UI_TEXT_COLOR);
+                    dialog
+                        .spawn(Node {
+                            column_gap: Val::Px(UI_MARGIN_LARGE),
+                            ..default()
+                        })
+                        .with_children(|buttons| {
+                            spawn_button(buttons, accept.0, accept.1);
+                            spawn_button(buttons, decline.0, decline.1);
+                        });
+                });
+        });
+}
+
+pub fn update_game_action_labels(
+    negotiation: Res<Negotiation>,
+    ui_state: Res<UIState>,
+    buttons: Query<(&ActionButton, &Children)>,
+    mut texts: Query<&mut Text, Without<GameActionStatusText>>,
+    mut status_text: Query<&mut Text, With<GameActionStatusText>>,
+) {
+    if negotiation.is_changed() {
+        for (ActionButton(action), children) in buttons.iter() {
+            let label = match action {
+                GameAction::OfferDraw if negotiation.awaiting_draw_response => "Draw offered",
+                GameAction::OfferDraw => "Offer draw",
+                GameAction::RequestUndo if negotiation.awaiting_undo_response => "Undo requested",
+                GameAction::RequestUndo => "Undo",
+                _ => continue,
+            };
+            for child in children.iter() {
+                if let Ok(mut text) = texts.get_mut(child) {
+                    text.0 = label.to_string();
+                }
+            }
+        }
+    }
+
+    if ui_state.is_changed()
+        && let Ok(mut text) = status_text.single_mut()
+    {
+        text.0 = ui_state.status_message.clone();
+    }
+}
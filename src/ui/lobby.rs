@@ -0,0 +1,519 @@
+//! Lobby screen: open games and online players, refreshed periodically, plus
+//! creating a game with a time control and joining one by ID.
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    core::{
+        constants::*,
+        events::*,
+        resources::{GameSettings, UIState},
+        states::GameState,
+    },
+    ui::widgets::*,
+};
+
+/// Offered time controls as (name, minutes, increment seconds, delay); one past the end is unlimited.
+const TIME_CONTROLS: [(&str, u32, u32, Option<ClockDelay>); 7] = [
+    ("Bullet", 1, 0, None),
+    ("Blitz", 3, 2, None),
+    ("Blitz", 5, 3, None),
+    ("Blitz", 5, 0, Some(ClockDelay::Simple(3))),
+    ("Rapid", 10, 5, None),
+    ("Rapid", 15, 0, Some(ClockDelay::Bronstein(10))),
+    ("Classical", 30, 0, None),
+];
+
+#[derive(Resource, Debug)]
+pub struct Lobby {
+    pub games: Vec<GameInfo>,
+    pub players: Vec<PlayerInfo>,
+    /// Index into `TIME_CONTROLS`.
+    pub time_control: usize,
+    pub is_private: bool,
+    pub status: Option<(String, Color)>,
+    pub last_refresh: Option<f32>,
+}
+
+impl Default for Lobby {
+    fn default() -> Self {
+        Self {
+            games: Vec::new(),
+            players: Vec::new(),
+            time_control: 2,
+            is_private: false,
+            status: None,
+            last_refresh: None,
+        }
+    }
+}
+
+impl Lobby {
+    pub fn selected_time_control(&self) -> Option<TimeControl> {
+        TIME_CONTROLS.get(self.time_control).map(|(name, minutes, increment, delay)| TimeControl {
+            initial_time_seconds: minutes * 60,
+            increment_seconds: *increment,
+            name: format!("{} {}+{}{}", name, minutes, increment, delay_label(*delay)),
+            delay: *delay,
+        })
+    }
+
+    fn set_status(&mut self, message: impl Into<String>, color: Color) {
+        self.status = Some((message.into(), color));
+    }
+}
+
+pub fn time_control_label(time_control: Option<&TimeControl>) -> String {
+    match time_control {
+        None => "Unlimited".to_string(),
+        Some(time_control) if !time_control.name.is_empty() => time_control.name.clone(),
+        Some(time_control) => format!(
+            "{}+{}{}",
+            time_control.initial_time_seconds / 60,
+            time_control.increment_seconds,
+            delay_label(time_control.delay)
+        ),
+    }
+}
+
+/// ` d3` for a 3 s simple delay, ` b10` for a 10 s Bronstein delay.
+fn delay_label(delay: Option<ClockDelay>) -> String {
+    match delay {
+        None => String::new(),
+        Some(ClockDelay::Simple(seconds)) => format!(" d{}", seconds),
+        Some(ClockDelay::Bronstein(seconds)) => format!(" b{}", seconds),
+    }
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub enum LobbyButton {
+    Refresh,
+    Leave,
+    NextTimeControl,
+    TogglePrivate,
+    Create,
+    Join,
+    JoinListed(String),
+}
+
+#[derive(Component)]
+pub struct LobbyRoot;
+
+#[derive(Component)]
+pub struct GameListContainer;
+
+#[derive(Component)]
+pub struct PlayerListContainer;
+
+#[derive(Component)]
+pub struct LobbyStatusText;
+
+#[derive(Component)]
+pub struct JoinGameIdInput;
+
+#[derive(Component)]
+pub struct JoinPasswordInput;
+
+#[derive(Component)]
+pub struct CreatePasswordInput;
+
+type GameIdInputFilter = (With<JoinGameIdInput>, Without<JoinPasswordInput>, Without<CreatePasswordInput>);
+
+/// The lobby's text fields.
+#[derive(SystemParam)]
+pub struct LobbyInputs<'w, 's> {
+    game_id: Query<'w, 's, &'static mut TextInput, GameIdInputFilter>,
+    join_password: Query<'w, 's, &'static TextInput, With<JoinPasswordInput>>,
+    create_password: Query<'w, 's, &'static TextInput, With<CreatePasswordInput>>,
+}
+
+impl LobbyInputs<'_, '_> {
+    fn is_join_field(&self, entity: Entity) -> bool {
+        self.game_id.contains(entity) || self.join_password.contains(entity)
+    }
+}
+
+/// Typed password, or `None` if the field is empty.
+fn password_of(input: &Query<&TextInput, impl bevy::ecs::query::QueryFilter>) -> Option<String> {
+    input
+        .single()
+        .ok()
+        .map(|input| input.value.clone())
+        .filter(|password| !password.is_empty())
+}
+
+fn panel(flex_grow: f32) -> impl Bundle {
+    (
+        Node {
+            flex_direction: FlexDirection::Column,
+            flex_grow,
+            flex_basis: Val::Px(0.0),
+            padding: UiRect::all(Val::Px(UI_MARGIN_LARGE)),
+            row_gap: Val::Px(UI_MARGIN_SMALL),
+            overflow: Overflow::clip(),
+            ..default()
+        },
+        BackgroundColor(UI_PANEL_COLOR),
+        BorderRadius::all(Val::Px(UI_PANEL_BORDER_RADIUS)),
+    )
+}
+
+fn row() -> Node {
+    Node {
+        flex_direction: FlexDirection::Row,
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(UI_MARGIN_MEDIUM),
+        ..default()
+    }
+}
+
+pub fn setup_lobby(
+    mut commands: Commands,
+    mut lobby: ResMut<Lobby>,
+    mut ui_state: ResMut<UIState>,
+    settings: Res<GameSettings>,
+) {
+    lobby.last_refresh = None;
+    lobby.status = None;
+    ui_state.show_screen(crate::core::states::UIState::GameLobby);
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(UI_MARGIN_LARGE)),
+                row_gap: Val::Px(UI_MARGIN_LARGE),
+                ..default()
+            },
+            BackgroundColor(UI_BACKGROUND_COLOR),
+            LobbyRoot,
+        ))
+        .with_children(|root| {
+            root.spawn(row()).with_children(|header| {
+                spawn_label(header, "Lobby", UI_FONT_SIZE_TITLE, UI_TEXT_COLOR);
+                header.spawn(Node {
+                    flex_grow: 1.0,
+                    ..default()
+                });
+                spawn_label(header, settings.player_name.clone(), UI_FONT_SIZE_MEDIUM, UI_ACCENT_COLOR);
+                spawn_button(header, "Refresh", LobbyButton::Refresh);
+                spawn_button(header, "Leave", LobbyButton::Leave);
+            });
+
+            root.spawn(Node {
+                flex_grow: 1.0,
+                column_gap: Val::Px(UI_MARGIN_LARGE),
+                ..default()
+            })
+            .with_children(|body| {
+                body.spawn(panel(2.0)).with_children(|games| {
+                    spawn_label(games, "Open games", UI_FONT_SIZE_LARGE, UI_TEXT_COLOR);
+                    games.spawn((
+                        Node {
+                            flex_direction: FlexDirection::Column,
+                            row_gap: Val::Px(UI_MARGIN_SMALL),
+                            ..default()
+                        },
+                        GameListContainer,
+                    ));
+                });
+                body.spawn(panel(1.0)).with_children(|players| {
+                    spawn_label(players, "Players online", UI_FONT_SIZE_LARGE, UI_TEXT_COLOR);
+                    players.spawn((
+                        Node {
+                            flex_direction: FlexDirection::Column,
+                            row_gap: Val::Px(UI_MARGIN_SMALL),
+                            ..default()
+                        },
+                        PlayerListContainer,
+                    ));
+                });
+            });
+
+            root.spawn(row()).with_children(|create| {
+                spawn_label(create, "New game:", UI_FONT_SIZE_MEDIUM, UI_TEXT_COLOR);
+                spawn_button(create, time_control_label(lobby.selected_time_control().as_ref()), LobbyButton::NextTimeControl);
+                spawn_button(create, "Public", LobbyButton::TogglePrivate);
+                spawn_text_input(
+                    create,
+                    TextInput {
+                        placeholder: "Password (optional)".to_string(),
+                        max_length: MAX_PASSWORD_LENGTH,
+                        masked: true,
+                        ..default()
+                    },
+                    Val::Px(200.0),
+                    CreatePasswordInput,
+                );
+                spawn_button(create, "Create", LobbyButton::Create);
+            });
+
+            root.spawn(row()).with_children(|join| {
+                spawn_label(join, "Join by ID:", UI_FONT_SIZE_MEDIUM, UI_TEXT_COLOR);
+                spawn_text_input(
+                    join,
+                    TextInput {
+                        placeholder: "Game ID".to_string(),
+                        max_length: MAX_GAME_ID_LENGTH,
+                        ..default()
+                    },
+                    Val::Px(240.0),
+                    JoinGameIdInput,
+                );
+                spawn_text_input(
+                    join,
+                    TextInput {
+                        placeholder: "Password (optional)".to_string(),
+                        max_length: MAX_PASSWORD_LENGTH,
+                        masked: true,
+                        ..default()
+                    },
+                    Val::Px(200.0),
+                    JoinPasswordInput,
+                );
+                spawn_button(join, "Join", LobbyButton::Join);
+            });
+
+            root.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: UI_FONT_SIZE_SMALL,
+                    ..default()
+                },
+                TextColor(UI_TEXT_COLOR),
+                LobbyStatusText,
+            ));
+        });
+}
+
+pub fn cleanup_lobby(
+    mut commands: Commands,
+    roots: Query<Entity, With<LobbyRoot>>,
+    mut ui_state: ResMut<UIState>,
+) {
+    for entity in roots.iter() {
+        commands.entity(entity).despawn();
+    }
+    ui_state.hide_screen(crate::core::states::UIState::GameLobby);
+}
+
+/// Asks for fresh lists on entering the lobby and every `LOBBY_REFRESH_INTERVAL_SECONDS`.
+pub fn refresh_lobby(
+    mut lobby: ResMut<Lobby>,
+    mut requests: EventWriter<SendClientRequestEvent>,
+    time: Res<Time<Real>>,
+) {
+    let now = time.elapsed_secs();
+    if lobby
+        .last_refresh
+        .is_some_and(|last_refresh| now - last_refresh < LOBBY_REFRESH_INTERVAL_SECONDS)
+    {
+        return;
+    }
+
+    lobby.last_refresh = Some(now);
+    requests.write(SendClientRequestEvent { request: ClientRequest::GetGameList });
+    requests.write(SendClientRequestEvent { request: ClientRequest::GetPlayerList });
+}
+
+pub fn handle_lobby_buttons(
+    buttons: Query<(&Interaction, &LobbyButton), Changed<Interaction>>,
+    mut lobby: ResMut<Lobby>,
+    mut inputs: LobbyInputs,
+    mut requests: EventWriter<SendClientRequestEvent>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            LobbyButton::Refresh => lobby.last_refresh = None,
+            LobbyButton::Leave => next_state.set(GameState::MainMenu),
+            LobbyButton::NextTimeControl => lobby.time_control = (lobby.time_control + 1) % (TIME_CONTROLS.len() + 1),
+            LobbyButton::TogglePrivate => lobby.is_private = !lobby.is_private,
+            LobbyButton::Create => {
+                requests.write(SendClientRequestEvent {
+                    request: ClientRequest::CreateGame {
+                        time_control: lobby.selected_time_control(),
+                        is_private: lobby.is_private,
+                        password: password_of(&inputs.create_password),
+                    },
+                });
+                lobby.set_status("Creating game...", UI_TEXT_COLOR);
+            }
+            LobbyButton::Join => request_join(None, &mut lobby, &mut inputs, &mut requests),
+            LobbyButton::JoinListed(game_id) => request_join(Some(game_id.clone()), &mut lobby, &mut inputs, &mut requests),
+        }
+    }
+}
+
+/// Enter in the join fields joins the game.
+pub fn submit_join_on_enter(
+    mut submitted: EventReader<TextSubmitted>,
+    mut lobby: ResMut<Lobby>,
+    mut inputs: LobbyInputs,
+    mut requests: EventWriter<SendClientRequestEvent>,
+) {
+    if submitted.read().filter(|event| inputs.is_join_field(event.entity)).count() > 0 {
+        request_join(None, &mut lobby, &mut inputs, &mut requests);
+    }
+}
+
+/// Joins `listed`, or the game whose ID is typed in, with the typed password.
+fn request_join(
+    listed: Option<String>,
+    lobby: &mut Lobby,
+    inputs: &mut LobbyInputs,
+    requests: &mut EventWriter<SendClientRequestEvent>,
+) {
+    let game_id = listed.unwrap_or_else(|| {
+        inputs
+            .game_id
+            .single()
+            .map(|input| input.value.trim().to_string())
+            .unwrap_or_default()
+    });
+    if game_id.is_empty() {
+        lobby.set_status("Enter a game ID to join", UI_WARNING_COLOR);
+        return;
+    }
+
+    if let Ok(mut input) = inputs.game_id.single_mut() {
+        input.value = game_id.clone();
+    }
+    requests.write(SendClientRequestEvent {
+        request: ClientRequest::JoinGame {
+            game_id: game_id.clone(),
+            password: password_of(&inputs.join_password),
+        },
+    });
+    lobby.set_status(format!("Joining {}...", game_id), UI_TEXT_COLOR);
+}
+
+pub fn handle_lobby_responses(
+    mut responses: EventReader<NetworkResponseEvent>,
+    mut lobby: ResMut<Lobby>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for event in responses.read() {
+        match &event.response {
+            ServerResponse::GameList { games } => lobby.games = games.clone(),
+            ServerResponse::PlayerList { players } => lobby.players = players.clone(),
+            ServerResponse::GameCreated { game_id, .. } => {
+                lobby.set_status(format!("Created game {}", game_id), UI_SUCCESS_COLOR);
+                next_state.set(GameState::InGame);
+            }
+            ServerResponse::GameJoined { game_id, .. } => {
+                lobby.set_status(format!("Joined game {}", game_id), UI_SUCCESS_COLOR);
+                next_state.set(GameState::InGame);
+            }
+            ServerResponse::Error { message, .. } => lobby.set_status(message.clone(), UI_ERROR_COLOR),
+            _ => {}
+        }
+    }
+}
+
+pub fn update_lobby_lists(
+    mut commands: Commands,
+    lobby: Res<Lobby>,
+    game_list: Query<Entity, With<GameListContainer>>,
+    player_list: Query<Entity, With<PlayerListContainer>>,
+) {
+    if !lobby.is_changed() {
+        return;
+    }
+
+    if let Ok(container) = game_list.single() {
+        commands.entity(container).despawn_related::<Children>().with_children(|list| {
+            if lobby.games.is_empty() {
+                spawn_label(list, "No open games. Create one!", UI_FONT_SIZE_MEDIUM, UI_TEXT_DISABLED_COLOR);
+            }
+            for game in &lobby.games {
+                let name = |player: &Option<PlayerInfo>| {
+                    player
+                        .as_ref()
+                        .map_or("(open)".to_string(), |player| format!("{} ({})", player.name, player.rating))
+                };
+                list.spawn(row()).with_children(|entry| {
+                    spawn_label(
+                        entry,
+                        format!(
+                            "{}  {} vs {}  {}  {} moves",
+                            game.id,
+                            name(&game.white_player),
+                            name(&game.black_player),
+                            time_control_label(game.time_control.as_ref()),
+                            game.move_count
+                        ),
+                        UI_FONT_SIZE_MEDIUM,
+                        UI_TEXT_COLOR,
+                    );
+                    if game.status == GameStatus::Waiting {
+                        spawn_button(entry, "Join", LobbyButton::JoinListed(game.id.clone()));
+                    } else {
+                        spawn_label(entry, "in progress", UI_FONT_SIZE_SMALL, UI_TEXT_DISABLED_COLOR);
+                    }
+                });
+            }
+        });
+    }
+
+    if let Ok(container) = player_list.single() {
+        commands.entity(container).despawn_related::<Children>().with_children(|list| {
+            for player in &lobby.players {
+                let (status, color) = match player.status {
+                    PlayerStatus::Online => ("online", UI_SUCCESS_COLOR),
+                    PlayerStatus::Away => ("away", UI_WARNING_COLOR),
+                    PlayerStatus::InGame => ("in game", UI_ACCENT_COLOR),
+                    PlayerStatus::Offline => ("offline", UI_TEXT_DISABLED_COLOR),
+                };
+                list.spawn(row()).with_children(|entry| {
+                    spawn_label(
+                        entry,
+                        format!("{} ({})", player.name, player.rating),
+                        UI_FONT_SIZE_MEDIUM,
+                        UI_TEXT_COLOR,
+                    );
+                    spawn_label(entry, status, UI_FONT_SIZE_SMALL, color);
+                });
+            }
+        });
+    }
+}
+
+type StatusText<'a> = (&'a mut Text, &'a mut TextColor);
+
+pub fn update_lobby_labels(
+    lobby: Res<Lobby>,
+    buttons: Query<(&LobbyButton, &Children)>,
+    mut texts: Query<&mut Text>,
+    mut status_text: Query<StatusText, (With<LobbyStatusText>, Without<LobbyButton>)>,
+) {
+    if !lobby.is_changed() {
+        return;
+    }
+
+    for (button, children) in buttons.iter() {
+        let label = match button {
+            LobbyButton::NextTimeControl => time_control_label(lobby.selected_time_control().as_ref()),
+            LobbyButton::TogglePrivate if lobby.is_private => "Private".to_string(),
+            LobbyButton::TogglePrivate => "Public".to_string(),
+            _ => continue,
+        };
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.0 = label.clone();
+            }
+        }
+    }
+
+    if let Ok((mut text, mut color)) = status_text.single_mut() {
+        let (message, message_color) = lobby.status.clone().unwrap_or_default();
+        text.0 = message;
+        color.0 = message_color;
+    }
+}
@@ -1,10 +1,60 @@
+pub mod widgets;
+pub mod lobby;
+pub mod chat;
+pub mod game_actions;
+pub mod clock;
+
 use bevy::prelude::*;
+use crate::core::{GameState, CoreSet};
+
+pub use widgets::*;
+pub use lobby::*;
+pub use chat::*;
+pub use game_actions::*;
+pub use clock::*;
 
 pub struct UIPlugin;
 
 impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
-        // TODO: UIシステムの実装
-        info!("UI plugin loaded (placeholder)");
+        app
+            .init_resource::<Lobby>()
+            .init_resource::<Chat>()
+            .init_resource::<ConfirmPrompt>()
+            .add_event::<TextSubmitted>()
+            .add_systems(OnEnter(GameState::MainMenu), reset_chat)
+            .add_systems(OnEnter(GameState::Lobby), (setup_lobby, setup_chat_panel))
+            .add_systems(OnExit(GameState::Lobby), (cleanup_lobby, cleanup_chat_panel))
+            .add_systems(OnEnter(GameState::InGame), (setup_chat_panel, setup_game_actions, setup_clock_panel))
+            .add_systems(OnExit(GameState::InGame), (cleanup_chat_panel, cleanup_game_actions, cleanup_clock_panel))
+            .add_systems(Update, (
+                update_button_colors,
+                focus_text_inputs,
+                type_into_text_inputs,
+                render_text_inputs,
+            ).chain().in_set(CoreSet::UI))
+            .add_systems(Update, (
+                submit_join_on_enter,
+                handle_lobby_buttons,
+                refresh_lobby,
+                handle_lobby_responses,
+                update_lobby_lists,
+                update_lobby_labels,
+            ).chain().after(render_text_inputs).in_set(CoreSet::UI).run_if(in_state(GameState::Lobby)))
+            .add_systems(Update, (
+                collect_chat_messages,
+                handle_chat_buttons,
+                send_chat_message,
+                update_chat_panel,
+            ).chain().after(render_text_inputs).in_set(CoreSet::UI))
+            .add_systems(Update, (
+                handle_action_buttons,
+                handle_dialog_buttons,
+                update_confirm_dialog,
+                update_game_action_labels,
+                update_clock_panel,
+            ).chain().after(update_chat_panel).in_set(CoreSet::UI).run_if(in_state(GameState::InGame)));
+
+        info!("UI plugin loaded");
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,220 @@
+//! Small building blocks shared by the screens: buttons that react to the
+//! pointer and single-line text inputs fed from the keyboard.
+
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+
+use crate::core::constants::*;
+
+/// Single-line text field. Click to focus; Enter sends `TextSubmitted`, Escape unfocuses.
+#[derive(Component, Debug, Clone, Default)]
+pub struct TextInput {
+    pub value: String,
+    pub placeholder: String,
+    pub max_length: usize,
+    /// Shows `*` instead of the characters, for passwords.
+    pub masked: bool,
+}
+
+/// The text input keystrokes go to. At most one at a time.
+#[derive(Component)]
+pub struct Focused;
+
+#[derive(Component)]
+pub struct TextInputLabel;
+
+#[derive(Event, Debug, Clone)]
+pub struct TextSubmitted {
+    pub entity: Entity,
+    pub value: String,
+}
+
+pub fn spawn_label(parent: &mut ChildSpawnerCommands, text: impl Into<String>, font_size: f32, color: Color) -> Entity {
+    parent
+        .spawn((
+            Text::new(text),
+            TextFont {
+                font_size,
+                ..default()
+            },
+            TextColor(color),
+        ))
+        .id()
+}
+
+pub fn spawn_button(parent: &mut ChildSpawnerCommands, label: impl Into<String>, action: impl Bundle) -> Entity {
+    parent
+        .spawn((
+            Button,
+            Node {
+                height: Val::Px(UI_BUTTON_HEIGHT),
+                padding: UiRect::horizontal(Val::Px(UI_MARGIN_LARGE)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(UI_BUTTON_COLOR),
+            BorderRadius::all(Val::Px(UI_PANEL_BORDER_RADIUS)),
+            action,
+        ))
+        .with_children(|button| {
+            spawn_label(button, label, UI_FONT_SIZE_MEDIUM, UI_TEXT_COLOR);
+        })
+        .id()
+}
+
+/// Compact button for use inside list rows.
+pub fn spawn_small_button(parent: &mut ChildSpawnerCommands, label: impl Into<String>, action: impl Bundle) -> Entity {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(UI_MARGIN_MEDIUM), Val::Px(UI_MARGIN_SMALL / 2.0)),
+                ..default()
+            },
+            BackgroundColor(UI_BUTTON_COLOR),
+            BorderRadius::all(Val::Px(UI_MARGIN_SMALL)),
+            action,
+        ))
+        .with_children(|button| {
+            spawn_label(button, label, UI_FONT_SIZE_SMALL, UI_TEXT_COLOR);
+        })
+        .id()
+}
+
+pub fn spawn_text_input(parent: &mut ChildSpawnerCommands, input: TextInput, width: Val, marker: impl Bundle) -> Entity {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width,
+                height: Val::Px(UI_BUTTON_HEIGHT),
+                padding: UiRect::horizontal(Val::Px(UI_MARGIN_MEDIUM)),
+                align_items: AlignItems::Center,
+                border: UiRect::all(Val::Px(1.0)),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            BackgroundColor(UI_BACKGROUND_COLOR),
+            BorderColor(UI_TEXT_DISABLED_COLOR),
+            BorderRadius::all(Val::Px(UI_MARGIN_SMALL)),
+            input,
+            marker,
+        ))
+        .with_children(|field| {
+            field.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: UI_FONT_SIZE_MEDIUM,
+                    ..default()
+                },
+                TextColor(UI_TEXT_COLOR),
+                TextInputLabel,
+            ));
+        })
+        .id()
+}
+
+/// Buttons that aren't text fields; those colour themselves by focus.
+type PlainButton = (With<Button>, Without<TextInput>);
+
+pub fn update_button_colors(mut buttons: Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, PlainButton)>) {
+    for (interaction, mut background) in buttons.iter_mut() {
+        background.0 = match interaction {
+            Interaction::Pressed => UI_BUTTON_PRESSED_COLOR,
+            Interaction::Hovered => UI_BUTTON_HOVER_COLOR,
+            Interaction::None => UI_BUTTON_COLOR,
+        };
+    }
+}
+
+type ClickedTextInput = (Changed<Interaction>, With<TextInput>);
+
+pub fn focus_text_inputs(
+    mut commands: Commands,
+    inputs: Query<(Entity, &Interaction), ClickedTextInput>,
+    focused: Query<Entity, With<Focused>>,
+) {
+    for (entity, interaction) in inputs.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        for previous in focused.iter().filter(|previous| *previous != entity) {
+            commands.entity(previous).remove::<Focused>();
+        }
+        commands.entity(entity).insert(Focused);
+    }
+}
+
+pub fn type_into_text_inputs(
+    mut commands: Commands,
+    mut keyboard: EventReader<KeyboardInput>,
+    mut inputs: Query<(Entity, &mut TextInput), With<Focused>>,
+    mut submitted: EventWriter<TextSubmitted>,
+) {
+    let Ok((entity, mut input)) = inputs.single_mut() else {
+        keyboard.clear();
+        return;
+    };
+
+    for event in keyboard.read().filter(|event| event.state == ButtonState::Pressed) {
+        match &event.logical_key {
+            Key::Backspace => {
+                input.value.pop();
+            }
+            Key::Enter => {
+                submitted.write(TextSubmitted {
+                    entity,
+                    value: input.value.clone(),
+                });
+            }
+            Key::Escape => {
+                commands.entity(entity).remove::<Focused>();
+            }
+            _ => {
+                let Some(text) = &event.text else {
+                    continue;
+                };
+                for character in text.chars().filter(|character| !character.is_control()) {
+                    if input.max_length == 0 || input.value.chars().count() < input.max_length {
+                        input.value.push(character);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn render_text_inputs(
+    mut inputs: Query<(Ref<TextInput>, Has<Focused>, &Children, &mut BorderColor)>,
+    mut labels: Query<(&mut Text, &mut TextColor), With<TextInputLabel>>,
+) {
+    for (input, focused, children, mut border) in inputs.iter_mut() {
+        // The border doubles as the last focus state drawn
+        let focus_color = if focused { UI_ACCENT_COLOR } else { UI_TEXT_DISABLED_COLOR };
+        if !input.is_changed() && border.0 == focus_color {
+            continue;
+        }
+        border.0 = focus_color;
+
+        let cursor = if focused { "|" } else { "" };
+        let (content, color) = match (input.value.is_empty(), input.masked) {
+            (true, _) if focused => (cursor.to_string(), UI_TEXT_COLOR),
+            (true, _) => (input.placeholder.clone(), UI_TEXT_DISABLED_COLOR),
+            (false, true) => (format!("{}{}", "*".repeat(input.value.chars().count()), cursor), UI_TEXT_COLOR),
+            (false, false) => (format!("{}{}", input.value, cursor), UI_TEXT_COLOR),
+        };
+
+        for child in children.iter() {
+            if let Ok((mut text, mut text_color)) = labels.get_mut(child) {
+                text.0 = content.clone();
+                text_color.0 = color;
+            }
+        }
+    }
+}
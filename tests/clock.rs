@@ -0,0 +1,109 @@
+//! Chess clock delays and how a flag fall is scored.
+
+use client::{
+    core::events::{ClockDelay, DrawReason, GameEndReason, GameResult, TimeControl},
+    game::{clock::timeout_result, fen::FenPosition, ChessClock},
+    PieceColor,
+};
+
+fn clock(initial_time_seconds: u32, increment_seconds: u32, delay: Option<ClockDelay>) -> ChessClock {
+    ChessClock::new(Some(TimeControl {
+        initial_time_seconds,
+        increment_seconds,
+        name: String::new(),
+        delay,
+    }))
+}
+
+fn flag(flagged: PieceColor, fen: &str) -> GameResult {
+    timeout_result(flagged, &FenPosition::parse(fen).unwrap())
+}
+
+#[test]
+fn simple_delay_runs_before_the_clock() {
+    let mut clock = clock(60, 0, Some(ClockDelay::Simple(3)));
+    clock.advance_to(1);
+    assert_eq!(clock.running, Some(PieceColor::Black));
+
+    clock.tick(2.0);
+    assert_eq!(clock.black_remaining, 60.0);
+    clock.tick(2.0);
+    assert_eq!(clock.black_remaining, 59.0);
+
+    // The delay starts over with every move
+    clock.advance_to(2);
+    clock.tick(2.5);
+    assert_eq!(clock.white_remaining, 60.0);
+    assert_eq!(clock.black_remaining, 59.0);
+}
+
+#[test]
+fn bronstein_delay_gives_back_time_used_up_to_the_delay() {
+    let mut clock = clock(60, 0, Some(ClockDelay::Bronstein(3)));
+    clock.advance_to(1);
+
+    clock.tick(2.0);
+    assert_eq!(clock.black_remaining, 58.0);
+    clock.advance_to(2);
+    assert_eq!(clock.black_remaining, 60.0);
+
+    clock.tick(5.0);
+    assert_eq!(clock.white_remaining, 55.0);
+    clock.advance_to(3);
+    assert_eq!(clock.white_remaining, 58.0);
+}
+
+#[test]
+fn increments_are_added_after_each_move() {
+    let mut clock = clock(60, 2, None);
+    clock.advance_to(1);
+    clock.tick(1.5);
+    clock.advance_to(2);
+    assert_eq!((clock.white_remaining, clock.black_remaining), (62.0, 60.5));
+    assert_eq!(clock.running, Some(PieceColor::White));
+}
+
+#[test]
+fn running_out_flags_the_side_to_move() {
+    let mut clock = clock(10, 0, None);
+    clock.advance_to(1);
+
+    assert_eq!(clock.tick(9.0), None);
+    assert_eq!(clock.tick(2.0), Some(PieceColor::Black));
+    assert_eq!((clock.black_remaining, clock.flagged, clock.running), (0.0, Some(PieceColor::Black), None));
+    assert_eq!(clock.tick(1.0), None);
+}
+
+#[test]
+fn flag_falls_lose_when_the_opponent_can_still_mate() {
+    let white_wins = GameResult::WhiteWins(GameEndReason::Timeout);
+
+    // Queen, rook or pawn
+    assert_eq!(flag(PieceColor::Black, "4k3/8/8/8/8/8/8/3QK3"), white_wins);
+    assert_eq!(flag(PieceColor::Black, "4k3/8/8/8/8/8/4P3/4K3"), white_wins);
+    // Two minor pieces
+    assert_eq!(flag(PieceColor::Black, "4k3/8/8/8/8/8/8/1N2K1N1"), white_wins);
+    // A lone knight mates a king hemmed in by its own pawns
+    assert_eq!(flag(PieceColor::Black, "4k3/3ppp2/8/8/8/8/8/1N2K3"), white_wins);
+    // Bishops on both colours
+    assert_eq!(flag(PieceColor::Black, "2b1k3/8/8/8/8/8/8/2B1K3"), white_wins);
+    assert_eq!(
+        flag(PieceColor::White, "r3k3/8/8/8/8/8/8/4K3"),
+        GameResult::BlackWins(GameEndReason::Timeout)
+    );
+}
+
+#[test]
+fn flag_falls_draw_without_mating_material() {
+    let draw = GameResult::Draw(DrawReason::Timeout);
+
+    // Bare king
+    assert_eq!(flag(PieceColor::Black, "3qk3/8/8/8/8/8/8/4K3"), draw);
+    // A knight against a king, or a king and queen
+    assert_eq!(flag(PieceColor::Black, "4k3/8/8/8/8/8/8/1N2K3"), draw);
+    assert_eq!(flag(PieceColor::Black, "3qk3/8/8/8/8/8/8/1N2K3"), draw);
+    assert_eq!(flag(PieceColor::White, "4k1n1/8/8/8/8/8/8/3QK3"), draw);
+    // Bishops that all run on the same colour
+    assert_eq!(flag(PieceColor::Black, "4k3/8/8/8/8/8/8/2B1K3"), draw);
+    assert_eq!(flag(PieceColor::Black, "3bk3/8/8/8/8/8/8/2B1K3"), draw);
+}
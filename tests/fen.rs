@@ -0,0 +1,122 @@
+//! FEN parsing and `FenPosition::apply_move`, which online games replay server moves with.
+
+use client::{
+    game::{
+        fen::{FenError, FenPosition, STARTING_FEN},
+        state::{CastlingRights, ChessMove},
+    },
+    BoardPosition, PieceColor, PieceType,
+};
+
+fn pos(notation: &str) -> BoardPosition {
+    BoardPosition::from_algebraic(notation).unwrap()
+}
+
+fn play(position: &mut FenPosition, from: &str, to: &str, promotion: Option<PieceType>) -> ChessMove {
+    position
+        .apply_move(pos(from), pos(to), promotion)
+        .unwrap_or_else(|| panic!("{}{} should be playable in {}", from, to, position))
+}
+
+#[test]
+fn parses_and_prints_full_fen() {
+    let position = FenPosition::parse(STARTING_FEN).unwrap();
+    assert_eq!(position, FenPosition::starting());
+    assert_eq!(position.to_string(), STARTING_FEN);
+    assert_eq!(position.piece_at(pos("e1")), Some((PieceType::King, PieceColor::White)));
+    assert_eq!(position.piece_at(pos("d8")), Some((PieceType::Queen, PieceColor::Black)));
+    assert_eq!(position.castling_rights, CastlingRights::all());
+
+    let fen = "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w Kq e6 0 2";
+    let position = FenPosition::parse(fen).unwrap();
+    assert_eq!(position.en_passant_target, Some(pos("e6")));
+    assert!(position.castling_rights.white_kingside && !position.castling_rights.white_queenside);
+    assert_eq!(position.to_string(), fen);
+}
+
+#[test]
+fn missing_fields_take_defaults() {
+    let position = FenPosition::parse("4k3/8/8/8/8/8/8/4K3").unwrap();
+    assert_eq!(position.active_color, PieceColor::White);
+    assert_eq!(position.castling_rights, CastlingRights::none());
+    assert_eq!(position.en_passant_target, None);
+    assert_eq!((position.halfmove_clock, position.fullmove_number), (0, 1));
+}
+
+#[test]
+fn rejects_malformed_fen() {
+    assert_eq!(FenPosition::parse("  "), Err(FenError::Empty));
+    assert!(matches!(FenPosition::parse("8/8/8/8/8/8/8"), Err(FenError::Placement(_))));
+    assert!(matches!(FenPosition::parse("8/8/8/8/8/8/8/9"), Err(FenError::Placement(_))));
+    assert!(matches!(FenPosition::parse("8/8/8/8/8/8/8/4X3"), Err(FenError::Placement(_))));
+    assert!(matches!(FenPosition::parse("8/8/8/8/8/8/8/8 x"), Err(FenError::ActiveColor(_))));
+    assert!(matches!(FenPosition::parse("8/8/8/8/8/8/8/8 w KX"), Err(FenError::Castling(_))));
+    assert!(matches!(FenPosition::parse("8/8/8/8/8/8/8/8 w - z9"), Err(FenError::EnPassant(_))));
+    assert!(matches!(FenPosition::parse("8/8/8/8/8/8/8/8 w - - x"), Err(FenError::Counter(_))));
+}
+
+#[test]
+fn castling_moves_the_rook_and_drops_rights() {
+    let mut position = FenPosition::parse("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+    let kingside = play(&mut position, "e1", "g1", None);
+    assert!(kingside.is_castling);
+    assert_eq!(position.piece_at(pos("f1")), Some((PieceType::Rook, PieceColor::White)));
+    assert_eq!(position.piece_at(pos("h1")), None);
+    assert!(!position.castling_rights.white_kingside && !position.castling_rights.white_queenside);
+    assert!(position.castling_rights.black_kingside && position.castling_rights.block_queenside);
+
+    let queenside = play(&mut position, "e8", "c8", None);
+    assert!(queenside.is_castling);
+    assert_eq!(position.to_string(), "2kr3r/8/8/8/8/8/8/R4RK1 w - - 2 2");
+}
+
+#[test]
+fn capturing_a_rook_on_its_corner_drops_castling_rights() {
+    let mut position = FenPosition::parse("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 5 1").unwrap();
+
+    let capture = play(&mut position, "a1", "a8", None);
+    assert_eq!(capture.captured_piece, Some(PieceType::Rook));
+    assert!(!position.castling_rights.white_queenside && !position.castling_rights.block_queenside);
+    assert!(position.castling_rights.white_kingside && position.castling_rights.black_kingside);
+    assert_eq!(position.halfmove_clock, 0);
+}
+
+#[test]
+fn en_passant_takes_the_pawn_beside_the_target() {
+    let mut position = FenPosition::parse("4k3/8/8/8/4p3/8/3P4/4K3 w - - 0 1").unwrap();
+
+    play(&mut position, "d2", "d4", None);
+    assert_eq!(position.en_passant_target, Some(pos("d3")));
+
+    let capture = play(&mut position, "e4", "d3", None);
+    assert!(capture.is_en_passant);
+    assert_eq!(capture.captured_piece, Some(PieceType::Pawn));
+    assert_eq!(position.piece_at(pos("d4")), None);
+    assert_eq!(position.piece_at(pos("d3")), Some((PieceType::Pawn, PieceColor::Black)));
+    assert_eq!(position.en_passant_target, None);
+}
+
+#[test]
+fn pawns_promote_on_the_last_rank() {
+    let mut position = FenPosition::parse("3r4/4P3/8/8/8/8/8/4K2k w - - 0 1").unwrap();
+    let mut queening = position.clone();
+
+    let underpromotion = play(&mut position, "e7", "d8", Some(PieceType::Knight));
+    assert_eq!(underpromotion.promotion, Some(PieceType::Knight));
+    assert_eq!(underpromotion.captured_piece, Some(PieceType::Rook));
+    assert_eq!(position.piece_at(pos("d8")), Some((PieceType::Knight, PieceColor::White)));
+
+    // Without a choice the pawn becomes a queen
+    let promotion = play(&mut queening, "e7", "e8", None);
+    assert_eq!(promotion.promotion, Some(PieceType::Queen));
+    assert_eq!(queening.piece_at(pos("e8")), Some((PieceType::Queen, PieceColor::White)));
+}
+
+#[test]
+fn only_the_side_to_move_can_play() {
+    let mut position = FenPosition::starting();
+    assert!(position.apply_move(pos("e7"), pos("e5"), None).is_none());
+    assert!(position.apply_move(pos("e4"), pos("e5"), None).is_none());
+    assert_eq!(position, FenPosition::starting());
+}
@@ -0,0 +1,58 @@
+//! Overlay toggle keys, which must leave keystrokes meant for a text field alone.
+
+use bevy::prelude::*;
+use client::{
+    core::{
+        events::{GameAction, GameActionEvent},
+        resources::InputSettings,
+    },
+    input::InputPlugin,
+    ui::Focused,
+};
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<InputSettings>()
+        .add_event::<GameActionEvent>()
+        .add_plugins(InputPlugin);
+    app
+}
+
+/// Taps `key` for one frame and returns the actions it produced.
+fn tap(app: &mut App, key: KeyCode) -> Vec<GameAction> {
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(key);
+    app.update();
+
+    let mut keyboard = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    keyboard.release(key);
+    keyboard.clear();
+    app.world_mut()
+        .resource_mut::<Events<GameActionEvent>>()
+        .drain()
+        .map(|event| event.action)
+        .collect()
+}
+
+#[test]
+fn bound_keys_toggle_the_overlays() {
+    let mut app = app();
+
+    assert!(matches!(tap(&mut app, KeyCode::KeyC).as_slice(), [GameAction::ToggleCoordinates]));
+    assert!(matches!(tap(&mut app, KeyCode::KeyT).as_slice(), [GameAction::ToggleThreatOverlay]));
+    assert!(tap(&mut app, KeyCode::KeyX).is_empty());
+}
+
+#[test]
+fn typing_into_a_focused_field_toggles_nothing() {
+    let mut app = app();
+    let field = app.world_mut().spawn(Focused).id();
+
+    for key in [KeyCode::KeyC, KeyCode::KeyT] {
+        assert!(tap(&mut app, key).is_empty(), "{:?} went to the text field", key);
+    }
+
+    app.world_mut().entity_mut(field).remove::<Focused>();
+    assert!(matches!(tap(&mut app, KeyCode::KeyC).as_slice(), [GameAction::ToggleCoordinates]));
+}
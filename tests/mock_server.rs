@@ -0,0 +1,577 @@
+//! Two headless clients, each a Bevy app with only the network plugin, playing
+//! against the in-process mock server. Clients that enter the game screen get a
+//! board without any rendering, so the reconciliation systems run as well.
+//! Run with `cargo test --features mock_server`.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use bevy::{prelude::*, state::app::StatesPlugin};
+use client::{
+    core::{
+        events::*,
+        resources::{GameSettings, NetworkState, UIState},
+        states::GameState,
+    },
+    game::{
+        fen::FenPosition,
+        pieces::ChessPiece,
+        state::{GameStateResource, MoveHistory},
+        ChessBoard, ChessClock,
+    },
+    graphics::{ChessMaterials, ChessMeshes, Tweens},
+    network::{
+        mock_server::{MockServer, MockServerHandle},
+        NetworkPlugin, OnlineGame,
+    },
+    BoardPosition, PieceColor,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Resource, Default)]
+struct Inbox(VecDeque<ServerResponse>);
+
+fn collect_responses(mut events: EventReader<NetworkResponseEvent>, mut inbox: ResMut<Inbox>) {
+    inbox.0.extend(events.read().map(|event| event.response.clone()));
+}
+
+struct TestClient {
+    app: App,
+}
+
+impl TestClient {
+    fn connect(server: &MockServerHandle, name: &str) -> Self {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .init_state::<GameState>()
+            .insert_resource(GameSettings {
+                server_address: server.address(),
+                player_name: name.to_string(),
+                ..default()
+            })
+            .init_resource::<NetworkState>()
+            .init_resource::<UIState>()
+            .init_resource::<ChessClock>()
+            .init_resource::<Inbox>()
+            .add_event::<MovePieceEvent>()
+            .add_event::<SendNetworkMessageEvent>()
+            .add_event::<SendClientRequestEvent>()
+            .add_event::<NetworkResponseEvent>()
+            .add_event::<NetworkEvent>()
+            .add_event::<GameActionEvent>()
+            .add_plugins(NetworkPlugin)
+            .add_systems(Last, collect_responses);
+        app.world_mut().resource_mut::<NextState<GameState>>().set(GameState::Connecting);
+
+        let mut client = Self { app };
+        client.pump_until(|app| *app.world().resource::<State<GameState>>().get() == GameState::Lobby);
+        client
+    }
+
+    /// Opens the game screen on a headless board.
+    fn enter_game(&mut self) {
+        self.app
+            .init_resource::<ChessBoard>()
+            .init_resource::<ChessMeshes>()
+            .init_resource::<ChessMaterials>()
+            .init_resource::<Tweens>()
+            .init_resource::<MoveHistory>()
+            .init_resource::<GameStateResource>();
+        self.app.world_mut().resource_mut::<GameSettings>().instant_moves = true;
+        self.app.world_mut().resource_mut::<NextState<GameState>>().set(GameState::InGame);
+        self.pump_until(|app| *app.world().resource::<State<GameState>>().get() == GameState::InGame);
+    }
+
+    /// A move made on our own board, as the input layer reports it.
+    fn play(&mut self, from: &str, to: &str) {
+        self.app.world_mut().send_event(MovePieceEvent {
+            from: pos(from),
+            to: pos(to),
+            promotion: None,
+            is_player_move: true,
+        });
+        self.app.update();
+    }
+
+    fn online(&self) -> &OnlineGame {
+        self.app.world().resource::<OnlineGame>()
+    }
+
+    fn history_len(&self) -> usize {
+        self.app.world().resource::<MoveHistory>().moves.len()
+    }
+
+    fn player_id(&self) -> String {
+        self.app.world().resource::<NetworkState>().player_id.clone().expect("connected")
+    }
+
+    fn send(&mut self, request: ClientRequest) {
+        self.app.world_mut().send_event(SendClientRequestEvent { request });
+        self.app.update();
+    }
+
+    fn pump_until(&mut self, mut done: impl FnMut(&mut App) -> bool) {
+        let deadline = Instant::now() + TIMEOUT;
+        while !done(&mut self.app) {
+            assert!(Instant::now() < deadline, "timed out waiting for the server");
+            self.app.update();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    /// Takes the first response `matcher` accepts, leaving the others queued.
+    fn expect<T>(&mut self, mut matcher: impl FnMut(&ServerResponse) -> Option<T>) -> T {
+        let mut found = None;
+        self.pump_until(|app| {
+            let mut inbox = app.world_mut().resource_mut::<Inbox>();
+            if let Some(index) = inbox.0.iter().position(|response| matcher(response).is_some()) {
+                found = inbox.0.remove(index);
+            }
+            found.is_some()
+        });
+        matcher(&found.unwrap()).unwrap()
+    }
+}
+
+fn pos(notation: &str) -> BoardPosition {
+    BoardPosition::from_algebraic(notation).unwrap()
+}
+
+fn make_move(from: &str, to: &str) -> ClientRequest {
+    ClientRequest::MakeMove {
+        from: pos(from),
+        to: pos(to),
+        promotion: None,
+    }
+}
+
+/// Placement of the pieces on the client's board.
+fn board_placement(app: &mut App) -> String {
+    let world = app.world_mut();
+    let mut pieces = world.query::<&ChessPiece>();
+    let world = &*world;
+    let board = world.resource::<ChessBoard>();
+    FenPosition::placement_of(board.get_all_pieces().into_iter().filter_map(|(pos, entity)| {
+        pieces
+            .get(world, entity)
+            .ok()
+            .map(|piece| (pos, piece.piece_type, piece.color))
+    }))
+}
+
+fn move_number(response: &ServerResponse) -> Option<u32> {
+    match response {
+        ServerResponse::MoveUpdate { move_number, .. } => Some(*move_number),
+        _ => None,
+    }
+}
+
+/// Alice creates a game and Bob joins it from the game list.
+fn start_game(server: &MockServerHandle) -> (TestClient, TestClient, String) {
+    start_timed_game(server, None)
+}
+
+fn start_timed_game(server: &MockServerHandle, time_control: Option<TimeControl>) -> (TestClient, TestClient, String) {
+    let mut alice = TestClient::connect(server, "Alice");
+    let mut bob = TestClient::connect(server, "Bob");
+
+    alice.send(ClientRequest::CreateGame {
+        time_control,
+        is_private: false,
+        password: None,
+    });
+    let game_id = alice.expect(|response| match response {
+        ServerResponse::GameCreated { game_id, player_color } => {
+            assert_eq!(*player_color, PieceColor::White);
+            Some(game_id.clone())
+        }
+        _ => None,
+    });
+
+    bob.send(ClientRequest::GetGameList);
+    let listed = bob.expect(|response| match response {
+        ServerResponse::GameList { games } => Some(games.clone()),
+        _ => None,
+    });
+    assert!(listed.iter().any(|game| game.id == game_id && game.status == GameStatus::Waiting));
+
+    bob.send(ClientRequest::JoinGame {
+        game_id: game_id.clone(),
+        password: None,
+    });
+    let (color, opponent) = bob.expect(|response| match response {
+        ServerResponse::GameJoined { player_color, opponent_info, .. } => Some((*player_color, opponent_info.clone())),
+        _ => None,
+    });
+    assert_eq!(color, PieceColor::Black);
+    assert_eq!(opponent.map(|opponent| opponent.name), Some("Alice".to_string()));
+
+    // The creator hears about the opponent too
+    let opponent = alice.expect(|response| match response {
+        ServerResponse::GameJoined { opponent_info, .. } => opponent_info.clone(),
+        _ => None,
+    });
+    assert_eq!(opponent.name, "Bob");
+
+    (alice, bob, game_id)
+}
+
+#[test]
+fn moves_are_relayed_to_both_players() {
+    let server = MockServer::spawn("127.0.0.1:0").unwrap();
+    let (mut alice, mut bob, game_id) = start_game(&server);
+
+    alice.send(make_move("e2", "e4"));
+    assert_eq!(alice.expect(move_number), 1);
+    let (from, to) = bob.expect(|response| match response {
+        ServerResponse::MoveUpdate { from, to, move_number: 1, .. } => Some((*from, *to)),
+        _ => None,
+    });
+    assert_eq!((from, to), (pos("e2"), pos("e4")));
+
+    // Out of turn
+    alice.send(make_move("d2", "d4"));
+    alice.expect(|response| match response {
+        ServerResponse::Error { error_code: Some(409), .. } => Some(()),
+        _ => None,
+    });
+
+    bob.send(make_move("e7", "e5"));
+    assert_eq!(bob.expect(move_number), 2);
+    assert_eq!(alice.expect(move_number), 2);
+
+    bob.send(ClientRequest::RequestGameState);
+    let snapshot = bob.expect(|response| match response {
+        ServerResponse::GameStateUpdate { game_state } => Some(game_state.clone()),
+        _ => None,
+    });
+    assert_eq!(snapshot.move_history, vec!["e2e4", "e7e5"]);
+    assert_eq!(snapshot.current_player, PieceColor::White);
+    assert_eq!(
+        snapshot.board_fen.last().unwrap(),
+        "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2"
+    );
+
+    alice.send(ClientRequest::GetGameList);
+    let games = alice.expect(|response| match response {
+        ServerResponse::GameList { games } => Some(games.clone()),
+        _ => None,
+    });
+    let game = games.iter().find(|game| game.id == game_id).unwrap();
+    assert_eq!((game.status.clone(), game.move_count), (GameStatus::Active, 2));
+}
+
+#[test]
+fn draw_undo_and_resign_flows() {
+    let server = MockServer::spawn("127.0.0.1:0").unwrap();
+    let (mut alice, mut bob, _) = start_game(&server);
+    let alice_id = alice.player_id();
+
+    alice.send(ClientRequest::OfferDraw);
+    let from = bob.expect(|response| match response {
+        ServerResponse::DrawOffered { from_player } => Some(from_player.clone()),
+        _ => None,
+    });
+    assert_eq!(from, alice_id);
+    bob.send(ClientRequest::DeclineDraw);
+    assert!(!alice.expect(|response| match response {
+        ServerResponse::DrawResponse { accepted } => Some(*accepted),
+        _ => None,
+    }));
+
+    alice.send(make_move("g1", "f3"));
+    bob.expect(move_number);
+    alice.send(ClientRequest::RequestUndo);
+    let moves_count = bob.expect(|response| match response {
+        ServerResponse::UndoOffered { moves_count, .. } => Some(*moves_count),
+        _ => None,
+    });
+    assert_eq!(moves_count, 1);
+    bob.send(ClientRequest::AcceptUndo);
+    assert!(alice.expect(|response| match response {
+        ServerResponse::UndoResponse { accepted } => Some(*accepted),
+        _ => None,
+    }));
+    for client in [&mut alice, &mut bob] {
+        let snapshot = client.expect(|response| match response {
+            ServerResponse::GameStateUpdate { game_state } => Some(game_state.clone()),
+            _ => None,
+        });
+        assert_eq!((snapshot.move_count, snapshot.current_player), (0, PieceColor::White));
+    }
+
+    bob.send(ClientRequest::Resign);
+    for client in [&mut alice, &mut bob] {
+        let result = client.expect(|response| match response {
+            ServerResponse::GameOver { result } => Some(result.clone()),
+            _ => None,
+        });
+        assert_eq!(result, GameResult::WhiteWins(GameEndReason::Resignation));
+    }
+}
+
+#[test]
+fn draw_by_agreement_ends_the_game() {
+    let server = MockServer::spawn("127.0.0.1:0").unwrap();
+    let (mut alice, mut bob, _) = start_game(&server);
+
+    bob.send(ClientRequest::OfferDraw);
+    alice.expect(|response| matches!(response, ServerResponse::DrawOffered { .. }).then_some(()));
+    alice.send(ClientRequest::AcceptDraw);
+
+    assert!(bob.expect(|response| match response {
+        ServerResponse::DrawResponse { accepted } => Some(*accepted),
+        _ => None,
+    }));
+    for client in [&mut alice, &mut bob] {
+        let result = client.expect(|response| match response {
+            ServerResponse::GameOver { result } => Some(result.clone()),
+            _ => None,
+        });
+        assert_eq!(result, GameResult::Draw(DrawReason::Agreement));
+    }
+}
+
+#[test]
+fn chat_and_player_list() {
+    let server = MockServer::spawn("127.0.0.1:0").unwrap();
+    let (mut alice, mut bob, _) = start_game(&server);
+    let bob_id = bob.player_id();
+
+    // The server announces the join in the game chat
+    alice.expect(|response| match response {
+        ServerResponse::ChatMessage { message_type: ChatMessageType::System, message, .. } => {
+            assert!(message.contains("Bob"));
+            Some(())
+        }
+        _ => None,
+    });
+
+    bob.send(ClientRequest::SendChatMessage {
+        message: "good luck".to_string(),
+        message_type: ChatMessageType::Game,
+    });
+    for client in [&mut alice, &mut bob] {
+        let (from, message) = client.expect(|response| match response {
+            ServerResponse::ChatMessage { from_player, message, message_type: ChatMessageType::Game, .. } => {
+                Some((from_player.clone(), message.clone()))
+            }
+            _ => None,
+        });
+        assert_eq!((from, message.as_str()), (bob_id.clone(), "good luck"));
+    }
+
+    bob.send(ClientRequest::SendChatMessage {
+        message: "hi".to_string(),
+        message_type: ChatMessageType::System,
+    });
+    bob.expect(|response| match response {
+        ServerResponse::Error { error_code: Some(403), .. } => Some(()),
+        _ => None,
+    });
+
+    alice.send(ClientRequest::GetPlayerList);
+    let players = alice.expect(|response| match response {
+        ServerResponse::PlayerList { players } => Some(players.clone()),
+        _ => None,
+    });
+    let mut names: Vec<_> = players.iter().map(|player| player.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, ["Alice", "Bob"]);
+    assert!(players.iter().all(|player| player.status == PlayerStatus::InGame));
+}
+
+#[test]
+fn clocks_follow_the_server() {
+    let server = MockServer::spawn("127.0.0.1:0").unwrap();
+    let time_control = TimeControl {
+        initial_time_seconds: 60,
+        increment_seconds: 2,
+        name: "1+2".to_string(),
+        delay: None,
+    };
+    let (mut alice, mut bob, _) = start_timed_game(&server, Some(time_control.clone()));
+    for client in [&alice, &bob] {
+        let clock = client.app.world().resource::<ChessClock>();
+        assert_eq!(clock.time_control.as_ref(), Some(&time_control));
+        assert_eq!((clock.white_remaining, clock.black_remaining, clock.running), (60.0, 60.0, None));
+    }
+
+    // White's first move starts Black's clock and earns the increment
+    alice.send(make_move("e2", "e4"));
+    let times = bob.expect(|response| match response {
+        ServerResponse::MoveUpdate { time_remaining, .. } => Some(*time_remaining),
+        _ => None,
+    });
+    assert_eq!(times, Some((62.0, 60.0)));
+    let clock = bob.app.world().resource::<ChessClock>();
+    assert_eq!(clock.running, Some(PieceColor::Black));
+    assert_eq!(clock.white_remaining, 62.0);
+    assert!(clock.black_remaining <= 60.0);
+
+    std::thread::sleep(Duration::from_millis(200));
+    bob.send(make_move("e7", "e5"));
+    let (white, black) = alice
+        .expect(|response| match response {
+            ServerResponse::MoveUpdate { move_number: 2, time_remaining, .. } => *time_remaining,
+            _ => None,
+        });
+    assert_eq!(white, 62.0);
+    assert!(black < 61.8 && black > 60.0, "black spent time before the increment: {}", black);
+    let clock = alice.app.world().resource::<ChessClock>();
+    assert_eq!(clock.running, Some(PieceColor::White));
+    assert!((clock.black_remaining - black).abs() < 0.01);
+
+    alice.send(ClientRequest::RequestGameState);
+    let snapshot = alice.expect(|response| match response {
+        ServerResponse::GameStateUpdate { game_state } => Some(game_state.clone()),
+        _ => None,
+    });
+    assert_eq!(snapshot.black_time_remaining, Some(black));
+    assert!(snapshot.white_time_remaining.unwrap() < 62.0);
+}
+
+#[test]
+fn online_boards_follow_the_server() {
+    let server = MockServer::spawn("127.0.0.1:0").unwrap();
+    let (mut alice, mut bob, _) = start_game(&server);
+    let start = FenPosition::starting().placement();
+    for client in [&mut alice, &mut bob] {
+        client.enter_game();
+        client.pump_until(|app| board_placement(app) == start);
+    }
+
+    // Shown straight away, confirmed by the echo
+    alice.play("e2", "e4");
+    assert_eq!(alice.online().pending_moves.len(), 1);
+    let after_e4 = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR";
+    assert_eq!(board_placement(&mut alice.app), after_e4);
+    for client in [&mut alice, &mut bob] {
+        client.pump_until(|app| app.world().resource::<OnlineGame>().confirmed_moves == 1);
+        assert!(client.online().pending_moves.is_empty());
+        assert_eq!(board_placement(&mut client.app), after_e4);
+        assert_eq!(client.history_len(), 1);
+        assert_eq!(client.app.world().resource::<GameStateResource>().current_player, PieceColor::Black);
+    }
+
+    // Refusing a second draw offer must not take back the move sent with it
+    alice.send(ClientRequest::OfferDraw);
+    bob.expect(|response| matches!(response, ServerResponse::DrawOffered { .. }).then_some(()));
+    bob.app.world_mut().send_event(SendClientRequestEvent {
+        request: ClientRequest::OfferDraw,
+    });
+    bob.play("e7", "e5");
+    bob.expect(|response| match response {
+        ServerResponse::Error { error_code: Some(429), .. } => Some(()),
+        _ => None,
+    });
+    assert_eq!(bob.history_len(), 2, "the move is still on the board");
+    bob.pump_until(|app| app.world().resource::<OnlineGame>().confirmed_moves == 2);
+    for _ in 0..20 {
+        bob.app.update();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    let after_e5 = "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR";
+    assert_eq!(board_placement(&mut bob.app), after_e5);
+    assert_eq!(bob.history_len(), 2);
+    assert!(!bob.online().is_awaiting_snapshot());
+    let inbox = &bob.app.world().resource::<Inbox>().0;
+    assert!(
+        !inbox.iter().any(|response| matches!(response, ServerResponse::GameStateUpdate { .. })),
+        "no snapshot should have been needed"
+    );
+}
+
+#[test]
+fn rejected_moves_are_taken_back() {
+    let server = MockServer::spawn("127.0.0.1:0").unwrap();
+    let mut alice = TestClient::connect(&server, "Alice");
+    alice.send(ClientRequest::CreateGame {
+        time_control: None,
+        is_private: false,
+        password: None,
+    });
+    alice.expect(|response| matches!(response, ServerResponse::GameCreated { .. }).then_some(()));
+    let start = FenPosition::starting().placement();
+    alice.enter_game();
+    alice.pump_until(|app| board_placement(app) == start);
+
+    // Nobody has joined yet, so the server turns the move down
+    alice.play("e2", "e4");
+    assert_eq!(alice.history_len(), 1);
+    alice.expect(|response| match response {
+        ServerResponse::Error { error_code: Some(409), .. } => Some(()),
+        _ => None,
+    });
+    alice.pump_until(|app| board_placement(app) == start);
+
+    assert!(alice.online().pending_moves.is_empty());
+    assert_eq!(alice.history_len(), 0);
+    let game_state = alice.app.world().resource::<GameStateResource>();
+    assert_eq!((game_state.current_player, game_state.move_count), (PieceColor::White, 0));
+}
+
+#[test]
+fn joining_needs_the_game_password() {
+    let server = MockServer::spawn("127.0.0.1:0").unwrap();
+    let mut alice = TestClient::connect(&server, "Alice");
+    let mut bob = TestClient::connect(&server, "Bob");
+
+    alice.send(ClientRequest::CreateGame {
+        time_control: None,
+        is_private: true,
+        password: Some("secret".to_string()),
+    });
+    let game_id = alice.expect(|response| match response {
+        ServerResponse::GameCreated { game_id, .. } => Some(game_id.clone()),
+        _ => None,
+    });
+
+    for password in [None, Some("guess".to_string())] {
+        bob.send(ClientRequest::JoinGame {
+            game_id: game_id.clone(),
+            password,
+        });
+        bob.expect(|response| match response {
+            ServerResponse::Error { error_code: Some(403), .. } => Some(()),
+            _ => None,
+        });
+    }
+
+    bob.send(ClientRequest::JoinGame {
+        game_id: game_id.clone(),
+        password: Some("secret".to_string()),
+    });
+    let color = bob.expect(|response| match response {
+        ServerResponse::GameJoined { player_color, .. } => Some(*player_color),
+        _ => None,
+    });
+    assert_eq!(color, PieceColor::Black);
+}
+
+#[test]
+fn leaving_the_online_flow_stops_the_clock() {
+    let server = MockServer::spawn("127.0.0.1:0").unwrap();
+    let time_control = TimeControl {
+        initial_time_seconds: 60,
+        increment_seconds: 0,
+        name: "1+0".to_string(),
+        delay: None,
+    };
+    let (mut alice, _bob, _) = start_timed_game(&server, Some(time_control));
+    alice.send(make_move("e2", "e4"));
+    alice.expect(move_number);
+    assert!(alice.app.world().resource::<ChessClock>().is_enabled());
+
+    alice.app.world_mut().resource_mut::<NextState<GameState>>().set(GameState::MainMenu);
+    alice.pump_until(|app| *app.world().resource::<State<GameState>>().get() == GameState::MainMenu);
+
+    let clock = alice.app.world().resource::<ChessClock>();
+    assert!(!clock.is_enabled());
+    assert_eq!(clock.running, None);
+    assert!(!alice.online().is_active());
+}
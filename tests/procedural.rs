@@ -0,0 +1,24 @@
+use client::graphics::{generate_texture_maps, ProceduralTexture, TextureMaps};
+
+fn bytes(maps: &TextureMaps) -> [Vec<u8>; 3] {
+    [&maps.albedo, &maps.roughness, &maps.normal].map(|image| image.data.clone().unwrap_or_default())
+}
+
+#[test]
+fn same_seed_generates_the_same_textures() {
+    for kind in ProceduralTexture::ALL {
+        let first = bytes(&generate_texture_maps(kind, 64, 0x5EED));
+        let second = bytes(&generate_texture_maps(kind, 64, 0x5EED));
+        assert!(first.iter().all(|map| map.len() == 64 * 64 * 4), "{} maps are 64x64 RGBA8", kind.name());
+        assert_eq!(first, second, "{} is not deterministic", kind.name());
+    }
+}
+
+#[test]
+fn different_seeds_generate_different_textures() {
+    for kind in ProceduralTexture::ALL {
+        let first = bytes(&generate_texture_maps(kind, 64, 1));
+        let second = bytes(&generate_texture_maps(kind, 64, 2));
+        assert_ne!(first[0], second[0], "{} albedo ignores the seed", kind.name());
+    }
+}
@@ -0,0 +1,280 @@
+use client::{
+    core::events::*,
+    network::{
+        framing,
+        protocol::{self, Frame, ProtocolError, WireMessage},
+    },
+    BoardPosition, PieceColor, PieceType,
+};
+
+fn pos(notation: &str) -> BoardPosition {
+    BoardPosition::from_algebraic(notation).unwrap()
+}
+
+fn player(id: &str) -> PlayerInfo {
+    PlayerInfo {
+        id: id.to_string(),
+        name: format!("Player {}", id),
+        rating: 1500,
+        games_played: 42,
+        win_rate: 0.55,
+        is_online: true,
+        status: PlayerStatus::InGame,
+    }
+}
+
+fn time_control() -> TimeControl {
+    TimeControl {
+        initial_time_seconds: 300,
+        increment_seconds: 3,
+        name: "Blitz 5+3".to_string(),
+        delay: Some(ClockDelay::Bronstein(2)),
+    }
+}
+
+fn snapshot() -> GameStateSnapshot {
+    GameStateSnapshot {
+        board_fen: vec!["rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1".to_string()],
+        move_history: vec!["e2e4".to_string()],
+        white_player: Some(player("w")),
+        black_player: None,
+        current_player: PieceColor::Black,
+        move_count: 1,
+        is_check: false,
+        game_result: Some(GameResult::Draw(DrawReason::Agreement)),
+        time_control: Some(time_control()),
+        white_time_remaining: Some(297.5),
+        black_time_remaining: None,
+        last_move: Some((pos("e2"), pos("e4"))),
+    }
+}
+
+fn round_trip(message: impl Into<WireMessage>) {
+    let message = message.into();
+    let text = protocol::encode(message.clone()).unwrap();
+    let frame = protocol::decode(&text).unwrap();
+
+    assert_eq!(frame.version, client::PROTOCOL_VERSION);
+    assert_eq!(frame.message, message, "round trip changed {}", text);
+}
+
+#[test]
+fn network_messages_round_trip() {
+    let messages = vec![
+        NetworkMessage::connect("Alice".to_string()),
+        NetworkMessage::resume("Alice".to_string(), "s1".to_string()),
+        NetworkMessage::PlayerLeft {
+            player_id: "p2".to_string(),
+            reason: "timeout".to_string(),
+        },
+        NetworkMessage::Pong { timestamp: 1_700_000_000_123 },
+        NetworkMessage::Error {
+            error_code: "E_BAD_MOVE".to_string(),
+            message: "Illegal move".to_string(),
+            details: Some("e2e5".to_string()),
+        },
+        NetworkMessage::Success { message: "ok".to_string() },
+    ];
+
+    for message in messages {
+        round_trip(message);
+    }
+}
+
+#[test]
+fn client_requests_round_trip() {
+    let requests = vec![
+        ClientRequest::CreateGame {
+            time_control: Some(time_control()),
+            is_private: true,
+            password: Some("hunter2".to_string()),
+        },
+        ClientRequest::JoinGame {
+            game_id: "g1".to_string(),
+            password: None,
+        },
+        ClientRequest::MakeMove {
+            from: pos("e7"),
+            to: pos("e8"),
+            promotion: Some(PieceType::Queen),
+        },
+        ClientRequest::OfferDraw,
+        ClientRequest::AcceptDraw,
+        ClientRequest::DeclineDraw,
+        ClientRequest::Resign,
+        ClientRequest::RequestUndo,
+        ClientRequest::AcceptUndo,
+        ClientRequest::DeclineUndo,
+        ClientRequest::RequestGameState,
+        ClientRequest::SendChatMessage {
+            message: "good luck".to_string(),
+            message_type: ChatMessageType::Game,
+        },
+        ClientRequest::Disconnect {
+            reason: Some("quit".to_string()),
+        },
+        ClientRequest::Ping,
+        ClientRequest::GetGameList,
+        ClientRequest::GetPlayerList,
+    ];
+
+    for request in requests {
+        round_trip(request);
+    }
+}
+
+#[test]
+fn server_responses_round_trip() {
+    let responses = vec![
+        ServerResponse::Connected {
+            player_id: "p1".to_string(),
+            session_id: "s1".to_string(),
+            server_info: ServerInfo {
+                server_name: "Main".to_string(),
+                version: "0.3.0".to_string(),
+                max_players: 1000,
+                current_players: 12,
+                features: vec!["chat".to_string(), "undo".to_string()],
+            },
+            protocol_version: "1.0".to_string(),
+        },
+        ServerResponse::Authenticated { player_info: player("p1") },
+        ServerResponse::GameCreated {
+            game_id: "g1".to_string(),
+            player_color: PieceColor::White,
+        },
+        ServerResponse::GameJoined {
+            game_id: "g1".to_string(),
+            player_color: PieceColor::Black,
+            opponent_info: Some(player("p2")),
+            game_state: snapshot(),
+        },
+        ServerResponse::MoveUpdate {
+            from: pos("g1"),
+            to: pos("f3"),
+            promotion: None,
+            move_number: 3,
+            time_remaining: Some((290.25, 288.0)),
+        },
+        ServerResponse::GameStateUpdate { game_state: snapshot() },
+        ServerResponse::GameOver {
+            result: GameResult::WhiteWins(GameEndReason::Checkmate),
+        },
+        ServerResponse::GameOver {
+            result: GameResult::BlackWins(GameEndReason::Timeout),
+        },
+        ServerResponse::DrawOffered { from_player: "p2".to_string() },
+        ServerResponse::DrawResponse { accepted: false },
+        ServerResponse::UndoOffered {
+            from_player: "p2".to_string(),
+            moves_count: 1,
+        },
+        ServerResponse::UndoResponse { accepted: true },
+        ServerResponse::ChatMessage {
+            from_player: "p2".to_string(),
+            message: "gg".to_string(),
+            message_type: ChatMessageType::Private,
+            timestamp: 1_700_000_000,
+        },
+        ServerResponse::GameList {
+            games: vec![GameInfo {
+                id: "g1".to_string(),
+                white_player: Some(player("p1")),
+                black_player: None,
+                status: GameStatus::Waiting,
+                time_control: None,
+                move_count: 0,
+                created_at: 1_700_000_000,
+            }],
+        },
+        ServerResponse::PlayerList {
+            players: vec![player("p1"), player("p2")],
+        },
+        ServerResponse::Error {
+            message: "Not your turn".to_string(),
+            error_code: Some(409),
+        },
+        ServerResponse::Pong,
+    ];
+
+    for response in responses {
+        round_trip(response);
+    }
+}
+
+#[test]
+fn wire_format_matches_server() {
+    let text = protocol::encode(ClientRequest::MakeMove {
+        from: pos("e2"),
+        to: pos("e4"),
+        promotion: None,
+    })
+    .unwrap();
+    let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "version": client::PROTOCOL_VERSION,
+            "kind": "request",
+            "body": {
+                "type": "make_move",
+                "from": { "file": 4, "rank": 1 },
+                "to": { "file": 4, "rank": 3 },
+                "promotion": null,
+            },
+        })
+    );
+
+    let frame = protocol::decode(
+        r#"{"version":"1.0","kind":"response","body":{"type":"game_over","result":{"outcome":"draw","reason":"stalemate"}}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        frame.message,
+        WireMessage::from(ServerResponse::GameOver {
+            result: GameResult::Draw(DrawReason::Stalemate),
+        })
+    );
+}
+
+#[test]
+fn version_negotiation() {
+    let current = protocol::ProtocolVersion::current();
+
+    assert_eq!(protocol::negotiate(client::PROTOCOL_VERSION).unwrap(), current);
+    // A newer minor release of the server falls back to ours
+    let newer_minor = format!("{}.{}", current.major, current.minor + 1);
+    assert_eq!(protocol::negotiate(&newer_minor).unwrap(), current);
+
+    let next_major = format!("{}.0", current.major + 1);
+    assert!(matches!(
+        protocol::negotiate(&next_major),
+        Err(ProtocolError::IncompatibleVersion { .. })
+    ));
+    assert!(matches!(
+        protocol::negotiate("one.zero"),
+        Err(ProtocolError::InvalidVersion(_))
+    ));
+
+    let frame = Frame {
+        version: next_major,
+        message: ServerResponse::Pong.into(),
+    };
+    let text = serde_json::to_string(&frame).unwrap();
+    assert!(protocol::decode(&text).is_err());
+}
+
+#[tokio::test]
+async fn framing_tells_a_clean_close_from_a_truncated_header() {
+    let mut stream = Vec::new();
+    framing::write_frame(&mut stream, b"ping").await.unwrap();
+
+    let mut reader = stream.as_slice();
+    assert_eq!(framing::read_frame(&mut reader).await.unwrap(), Some(b"ping".to_vec()));
+    assert_eq!(framing::read_frame(&mut reader).await.unwrap(), None);
+
+    let mut truncated = &stream[..2];
+    let error = framing::read_frame(&mut truncated).await.unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
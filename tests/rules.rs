@@ -0,0 +1,175 @@
+//! Attack map, hanging pieces and pins on small hand-built positions.
+
+use bevy::{ecs::system::SystemState, prelude::*};
+use client::{
+    game::{
+        pieces::ChessPiece,
+        rules::{attacks_square, hanging_pieces, pinned_pieces, AttackMap, Pin},
+        ChessBoard,
+    },
+    BoardPosition, PieceColor, PieceType,
+};
+
+use PieceColor::{Black, White};
+use PieceType::*;
+
+fn square(notation: &str) -> BoardPosition {
+    BoardPosition::from_algebraic(notation).unwrap()
+}
+
+fn piece(piece_type: PieceType, color: PieceColor, position: BoardPosition) -> ChessPiece {
+    ChessPiece {
+        piece_type,
+        color,
+        position,
+        has_moved: false,
+        move_count: 0,
+        last_moved_turn: None,
+    }
+}
+
+struct Position {
+    world: World,
+    pieces: SystemState<Query<'static, 'static, &'static ChessPiece>>,
+}
+
+impl Position {
+    fn new(setup: &[(&str, PieceType, PieceColor)]) -> Self {
+        let mut world = World::new();
+        let mut board = ChessBoard::default();
+        for (notation, piece_type, color) in setup {
+            let pos = square(notation);
+            let entity = world.spawn(piece(*piece_type, *color, pos)).id();
+            board.set_piece_at(pos, Some(entity));
+        }
+        world.insert_resource(board);
+
+        let pieces = SystemState::new(&mut world);
+        Self { world, pieces }
+    }
+
+    fn check<T>(&mut self, f: impl FnOnce(&ChessBoard, &Query<&ChessPiece>) -> T) -> T {
+        let pieces = self.pieces.get(&self.world);
+        f(self.world.resource::<ChessBoard>(), &pieces)
+    }
+}
+
+#[test]
+fn pawns_attack_diagonally_forward_for_their_side() {
+    let board = ChessBoard::default();
+    let white = piece(Pawn, White, square("e4"));
+    let black = piece(Pawn, Black, square("e5"));
+
+    for target in ["d5", "f5"] {
+        assert!(attacks_square(&white, square("e4"), square(target), &board), "white pawn attacks {}", target);
+    }
+    for target in ["d3", "f3", "e5", "e3"] {
+        assert!(!attacks_square(&white, square("e4"), square(target), &board), "white pawn does not attack {}", target);
+    }
+
+    for target in ["d4", "f4"] {
+        assert!(attacks_square(&black, square("e5"), square(target), &board), "black pawn attacks {}", target);
+    }
+    for target in ["d6", "f6", "e4", "e6"] {
+        assert!(!attacks_square(&black, square("e5"), square(target), &board), "black pawn does not attack {}", target);
+    }
+}
+
+#[test]
+fn attack_map_counts_attackers_and_stops_at_blockers() {
+    let mut position = Position::new(&[
+        ("a1", Rook, White),
+        ("h4", Rook, White),
+        ("b5", Pawn, Black),
+        ("a6", Knight, Black),
+        ("c2", Knight, White),
+    ]);
+    let map = position.check(AttackMap::compute);
+
+    assert_eq!(map.count(square("a4"), White), 2);
+    assert_eq!(map.count(square("a4"), Black), 1);
+    // The rook stops on the knight
+    assert_eq!(map.count(square("a6"), White), 1);
+    assert_eq!(map.count(square("a7"), White), 0);
+    // Squares under friendly pieces count as defended
+    assert_eq!(map.count(square("a1"), White), 1);
+    assert!(!map.is_attacked_by(square("a1"), Black));
+}
+
+#[test]
+fn undefended_attacked_pieces_hang_but_kings_never_do() {
+    let mut position = Position::new(&[
+        ("e1", King, White),
+        ("d4", Knight, White),
+        ("b2", Pawn, White),
+        ("h8", King, Black),
+        ("d8", Rook, Black),
+        ("e7", Rook, Black),
+    ]);
+    let hanging = position.check(|board, pieces| hanging_pieces(board, pieces, &AttackMap::compute(board, pieces)));
+    // The white king is attacked by the e7 rook and defended by nobody, yet not hanging
+    assert_eq!(hanging, vec![square("d4")]);
+
+    let mut position = Position::new(&[
+        ("e1", King, White),
+        ("d4", Knight, White),
+        ("c3", Pawn, White),
+        ("h8", King, Black),
+        ("d8", Rook, Black),
+    ]);
+    let hanging = position.check(|board, pieces| hanging_pieces(board, pieces, &AttackMap::compute(board, pieces)));
+    assert!(hanging.is_empty(), "the c3 pawn defends d4: {:?}", hanging);
+}
+
+#[test]
+fn pins_need_exactly_one_piece_between_slider_and_king() {
+    let mut position = Position::new(&[
+        ("e1", King, White),
+        ("e2", Knight, White),
+        ("e8", Rook, Black),
+        ("h8", King, Black),
+    ]);
+    let pins = position.check(|board, pieces| pinned_pieces(White, board, pieces));
+    assert_eq!(pins, vec![Pin { pinned: square("e2"), pinner: square("e8"), king: square("e1") }]);
+
+    // Two pieces in the line: neither is pinned
+    let mut position = Position::new(&[
+        ("e1", King, White),
+        ("e2", Knight, White),
+        ("e4", Pawn, White),
+        ("e8", Rook, Black),
+        ("h8", King, Black),
+    ]);
+    assert!(position.check(|board, pieces| pinned_pieces(White, board, pieces)).is_empty());
+
+    // An enemy piece in between blocks the line too
+    let mut position = Position::new(&[
+        ("e1", King, White),
+        ("e2", Knight, White),
+        ("e4", Pawn, Black),
+        ("e8", Rook, Black),
+        ("h8", King, Black),
+    ]);
+    assert!(position.check(|board, pieces| pinned_pieces(White, board, pieces)).is_empty());
+}
+
+#[test]
+fn pins_follow_how_the_pinner_moves() {
+    let mut position = Position::new(&[
+        ("e1", King, White),
+        ("d2", Bishop, White),
+        ("a5", Bishop, Black),
+        ("h8", King, Black),
+    ]);
+    let pins = position.check(|board, pieces| pinned_pieces(White, board, pieces));
+    assert_eq!(pins, vec![Pin { pinned: square("d2"), pinner: square("a5"), king: square("e1") }]);
+
+    // A rook does not pin along a diagonal
+    let mut position = Position::new(&[
+        ("e1", King, White),
+        ("d2", Bishop, White),
+        ("a5", Rook, Black),
+        ("h8", King, Black),
+    ]);
+    assert!(position.check(|board, pieces| pinned_pieces(White, board, pieces)).is_empty());
+}
@@ -0,0 +1,114 @@
+//! Tween timelines: sequences run their steps in order, delays hold the next
+//! step back, and each timeline reports once when it is done.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use client::{
+    core::{events::EaseType, resources::GameSettings},
+    graphics::{
+        update_tweens, ChessMeshes, ParticleBurstEvent, Tween, TweenAction, TweenCompleted, TweenNode, Tweening,
+        Tweens,
+    },
+};
+
+const STEP: f32 = 0.25;
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(STEP)))
+        .init_resource::<GameSettings>()
+        .init_resource::<ChessMeshes>()
+        .init_resource::<Tweens>()
+        .add_event::<TweenCompleted>()
+        .add_event::<ParticleBurstEvent>()
+        .add_systems(Update, update_tweens);
+    // The first frame has no delta yet
+    app.update();
+    app
+}
+
+fn slide(entity: Entity, x: f32, duration: f32) -> Tween {
+    Tween::new(entity, TweenAction::Translation { from: None, to: Vec3::new(x, 0.0, 0.0) }, duration)
+        .with_ease(EaseType::Linear)
+}
+
+fn grow(entity: Entity, scale: f32, duration: f32) -> Tween {
+    Tween::new(entity, TweenAction::Scale { from: None, to: Vec3::splat(scale) }, duration).with_ease(EaseType::Linear)
+}
+
+fn transform(app: &App, entity: Entity) -> Transform {
+    *app.world().get::<Transform>(entity).unwrap()
+}
+
+fn completed(app: &mut App) -> Vec<TweenCompleted> {
+    app.world_mut().resource_mut::<Events<TweenCompleted>>().drain().collect()
+}
+
+#[test]
+fn durations_add_up_in_sequences_and_overlap_in_parallel() {
+    let entity = Entity::PLACEHOLDER;
+    let sequence = TweenNode::sequence([Tween::delay(0.5), slide(entity, 1.0, 1.0)]);
+    assert_eq!(sequence.duration(), 1.5);
+
+    let parallel = TweenNode::parallel([sequence, grow(entity, 2.0, 0.25).into()]);
+    assert_eq!(parallel.duration(), 1.5);
+}
+
+#[test]
+fn sequence_steps_wait_for_the_delay_and_each_other() {
+    let mut app = app();
+    let entity = app.world_mut().spawn(Transform::default()).id();
+    let id = app.world_mut().resource_mut::<Tweens>().start(TweenNode::sequence([
+        Tween::delay(0.5),
+        slide(entity, 1.0, 0.5),
+        grow(entity, 2.0, 0.5),
+    ]));
+
+    // Still in the delay
+    app.update();
+    assert_eq!(transform(&app, entity), Transform::default());
+    assert!(app.world().get::<Tweening>(entity).is_some());
+
+    app.update();
+    app.update();
+    let halfway = transform(&app, entity);
+    assert!((halfway.translation.x - 0.5).abs() < 1e-4, "{:?}", halfway.translation);
+    assert_eq!(halfway.scale, Vec3::ONE, "the scale waits for the slide to finish");
+
+    app.update();
+    app.update();
+    let sliding_done = transform(&app, entity);
+    assert!((sliding_done.translation.x - 1.0).abs() < 1e-4);
+    assert!((sliding_done.scale.x - 1.5).abs() < 1e-4, "{:?}", sliding_done.scale);
+    assert!(completed(&mut app).is_empty());
+    assert!(app.world().resource::<Tweens>().is_animating(entity));
+
+    app.update();
+    assert_eq!(transform(&app, entity).scale, Vec3::splat(2.0));
+    let events = completed(&mut app);
+    assert!(matches!(events.as_slice(), [TweenCompleted { id: done, cancelled: false }] if *done == id));
+    assert!(!app.world().resource::<Tweens>().is_running(id));
+    assert!(!app.world().resource::<Tweens>().is_animating(entity));
+
+    app.update();
+    assert!(app.world().get::<Tweening>(entity).is_none());
+    assert!(completed(&mut app).is_empty(), "a timeline completes once");
+}
+
+#[test]
+fn cancelling_keeps_values_and_reports_the_cancel() {
+    let mut app = app();
+    let entity = app.world_mut().spawn(Transform::default()).id();
+    let id = app.world_mut().resource_mut::<Tweens>().start(slide(entity, 1.0, 1.0));
+
+    app.update();
+    app.world_mut().resource_mut::<Tweens>().cancel_entity(entity);
+    app.update();
+
+    assert!((transform(&app, entity).translation.x - STEP).abs() < 1e-4);
+    let events = completed(&mut app);
+    assert!(matches!(events.as_slice(), [TweenCompleted { id: done, cancelled: true }] if *done == id));
+    assert!(!app.world().resource::<Tweens>().is_animating(entity));
+}